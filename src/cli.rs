@@ -1,4 +1,6 @@
-use clap::{Parser, Subcommand};
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand, ValueEnum};
 
 #[derive(Parser)]
 #[command(name = "speq")]
@@ -30,9 +32,29 @@ pub enum Commands {
     },
 
     /// Record approved plan deltas to permanent specs
+    #[command(args_conflicts_with_subcommands = true)]
     Record {
         /// Name of the plan to record
-        plan_name: String,
+        plan_name: Option<String>,
+
+        /// Record only this feature's delta (repeatable); the rest stays in the plan
+        #[arg(long = "feature", value_name = "DOMAIN/FEATURE")]
+        features: Vec<String>,
+
+        /// Date for the archive name instead of today (defaults to SOURCE_DATE_EPOCH if set)
+        #[arg(long, value_name = "YYYY-MM-DD")]
+        date: Option<chrono::NaiveDate>,
+
+        /// Promote decisions marked `Promotes to ADR: yes` into specs/decision-log.md
+        #[arg(long)]
+        promote_adrs: bool,
+
+        /// Record even if the plan is not verified or has no passing verification report
+        #[arg(long)]
+        force: bool,
+
+        #[command(subcommand)]
+        command: Option<RecordCommands>,
     },
 
     /// Semantic search for specifications
@@ -41,17 +63,120 @@ pub enum Commands {
         command: SearchCommands,
     },
 
-    /// Validate the permanent decision log
+    /// Inspect and manage the permanent decision log
     DecisionLog {
         #[command(subcommand)]
         command: DecisionLogCommands,
     },
 }
 
+#[derive(Subcommand)]
+pub enum RecordCommands {
+    /// Undo a recorded plan and move it back to specs/_plans/
+    Revert {
+        /// Name of the archived plan in specs/_recorded/ (e.g. 2024-01-31-add-auth)
+        archive_name: String,
+    },
+}
+
 #[derive(Subcommand)]
 pub enum DecisionLogCommands {
     /// Validate the permanent decision log at specs/decision-log.md
-    Validate,
+    Validate {
+        /// Also match each ADR's plan against specs/_recorded/ and specs/_plans/
+        #[arg(long)]
+        cross_check: bool,
+    },
+
+    /// List permanent ADRs
+    List {
+        /// Only ADRs whose status starts with this (e.g. Accepted, Superseded)
+        #[arg(long)]
+        status: Option<String>,
+    },
+
+    /// Show one ADR (e.g. ADR-007 or 7)
+    Show {
+        /// ADR identifier
+        id: String,
+    },
+
+    /// Semantic search across ADRs (use: speq decision-log search "your query")
+    Search {
+        /// The search query
+        query: String,
+
+        /// Maximum number of results
+        #[arg(long, default_value = "5")]
+        limit: usize,
+    },
+
+    /// Promote a plan's `Promotes to ADR: yes` decisions into specs/decision-log.md
+    Promote {
+        /// Name of the plan whose decision-log.md to promote
+        plan_name: String,
+
+        /// ADR date instead of today (defaults to SOURCE_DATE_EPOCH if set)
+        #[arg(long, value_name = "YYYY-MM-DD")]
+        date: Option<chrono::NaiveDate>,
+    },
+
+    /// Renumber ADRs sequentially in file order, rewriting every ADR-NNN reference
+    Renumber {
+        /// Show the changes without writing specs/decision-log.md
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Write each ADR to its own NNNN-title.md file in a directory
+    Export {
+        /// Directory to write the ADR files to (e.g. docs/adr)
+        dir: PathBuf,
+
+        /// Layout of the ADR files
+        #[arg(long, value_enum, default_value = "madr")]
+        format: AdrFormat,
+    },
+
+    /// Build specs/decision-log.md from a directory of NNNN-title.md ADR files
+    Import {
+        /// Directory holding the ADR files (e.g. docs/adr)
+        dir: PathBuf,
+
+        /// Layout of the ADR files
+        #[arg(long, value_enum, default_value = "madr")]
+        format: AdrFormat,
+
+        /// Plan recorded for ADRs without a `Plan:` line
+        #[arg(long, default_value = "imported")]
+        plan: String,
+
+        /// Replace an existing specs/decision-log.md
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+/// Output of commands agents read as well as people
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum OutputFormat {
+    Text,
+    Json,
+}
+
+/// Statuses `speq plan status` sets; `speq plan approve` sets approved
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum PlanStatusTarget {
+    Implemented,
+    /// Needs a passing verification-report.md
+    Verified,
+}
+
+/// One-file-per-decision ADR layouts
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum AdrFormat {
+    /// adr-tools / MADR `NNNN-title.md` files
+    Madr,
 }
 
 #[derive(Subcommand)]
@@ -80,6 +205,83 @@ pub enum PlanCommands {
 
     /// List all active plans
     List,
+
+    /// Mark a draft plan that passes validation as approved
+    Approve {
+        /// Name of the plan to approve
+        plan_name: String,
+
+        /// Who approves the plan
+        #[arg(long, value_name = "NAME")]
+        by: String,
+    },
+
+    /// Move an approved plan to implemented, or an implemented plan to verified
+    Status {
+        /// Name of the plan
+        plan_name: String,
+
+        /// Status to move the plan to
+        #[arg(value_enum)]
+        status: PlanStatusTarget,
+    },
+
+    /// Report active plans whose deltas touch the same scenarios
+    Conflicts,
+
+    /// Snapshot current permanent specs as the merge base for a plan's deltas
+    Snapshot {
+        /// Name of the plan to snapshot
+        plan_name: String,
+
+        /// Overwrite existing base snapshots
+        #[arg(long)]
+        refresh: bool,
+    },
+
+    /// Summarize a plan's deltas per feature, ADR promotions and task count
+    Show {
+        /// Name of the plan
+        plan_name: String,
+
+        /// Print text or JSON
+        #[arg(long, value_enum, default_value = "text")]
+        format: OutputFormat,
+    },
+
+    /// Print a feature's spec as it will read after the plan is recorded
+    Preview {
+        /// Name of the plan
+        plan_name: String,
+
+        /// Feature to preview (e.g. cli/validate)
+        #[arg(value_name = "DOMAIN/FEATURE")]
+        feature: String,
+
+        /// Mark new, changed and removed sections with PREVIEW comments
+        #[arg(long)]
+        annotate: bool,
+    },
+
+    /// List the tasks of a plan's plan.md with their state and routing tag
+    Tasks {
+        /// Name of the plan
+        plan_name: String,
+
+        /// Only list tasks that are not done
+        #[arg(long)]
+        pending: bool,
+    },
+
+    /// Scaffold a plan named <verb>-<feature-scope>[-<qualifier>]
+    New {
+        /// Name of the plan, e.g. add-user-auth
+        plan_name: String,
+
+        /// Feature the plan touches (repeatable); gets a delta spec.md
+        #[arg(long = "feature", value_name = "DOMAIN/FEATURE")]
+        features: Vec<String>,
+    },
 }
 
 #[derive(Subcommand)]
@@ -0,0 +1,681 @@
+/// The plan's decision log, `specs/_plans/<plan>/decision-log.md`, and the permanent
+/// log, `specs/decision-log.md`, share a file name.
+pub const DECISION_LOG_FILE: &str = "decision-log.md";
+
+pub const PERMANENT_LOG_H1: &str = "# Architecture Decision Records";
+
+/// An `## ADR-NNN: <title>` entry of the permanent decision log.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Adr {
+    pub number: u32,
+    pub title: String,
+    pub date: Option<String>,
+    pub plan: Option<String>,
+    pub status: Option<String>,
+    /// `### <heading>` sections in order
+    pub sections: Vec<AdrSection>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AdrSection {
+    pub heading: String,
+    /// Section text with surrounding blank lines trimmed
+    pub body: String,
+}
+
+impl Adr {
+    /// `ADR-007`
+    pub fn id(&self) -> String {
+        format!("ADR-{:03}", self.number)
+    }
+
+    pub fn section(&self, heading: &str) -> Option<&str> {
+        self.sections
+            .iter()
+            .find(|section| section.heading == heading)
+            .map(|section| section.body.as_str())
+    }
+
+    /// The ADR number in a `Superseded by ADR-NNN` status
+    pub fn superseded_by(&self) -> Option<u32> {
+        self.status
+            .as_deref()?
+            .trim()
+            .strip_prefix("Superseded by ADR-")?
+            .parse()
+            .ok()
+    }
+
+    /// Whether the title or any section refers to `id` (e.g. `ADR-003`, but not `ADR-0031`)
+    pub fn mentions(&self, id: &str) -> bool {
+        mentions_id(&self.title, id) || self.sections.iter().any(|s| mentions_id(&s.body, id))
+    }
+
+    pub fn render(&self) -> String {
+        let mut adr = format!("## {}: {}\n", self.id(), self.title);
+        let fields = [
+            ("Date", &self.date),
+            ("Plan", &self.plan),
+            ("Status", &self.status),
+        ];
+        if fields.iter().any(|(_, value)| value.is_some()) {
+            adr.push('\n');
+        }
+        for (name, value) in fields {
+            if let Some(value) = value {
+                adr.push_str(&format!("**{}:** {}\n", name, value));
+            }
+        }
+        for section in &self.sections {
+            adr.push_str(&format!("\n### {}\n\n{}\n", section.heading, section.body));
+        }
+        adr
+    }
+}
+
+/// The permanent decision log, `specs/decision-log.md`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PermanentLog {
+    pub adrs: Vec<Adr>,
+}
+
+impl PermanentLog {
+    pub fn parse(content: &str) -> Self {
+        let mut adrs: Vec<Adr> = Vec::new();
+        let mut body: Option<Vec<&str>> = None;
+
+        for line in content.lines() {
+            let trimmed = line.trim();
+            if let Some(number) = parse_adr_heading(line) {
+                close_section(adrs.last_mut(), body.take());
+                let title = trimmed
+                    .split_once(':')
+                    .map_or("", |(_, title)| title.trim());
+                adrs.push(Adr {
+                    number,
+                    title: title.to_string(),
+                    ..Default::default()
+                });
+                continue;
+            }
+            let Some(adr) = adrs.last_mut() else { continue };
+
+            if let Some(heading) = trimmed.strip_prefix("### ") {
+                close_section(Some(&mut *adr), body.take());
+                adr.sections.push(AdrSection {
+                    heading: heading.trim().to_string(),
+                    body: String::new(),
+                });
+                body = Some(Vec::new());
+            } else if let Some(lines) = body.as_mut() {
+                lines.push(line.trim_end());
+                // Fields count anywhere in the ADR, but the header's take precedence
+                set_adr_field(adr, trimmed, false);
+            } else {
+                set_adr_field(adr, trimmed, true);
+            }
+        }
+        close_section(adrs.last_mut(), body.take());
+
+        PermanentLog { adrs }
+    }
+
+    /// Look up `ADR-007`, `007` or `7`.
+    pub fn find(&self, id: &str) -> Option<&Adr> {
+        let digits = id.trim().trim_start_matches("ADR-");
+        self.get(digits.parse::<u32>().ok()?)
+    }
+
+    pub fn get(&self, number: u32) -> Option<&Adr> {
+        self.adrs.iter().find(|adr| adr.number == number)
+    }
+
+    /// `adr` followed by each ADR that supersedes the previous one, ending at the
+    /// current decision or where a link is dangling or repeats.
+    pub fn supersession_chain<'a>(&'a self, adr: &'a Adr) -> Vec<&'a Adr> {
+        let mut chain = vec![adr];
+        while let Some(next) = chain
+            .last()
+            .and_then(|adr| adr.superseded_by())
+            .and_then(|number| self.get(number))
+        {
+            if chain.iter().any(|seen| seen.number == next.number) {
+                break;
+            }
+            chain.push(next);
+        }
+        chain
+    }
+
+    /// Every cycle of `Superseded by` links longer than one ADR, each listed once
+    /// starting from its lowest number.
+    pub fn supersession_cycles(&self) -> Vec<Vec<u32>> {
+        let mut cycles: Vec<Vec<u32>> = Vec::new();
+        for adr in &self.adrs {
+            let chain: Vec<u32> = self
+                .supersession_chain(adr)
+                .iter()
+                .map(|adr| adr.number)
+                .collect();
+            let Some(last) = chain.last().and_then(|n| self.get(*n)) else {
+                continue;
+            };
+            let Some(start) = last
+                .superseded_by()
+                .and_then(|next| chain.iter().position(|n| *n == next))
+            else {
+                continue;
+            };
+
+            let mut cycle = chain[start..].to_vec();
+            if cycle.len() < 2 {
+                continue;
+            }
+            let lowest = (0..cycle.len()).min_by_key(|i| cycle[*i]).unwrap_or(0);
+            cycle.rotate_left(lowest);
+            if !cycles.contains(&cycle) {
+                cycles.push(cycle);
+            }
+        }
+        cycles
+    }
+
+    pub fn last_number(&self) -> u32 {
+        self.adrs.iter().map(|adr| adr.number).max().unwrap_or(0)
+    }
+}
+
+fn close_section(adr: Option<&mut Adr>, body: Option<Vec<&str>>) {
+    if let (Some(adr), Some(lines)) = (adr, body)
+        && let Some(section) = adr.sections.last_mut()
+    {
+        section.body = lines.join("\n").trim().to_string();
+    }
+}
+
+fn set_adr_field(adr: &mut Adr, line: &str, overwrite: bool) {
+    let fields = [
+        ("Date", &mut adr.date),
+        ("Plan", &mut adr.plan),
+        ("Status", &mut adr.status),
+    ];
+    for (name, slot) in fields {
+        let prefix = format!("**{name}:**");
+        if let Some(index) = line.find(&prefix)
+            && (overwrite || slot.is_none())
+        {
+            *slot = Some(line[index + prefix.len()..].trim().to_string());
+        }
+    }
+}
+
+fn mentions_id(text: &str, id: &str) -> bool {
+    text.match_indices(id)
+        .any(|(index, _)| !text[index + id.len()..].starts_with(|c: char| c.is_ascii_digit()))
+}
+
+/// `## ADR-007: Title` -> 7
+pub fn parse_adr_heading(line: &str) -> Option<u32> {
+    let trimmed = line.trim_start();
+    let rest = trimmed.strip_prefix("## ADR-")?;
+    let colon_idx = rest.find(':')?;
+    let digits = &rest[..colon_idx];
+    if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    digits.parse::<u32>().ok()
+}
+
+/// A plan's `decision-log.md`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PlanLog {
+    /// From the `# Decision Log: <plan>` heading
+    pub plan_name: Option<String>,
+    pub date: Option<String>,
+    pub decisions: Vec<PlanDecision>,
+}
+
+impl PlanLog {
+    pub fn parse(content: &str) -> Self {
+        let line_value = |prefix: &str| {
+            content
+                .lines()
+                .find_map(|line| line.trim().strip_prefix(prefix))
+                .map(|value| value.trim().to_string())
+        };
+
+        PlanLog {
+            plan_name: line_value("# Decision Log:"),
+            date: line_value("Date:"),
+            decisions: parse_plan_decisions(content),
+        }
+    }
+}
+
+/// A `### [N] <title>` entry under `## Design Decisions` in a plan's decision log.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PlanDecision {
+    pub title: String,
+    pub decision: Option<String>,
+    pub alternatives: Option<String>,
+    pub rationale: Option<String>,
+    pub promotes: bool,
+}
+
+/// Parse the Design Decisions of a plan log.
+///
+/// Field values may wrap onto indented continuation lines, which are joined with spaces.
+pub fn parse_plan_decisions(content: &str) -> Vec<PlanDecision> {
+    let mut decisions: Vec<PlanDecision> = Vec::new();
+    let mut in_section = false;
+    let mut field: Option<&'static str> = None;
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if let Some(heading) = trimmed.strip_prefix("## ") {
+            in_section = heading.trim() == "Design Decisions";
+            field = None;
+            continue;
+        }
+        if !in_section {
+            continue;
+        }
+
+        if let Some(title) = trimmed.strip_prefix("### ") {
+            decisions.push(PlanDecision {
+                title: strip_decision_number(title).to_string(),
+                ..Default::default()
+            });
+            field = None;
+        } else if let Some(decision) = decisions.last_mut() {
+            if let Some((name, value)) = parse_field(trimmed) {
+                if name == "Promotes to ADR" {
+                    decision.promotes = value.eq_ignore_ascii_case("yes");
+                }
+                field = field_slot(decision, name).map(|(field, slot)| {
+                    *slot = Some(value.to_string());
+                    field
+                });
+            } else if trimmed.is_empty() || trimmed.starts_with("- ") || trimmed.starts_with("* ") {
+                field = None;
+            } else if let Some(name) = field
+                && let Some((_, Some(value))) = field_slot(decision, name)
+            {
+                value.push(' ');
+                value.push_str(trimmed);
+            }
+        }
+    }
+
+    decisions
+}
+
+/// `[3] Use line scanning` -> `Use line scanning`
+pub fn strip_decision_number(title: &str) -> &str {
+    let title = title.trim();
+    title
+        .strip_prefix('[')
+        .and_then(|rest| rest.split_once(']'))
+        .filter(|(number, _)| number.chars().all(|c| c.is_ascii_digit()))
+        .map(|(_, rest)| rest.trim())
+        .unwrap_or(title)
+}
+
+/// `- **Decision:** text` or `* **Decision:** text` -> (`Decision`, `text`)
+pub fn parse_field(line: &str) -> Option<(&str, &str)> {
+    let rest = line
+        .strip_prefix("- **")
+        .or_else(|| line.strip_prefix("* **"))?;
+    let (name, value) = rest.split_once(":**")?;
+    Some((name.trim(), value.trim()))
+}
+
+/// The `PlanDecision` field a `- **Name:**` bullet fills, with its canonical name.
+fn field_slot<'a>(
+    decision: &'a mut PlanDecision,
+    name: &str,
+) -> Option<(&'static str, &'a mut Option<String>)> {
+    match name {
+        "Decision" => Some(("Decision", &mut decision.decision)),
+        "Alternatives" => Some(("Alternatives", &mut decision.alternatives)),
+        "Rationale" => Some(("Rationale", &mut decision.rationale)),
+        _ => None,
+    }
+}
+
+/// Heading of an ADR inserted by hand, numbered by `renumber_adrs`
+pub const NEW_ADR_HEADING: &str = "## ADR-NNN:";
+
+/// A line `renumber_adrs` rewrote; `line` is 1-based.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LineChange {
+    pub line: usize,
+    pub before: String,
+    pub after: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Renumbering {
+    pub content: String,
+    pub changes: Vec<LineChange>,
+    /// Numbers carried by more than one ADR; references to them now point at the first
+    pub ambiguous: Vec<u32>,
+}
+
+/// Number the ADRs of a permanent log 1, 2, 3, … in file order and rewrite every
+/// `ADR-NNN` reference to match. Headings written as `## ADR-NNN:` are new ADRs and only
+/// get a number. A reference inside an ADR to its own old number stays with that ADR;
+/// references to numbers no ADR carried are left alone.
+pub fn renumber_adrs(content: &str) -> Renumbering {
+    let lines: Vec<&str> = content.split_inclusive('\n').collect();
+    let mut numbers: Vec<Option<u32>> = Vec::new();
+    let mut owners: Vec<Option<usize>> = Vec::with_capacity(lines.len());
+    for line in &lines {
+        if let Some(number) = parse_adr_heading(line) {
+            numbers.push(Some(number));
+        } else if line.trim_start().starts_with(NEW_ADR_HEADING) {
+            numbers.push(None);
+        }
+        owners.push(numbers.len().checked_sub(1));
+    }
+
+    let new_number = |index: usize| index as u32 + 1;
+    let mut ambiguous: Vec<u32> = numbers
+        .iter()
+        .flatten()
+        .filter(|number| numbers.iter().filter(|n| n == &&Some(**number)).count() > 1)
+        .copied()
+        .collect();
+    ambiguous.sort_unstable();
+    ambiguous.dedup();
+
+    let mut renumbered = String::with_capacity(content.len());
+    let mut changes = Vec::new();
+    for (index, (line, owner)) in lines.iter().zip(&owners).enumerate() {
+        let target = |old: u32| match owner {
+            Some(owner) if numbers[*owner] == Some(old) => Some(new_number(*owner)),
+            _ => numbers
+                .iter()
+                .position(|number| *number == Some(old))
+                .map(new_number),
+        };
+        let is_heading =
+            parse_adr_heading(line).is_some() || line.trim_start().starts_with(NEW_ADR_HEADING);
+        let rewritten = match (owner, line.split_once(':')) {
+            (Some(owner), Some((_, title))) if is_heading => format!(
+                "## ADR-{:03}:{}",
+                new_number(*owner),
+                rewrite_adr_references(title, target)
+            ),
+            _ => rewrite_adr_references(line, target),
+        };
+
+        if rewritten != *line {
+            changes.push(LineChange {
+                line: index + 1,
+                before: line.trim_end().to_string(),
+                after: rewritten.trim_end().to_string(),
+            });
+        }
+        renumbered.push_str(&rewritten);
+    }
+
+    Renumbering {
+        content: renumbered,
+        changes,
+        ambiguous,
+    }
+}
+
+/// Replace each `ADR-<digits>` in `text` whose number `target` maps.
+fn rewrite_adr_references(text: &str, target: impl Fn(u32) -> Option<u32>) -> String {
+    let mut rewritten = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("ADR-") {
+        let (before, reference) = rest.split_at(start + "ADR-".len());
+        rewritten.push_str(before);
+        let digits_len = reference
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(reference.len());
+        let (digits, after) = reference.split_at(digits_len);
+        match digits.parse().ok().and_then(&target) {
+            Some(number) => rewritten.push_str(&format!("{number:03}")),
+            None => rewritten.push_str(digits),
+        }
+        rest = after;
+    }
+    rewritten.push_str(rest);
+    rewritten
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PLAN_LOG: &str = r#"# Decision Log: add-auth
+
+Date: 2026-04-27
+
+## Interview
+
+**Q:** Which tokens?
+**A:** JWT.
+
+## Design Decisions
+
+### [1] Use JWT for sessions
+
+- **Decision:** Issue signed JWTs
+  with a 15 minute expiry.
+- **Alternatives:** Server-side sessions.
+- **Rationale:** Stateless services scale out.
+- **Promotes to ADR:** yes
+
+### [2] Name the cookie `sid`
+
+- **Decision:** Cookie name `sid`.
+- **Promotes to ADR:** no
+
+## Review Findings
+"#;
+
+    const PERMANENT_LOG: &str = "# Architecture Decision Records\n\n## ADR-001: Use line-oriented state machine\n\n**Date:** 2026-04-01\n**Plan:** add-decision-log-validation\n**Status:** Accepted\n\n### Context\n\nFlat markdown.\n\n### Decision\n\nLine scanning.\n";
+
+    #[test]
+    fn parses_permanent_log_model() {
+        let log = PermanentLog::parse(PERMANENT_LOG);
+
+        assert_eq!(log.adrs.len(), 1);
+        let adr = &log.adrs[0];
+        assert_eq!(adr.id(), "ADR-001");
+        assert_eq!(adr.title, "Use line-oriented state machine");
+        assert_eq!(adr.date.as_deref(), Some("2026-04-01"));
+        assert_eq!(adr.plan.as_deref(), Some("add-decision-log-validation"));
+        assert_eq!(adr.status.as_deref(), Some("Accepted"));
+        assert_eq!(adr.section("Context"), Some("Flat markdown."));
+        assert_eq!(adr.section("Decision"), Some("Line scanning."));
+        assert_eq!(log.find("ADR-001"), Some(adr));
+        assert_eq!(log.find("1"), Some(adr));
+        assert_eq!(log.find("ADR-002"), None);
+    }
+
+    #[test]
+    fn reads_fields_placed_after_sections() {
+        let content = "# Architecture Decision Records\n\n## ADR-001: Late fields\n\n**Date:** 2026-04-01\n\n### Context\n\nx\n\n### Decision\n\ny\n\n**Plan:** add-auth\n**Status:** Accepted\n";
+
+        let adr = &PermanentLog::parse(content).adrs[0];
+
+        assert_eq!(adr.plan.as_deref(), Some("add-auth"));
+        assert_eq!(adr.status.as_deref(), Some("Accepted"));
+        assert_eq!(
+            adr.section("Decision"),
+            Some("y\n\n**Plan:** add-auth\n**Status:** Accepted")
+        );
+    }
+
+    fn adr(number: u32, status: &str, decision: &str) -> Adr {
+        Adr {
+            number,
+            title: format!("Decision {number}"),
+            status: Some(status.to_string()),
+            sections: vec![AdrSection {
+                heading: "Decision".to_string(),
+                body: decision.to_string(),
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn mentions_only_whole_adr_ids() {
+        let adr = adr(2, "Accepted", "Replaces ADR-0011 and extends ADR-003.");
+
+        assert!(!adr.mentions("ADR-001"));
+        assert!(adr.mentions("ADR-003"));
+    }
+
+    #[test]
+    fn follows_supersession_chain_to_current_decision() {
+        let log = PermanentLog {
+            adrs: vec![
+                adr(1, "Superseded by ADR-002", "a"),
+                adr(2, "Superseded by ADR-003", "Replaces ADR-001."),
+                adr(3, "Accepted", "Replaces ADR-002."),
+            ],
+        };
+
+        let chain: Vec<String> = log
+            .supersession_chain(&log.adrs[0])
+            .iter()
+            .map(|adr| adr.id())
+            .collect();
+
+        assert_eq!(chain, vec!["ADR-001", "ADR-002", "ADR-003"]);
+        assert!(log.supersession_cycles().is_empty());
+        assert!(log.adrs[1].mentions("ADR-001"));
+    }
+
+    #[test]
+    fn finds_each_supersession_cycle_once() {
+        let log = PermanentLog {
+            adrs: vec![
+                adr(1, "Superseded by ADR-002", "a"),
+                adr(2, "Superseded by ADR-001", "b"),
+                adr(3, "Superseded by ADR-003", "c"),
+            ],
+        };
+
+        assert_eq!(log.supersession_cycles(), vec![vec![1, 2]]);
+    }
+
+    fn headings(content: &str) -> Vec<u32> {
+        PermanentLog::parse(content)
+            .adrs
+            .iter()
+            .map(|adr| adr.number)
+            .collect()
+    }
+
+    #[test]
+    fn renumbers_gaps_and_rewrites_references() {
+        let content = "# Architecture Decision Records\n\n## ADR-001: A\n\n**Status:** Superseded by ADR-004\n\n## ADR-004: B\n\n**Status:** Accepted\n\n### Context\n\nReplaces ADR-001; see ADR-009.\n";
+
+        let renumbering = renumber_adrs(content);
+
+        assert_eq!(headings(&renumbering.content), vec![1, 2]);
+        assert!(
+            renumbering
+                .content
+                .contains("**Status:** Superseded by ADR-002\n")
+        );
+        assert!(
+            renumbering
+                .content
+                .contains("Replaces ADR-001; see ADR-009.\n")
+        );
+        assert_eq!(
+            renumbering.changes,
+            vec![
+                LineChange {
+                    line: 5,
+                    before: "**Status:** Superseded by ADR-004".to_string(),
+                    after: "**Status:** Superseded by ADR-002".to_string(),
+                },
+                LineChange {
+                    line: 7,
+                    before: "## ADR-004: B".to_string(),
+                    after: "## ADR-002: B".to_string(),
+                },
+            ]
+        );
+        assert!(renumbering.ambiguous.is_empty());
+    }
+
+    #[test]
+    fn renumbers_duplicate_from_merged_branches() {
+        let content = "## ADR-001: A\n\n## ADR-002: B\n\nSee ADR-002.\n\n## ADR-002: C\n\nSee ADR-002 and ADR-001.\n";
+
+        let renumbering = renumber_adrs(content);
+
+        assert_eq!(
+            renumbering.content,
+            "## ADR-001: A\n\n## ADR-002: B\n\nSee ADR-002.\n\n## ADR-003: C\n\nSee ADR-003 and ADR-001.\n"
+        );
+        assert_eq!(renumbering.ambiguous, vec![2]);
+    }
+
+    #[test]
+    fn numbers_inserted_adr_and_shifts_later_references() {
+        let content = "## ADR-001: A\n\n**Status:** Superseded by ADR-002\n\n## ADR-NNN: Inserted\n\n## ADR-002: B\n";
+
+        let renumbering = renumber_adrs(content);
+
+        assert_eq!(
+            renumbering.content,
+            "## ADR-001: A\n\n**Status:** Superseded by ADR-003\n\n## ADR-002: Inserted\n\n## ADR-003: B\n"
+        );
+    }
+
+    #[test]
+    fn sequential_log_is_unchanged() {
+        let content =
+            "# Architecture Decision Records\n\n## ADR-001: A\n\n## ADR-002: B\n\nSee ADR-001.";
+
+        let renumbering = renumber_adrs(content);
+
+        assert_eq!(renumbering.content, content);
+        assert!(renumbering.changes.is_empty());
+    }
+
+    #[test]
+    fn renders_adr_as_parsed() {
+        let log = PermanentLog::parse(PERMANENT_LOG);
+
+        let rendered = log.adrs[0].render();
+
+        assert!(PERMANENT_LOG.ends_with(&rendered));
+    }
+
+    #[test]
+    fn parses_plan_log_model() {
+        let log = PlanLog::parse(PLAN_LOG);
+
+        assert_eq!(log.plan_name.as_deref(), Some("add-auth"));
+        assert_eq!(log.date.as_deref(), Some("2026-04-27"));
+        assert_eq!(log.decisions.len(), 2);
+    }
+
+    #[test]
+    fn parses_design_decisions() {
+        let decisions = parse_plan_decisions(PLAN_LOG);
+
+        assert_eq!(decisions.len(), 2);
+        assert_eq!(decisions[0].title, "Use JWT for sessions");
+        assert_eq!(
+            decisions[0].decision.as_deref(),
+            Some("Issue signed JWTs with a 15 minute expiry.")
+        );
+        assert!(decisions[0].promotes);
+        assert!(!decisions[1].promotes);
+    }
+}
@@ -15,6 +15,13 @@ impl FeaturePath {
         }
     }
 
+    /// Parse a `domain/feature` path, rejecting reserved (`_`, `.`) and nested segments.
+    pub fn parse(path: &str) -> Option<Self> {
+        let (domain, feature) = path.trim().trim_matches('/').split_once('/')?;
+        let valid = |s: &str| !s.is_empty() && !s.contains('/') && !s.starts_with(['_', '.']);
+        (valid(domain) && valid(feature)).then(|| Self::new(domain, feature))
+    }
+
     pub fn spec_path(&self, base: &Path) -> std::path::PathBuf {
         base.join(&self.domain).join(&self.feature).join("spec.md")
     }
@@ -141,6 +148,17 @@ mod tests {
         assert_eq!(format!("{}", fp), "domain/feature");
     }
 
+    #[test]
+    fn feature_path_parse() {
+        assert_eq!(
+            FeaturePath::parse("cli/validate"),
+            Some(FeaturePath::new("cli", "validate"))
+        );
+        assert_eq!(FeaturePath::parse("cli"), None);
+        assert_eq!(FeaturePath::parse("cli/a/b"), None);
+        assert_eq!(FeaturePath::parse("_plans/x"), None);
+    }
+
     #[test]
     fn feature_path_spec_path() {
         let fp = FeaturePath::new("cli", "validate");
@@ -0,0 +1,550 @@
+use std::fs;
+use std::path::Path;
+
+use crate::feature::FeaturePath;
+
+/// plan.md section listing feature-level operations, one bullet each:
+/// `* DELETE auth/legacy`, `* MOVE auth/login -> identity/login`, `* RENAME auth/signup -> auth/register`
+pub const OPERATIONS_HEADING: &str = "## Feature Operations";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FeatureOperation {
+    Delete(FeaturePath),
+    /// Move a feature to another domain (or any other path)
+    Move {
+        from: FeaturePath,
+        to: FeaturePath,
+    },
+    /// Rename a feature within its domain
+    Rename {
+        from: FeaturePath,
+        to: FeaturePath,
+    },
+}
+
+impl FeatureOperation {
+    pub fn source(&self) -> &FeaturePath {
+        match self {
+            FeatureOperation::Delete(feature) => feature,
+            FeatureOperation::Move { from, .. } | FeatureOperation::Rename { from, .. } => from,
+        }
+    }
+
+    pub fn destination(&self) -> Option<&FeaturePath> {
+        match self {
+            FeatureOperation::Delete(_) => None,
+            FeatureOperation::Move { to, .. } | FeatureOperation::Rename { to, .. } => Some(to),
+        }
+    }
+
+    /// Whether `feature` (a `domain/feature` path) is the source or destination
+    pub fn involves(&self, feature: &str) -> bool {
+        self.source().to_string() == feature
+            || self
+                .destination()
+                .is_some_and(|destination| destination.to_string() == feature)
+    }
+}
+
+impl std::fmt::Display for FeatureOperation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FeatureOperation::Delete(feature) => write!(f, "DELETE {feature}"),
+            FeatureOperation::Move { from, to } => write!(f, "MOVE {from} -> {to}"),
+            FeatureOperation::Rename { from, to } => write!(f, "RENAME {from} -> {to}"),
+        }
+    }
+}
+
+/// A feature operation and the plan.md line that declares it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlannedOperation {
+    pub line: usize,
+    pub operation: FeatureOperation,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OperationError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl std::fmt::Display for OperationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "plan.md:{}: {}", self.line, self.message)
+    }
+}
+
+/// A cross-reference to a moved or deleted feature found in another permanent spec.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReferenceChange {
+    Rewritten {
+        spec: String,
+        from: FeaturePath,
+        to: FeaturePath,
+        count: usize,
+    },
+    /// References to a deleted feature cannot be rewritten and are left for review
+    Dangling {
+        spec: String,
+        feature: FeaturePath,
+        count: usize,
+    },
+}
+
+impl std::fmt::Display for ReferenceChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReferenceChange::Rewritten {
+                spec,
+                from,
+                to,
+                count,
+            } => write!(
+                f,
+                "{spec}: {count} reference(s) to {from} rewritten to {to}"
+            ),
+            ReferenceChange::Dangling {
+                spec,
+                feature,
+                count,
+            } => write!(
+                f,
+                "{spec}: {count} reference(s) to deleted feature {feature} left in place"
+            ),
+        }
+    }
+}
+
+/// Parse the `## Feature Operations` section of plan.md.
+pub fn parse_operations(plan_md: &str) -> (Vec<PlannedOperation>, Vec<OperationError>) {
+    let mut operations = Vec::new();
+    let mut errors = Vec::new();
+    let mut in_section = false;
+
+    for (index, line) in plan_md.lines().enumerate() {
+        let trimmed = line.trim();
+
+        if trimmed.starts_with('#') {
+            in_section = trimmed == OPERATIONS_HEADING;
+            continue;
+        }
+
+        let Some(item) = trimmed
+            .strip_prefix("* ")
+            .or_else(|| trimmed.strip_prefix("- "))
+        else {
+            continue;
+        };
+        if !in_section {
+            continue;
+        }
+
+        match parse_operation(item.trim()) {
+            Ok(operation) => operations.push(PlannedOperation {
+                line: index + 1,
+                operation,
+            }),
+            Err(message) => errors.push(OperationError {
+                line: index + 1,
+                message,
+            }),
+        }
+    }
+
+    (operations, errors)
+}
+
+fn parse_operation(item: &str) -> Result<FeatureOperation, String> {
+    let (verb, rest) = item.split_once(' ').unwrap_or((item, ""));
+    let rest = rest.trim().trim_matches('`');
+
+    match verb {
+        "DELETE" => Ok(FeatureOperation::Delete(parse_path(rest)?)),
+        "MOVE" | "RENAME" => {
+            let Some((from, to)) = rest.split_once("->") else {
+                return Err(format!(
+                    "{verb} needs '<domain>/<feature> -> <domain>/<feature>'"
+                ));
+            };
+            let from = parse_path(from.trim().trim_matches('`'))?;
+            let to = parse_path(to.trim().trim_matches('`'))?;
+            if from == to {
+                return Err(format!("{verb} {from} -> {to} does not change the path"));
+            }
+            if verb == "MOVE" {
+                return Ok(FeatureOperation::Move { from, to });
+            }
+            if from.domain != to.domain {
+                return Err(format!(
+                    "RENAME {from} -> {to} changes the domain (use MOVE instead)"
+                ));
+            }
+            Ok(FeatureOperation::Rename { from, to })
+        }
+        _ => Err(format!(
+            "unknown feature operation '{verb}' (expected DELETE, MOVE or RENAME)"
+        )),
+    }
+}
+
+fn parse_path(path: &str) -> Result<FeaturePath, String> {
+    FeaturePath::parse(path).ok_or_else(|| format!("'{path}' is not a <domain>/<feature> path"))
+}
+
+/// Check operations against the permanent specs and the features the plan has deltas for.
+///
+/// Sources must exist, destinations must be free, each path may appear in one operation,
+/// and deltas must not target deleted features or the old path of a moved one.
+pub fn check_operations(
+    specs_base: &Path,
+    operations: &[PlannedOperation],
+    delta_features: &[String],
+) -> Vec<OperationError> {
+    let mut errors = Vec::new();
+    let mut claimed: Vec<(String, usize)> = Vec::new();
+    let mut error = |line: usize, message: String| errors.push(OperationError { line, message });
+
+    for planned in operations {
+        let operation = &planned.operation;
+        let source = operation.source();
+
+        if !source.spec_path(specs_base).exists() {
+            error(
+                planned.line,
+                format!("{operation}: no permanent spec at {source}/spec.md"),
+            );
+        }
+
+        if let Some(destination) = operation.destination()
+            && destination.spec_path(specs_base).exists()
+        {
+            error(
+                planned.line,
+                format!("{operation}: {destination} already has a permanent spec"),
+            );
+        }
+
+        for path in std::iter::once(source).chain(operation.destination()) {
+            let key = path.to_string();
+            if let Some((_, first_line)) = claimed.iter().find(|(p, _)| *p == key) {
+                error(
+                    planned.line,
+                    format!(
+                        "{operation}: {path} is already used by the operation at line {first_line}"
+                    ),
+                );
+            }
+            claimed.push((key, planned.line));
+        }
+
+        if delta_features.contains(&source.to_string()) {
+            let message = match operation.destination() {
+                None => format!("{operation}: the plan also has a delta for the deleted feature"),
+                Some(destination) => {
+                    format!("{operation}: the delta for {source} must be placed at {destination}")
+                }
+            };
+            error(planned.line, message);
+        }
+    }
+
+    errors
+}
+
+/// The feature whose current permanent spec a plan path refers to: the source of a move
+/// or rename when `feature` is its destination, otherwise `feature` itself.
+pub fn current_location(operations: &[PlannedOperation], feature: &str) -> String {
+    operations
+        .iter()
+        .find(|planned| {
+            planned
+                .operation
+                .destination()
+                .is_some_and(|destination| destination.to_string() == feature)
+        })
+        .map(|planned| planned.operation.source().to_string())
+        .unwrap_or_else(|| feature.to_string())
+}
+
+/// Where the feature at `feature` is once `operations` are applied; `None` if it is deleted.
+pub fn final_location(operations: &[PlannedOperation], feature: &str) -> Option<String> {
+    match operations
+        .iter()
+        .find(|planned| planned.operation.source().to_string() == feature)
+    {
+        Some(planned) => planned.operation.destination().map(ToString::to_string),
+        None => Some(feature.to_string()),
+    }
+}
+
+/// plan.md without the given (1-based) lines, used to drop the bullets of operations
+/// a partial record already applied.
+pub fn remove_operation_lines(plan_md: &str, lines: &[usize]) -> String {
+    plan_md
+        .split_inclusive('\n')
+        .enumerate()
+        .filter(|(index, _)| !lines.contains(&(index + 1)))
+        .map(|(_, line)| line)
+        .collect()
+}
+
+/// Move, rename and delete feature directories under `specs_base`.
+pub fn apply_operations(specs_base: &Path, operations: &[PlannedOperation]) -> std::io::Result<()> {
+    for planned in operations {
+        let source = planned.operation.source();
+        let source_dir = specs_base.join(&source.domain).join(&source.feature);
+
+        match planned.operation.destination() {
+            Some(destination) => {
+                let domain_dir = specs_base.join(&destination.domain);
+                fs::create_dir_all(&domain_dir)?;
+                fs::rename(&source_dir, domain_dir.join(&destination.feature))?;
+            }
+            None => fs::remove_dir_all(&source_dir)?,
+        }
+
+        remove_if_empty(&specs_base.join(&source.domain))?;
+    }
+
+    Ok(())
+}
+
+fn remove_if_empty(dir: &Path) -> std::io::Result<()> {
+    if fs::read_dir(dir)?.next().is_none() {
+        fs::remove_dir(dir)?;
+    }
+    Ok(())
+}
+
+/// Rewrite references to moved features in `specs`, `(domain/feature, content)` pairs at the
+/// paths they have once the operations are applied, and list references to deleted ones.
+/// Nothing is written. Returns what changed, per spec.
+pub fn rewrite_spec_references(
+    specs: &mut [(String, String)],
+    operations: &[PlannedOperation],
+) -> Vec<ReferenceChange> {
+    let mut changes = Vec::new();
+
+    for (feature, content) in specs.iter_mut() {
+        let spec = format!("{feature}/spec.md");
+
+        for planned in operations {
+            let source = planned.operation.source();
+            match planned.operation.destination() {
+                Some(destination) => {
+                    let (rewritten, count) =
+                        rewrite_references(content, &source.to_string(), &destination.to_string());
+                    if count > 0 {
+                        *content = rewritten;
+                        changes.push(ReferenceChange::Rewritten {
+                            spec: spec.clone(),
+                            from: source.clone(),
+                            to: destination.clone(),
+                            count,
+                        });
+                    }
+                }
+                None => {
+                    let count = reference_positions(content, &source.to_string()).len();
+                    if count > 0 {
+                        changes.push(ReferenceChange::Dangling {
+                            spec: spec.clone(),
+                            feature: source.clone(),
+                            count,
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    changes
+}
+
+/// Replace references to the feature path `from` with `to`, returning the count replaced.
+pub fn rewrite_references(content: &str, from: &str, to: &str) -> (String, usize) {
+    let positions = reference_positions(content, from);
+    let mut result = content.to_string();
+    for position in positions.iter().rev() {
+        result.replace_range(*position..position + from.len(), to);
+    }
+    (result, positions.len())
+}
+
+/// Byte offsets of `feature` (a `domain/feature` path) used as a whole path segment,
+/// e.g. in `specs/auth/login/spec.md`, `../auth/login/spec.md` or `` `auth/login` ``.
+fn reference_positions(content: &str, feature: &str) -> Vec<usize> {
+    let is_name_char = |c: char| c.is_ascii_alphanumeric() || c == '-' || c == '_';
+
+    content
+        .match_indices(feature)
+        .map(|(position, _)| position)
+        .filter(|&position| {
+            let before = content[..position].chars().next_back();
+            let after = content[position + feature.len()..].chars().next();
+            !before.is_some_and(is_name_char) && !after.is_some_and(is_name_char)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const PLAN_MD: &str = r#"# Plan: reorganize-auth
+
+## Feature Operations
+
+* MOVE auth/login -> identity/login
+* RENAME auth/signup -> auth/register
+* DELETE auth/legacy
+- ARCHIVE auth/old
+
+## Tasks
+
+* DELETE is not an operation here
+"#;
+
+    fn write_spec(base: &Path, feature: &str, content: &str) {
+        let dir = base.join(feature);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("spec.md"), content).unwrap();
+    }
+
+    #[test]
+    fn removes_applied_operation_lines() {
+        let (operations, _) = parse_operations(PLAN_MD);
+
+        let remaining = remove_operation_lines(PLAN_MD, &[operations[0].line]);
+
+        let (operations, _) = parse_operations(&remaining);
+        assert_eq!(operations.len(), 2);
+        assert_eq!(
+            operations[0].operation.to_string(),
+            "RENAME auth/signup -> auth/register"
+        );
+        assert!(remaining.ends_with("* DELETE is not an operation here\n"));
+    }
+
+    #[test]
+    fn parses_operations_section_only() {
+        let (operations, errors) = parse_operations(PLAN_MD);
+
+        let rendered: Vec<String> = operations
+            .iter()
+            .map(|p| format!("{}: {}", p.line, p.operation))
+            .collect();
+        assert_eq!(
+            rendered,
+            vec![
+                "5: MOVE auth/login -> identity/login",
+                "6: RENAME auth/signup -> auth/register",
+                "7: DELETE auth/legacy",
+            ]
+        );
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors[0].to_string(),
+            "plan.md:8: unknown feature operation 'ARCHIVE' (expected DELETE, MOVE or RENAME)"
+        );
+    }
+
+    #[test]
+    fn rejects_rename_across_domains() {
+        let (_, errors) =
+            parse_operations("## Feature Operations\n\n* RENAME auth/login -> identity/login\n");
+
+        assert_eq!(
+            errors[0].message,
+            "RENAME auth/login -> identity/login changes the domain (use MOVE instead)"
+        );
+    }
+
+    #[test]
+    fn checks_operations_against_specs_and_deltas() {
+        let tmp = TempDir::new().unwrap();
+        write_spec(tmp.path(), "auth/login", "# Feature: Login\n");
+        write_spec(tmp.path(), "identity/session", "# Feature: Session\n");
+        let (operations, _) = parse_operations(
+            "## Feature Operations\n\n* MOVE auth/login -> identity/session\n* DELETE auth/gone\n* DELETE auth/login\n",
+        );
+
+        let errors = check_operations(tmp.path(), &operations, &["auth/login".to_string()]);
+        let messages: Vec<String> = errors.iter().map(ToString::to_string).collect();
+
+        assert_eq!(
+            messages,
+            vec![
+                "plan.md:3: MOVE auth/login -> identity/session: identity/session already has a permanent spec",
+                "plan.md:3: MOVE auth/login -> identity/session: the delta for auth/login must be placed at identity/session",
+                "plan.md:4: DELETE auth/gone: no permanent spec at auth/gone/spec.md",
+                "plan.md:5: DELETE auth/login: auth/login is already used by the operation at line 3",
+                "plan.md:5: DELETE auth/login: the plan also has a delta for the deleted feature",
+            ]
+        );
+    }
+
+    #[test]
+    fn resolves_current_location_of_moved_feature() {
+        let (operations, _) = parse_operations(PLAN_MD);
+
+        assert_eq!(
+            current_location(&operations, "identity/login"),
+            "auth/login"
+        );
+        assert_eq!(current_location(&operations, "auth/other"), "auth/other");
+    }
+
+    #[test]
+    fn rewrites_whole_path_references_only() {
+        let content = "See `auth/login`, specs/auth/login/spec.md and [x](../../auth/login/spec.md), not auth/login-sso or oauth/login.";
+
+        let (rewritten, count) = rewrite_references(content, "auth/login", "identity/login");
+
+        assert_eq!(count, 3);
+        assert_eq!(
+            rewritten,
+            "See `identity/login`, specs/identity/login/spec.md and [x](../../identity/login/spec.md), not auth/login-sso or oauth/login."
+        );
+    }
+
+    #[test]
+    fn applies_operations_and_rewrites_references() {
+        let tmp = TempDir::new().unwrap();
+        let base = tmp.path();
+        write_spec(base, "auth/login", "# Feature: Login\n");
+        write_spec(base, "auth/legacy", "# Feature: Legacy\n");
+        write_spec(
+            base,
+            "cli/session",
+            "# Feature: Session\n\n* Uses `auth/login` and replaces `auth/legacy`\n",
+        );
+        let (operations, _) = parse_operations(
+            "## Feature Operations\n\n* MOVE auth/login -> identity/login\n* DELETE auth/legacy\n",
+        );
+
+        apply_operations(base, &operations).unwrap();
+        let mut specs = [(
+            "cli/session".to_string(),
+            fs::read_to_string(base.join("cli/session/spec.md")).unwrap(),
+        )];
+        let changes = rewrite_spec_references(&mut specs, &operations);
+
+        assert!(base.join("identity/login/spec.md").exists());
+        assert!(!base.join("auth").exists());
+        assert_eq!(
+            specs[0].1,
+            "# Feature: Session\n\n* Uses `identity/login` and replaces `auth/legacy`\n"
+        );
+        let rendered: Vec<String> = changes.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            rendered,
+            vec![
+                "cli/session/spec.md: 1 reference(s) to auth/login rewritten to identity/login",
+                "cli/session/spec.md: 1 reference(s) to deleted feature auth/legacy left in place",
+            ]
+        );
+    }
+}
@@ -1,8 +1,17 @@
 pub mod cli;
+pub mod decision_log;
 pub mod embedding;
 pub mod feature;
+pub mod feature_ops;
+pub mod madr;
+pub mod merge;
 pub mod plan;
+pub mod plan_status;
+pub mod plan_tasks;
+pub mod promote;
 pub mod record;
+pub mod revert;
+pub mod scaffold;
 pub mod search;
 pub mod tree;
 pub mod validate;
@@ -0,0 +1,438 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use thiserror::Error;
+
+use crate::decision_log::{Adr, AdrSection, DECISION_LOG_FILE, PERMANENT_LOG_H1, PermanentLog};
+use crate::validate::decision_log::{self as validation, DecisionLogError};
+
+/// ADRs in the adr-tools / MADR layout: one `NNNN-title.md` file per decision, with a
+/// `# N. Title` heading, `Date:` and `Plan:` lines and `## Status`, `## Context`,
+/// `## Decision`, … sections.
+#[derive(Error, Debug)]
+pub enum MadrError {
+    #[error("decision-log.md not found at {0}")]
+    LogNotFound(String),
+
+    #[error("{0} already exists (use --force to replace it)")]
+    LogExists(String),
+
+    #[error("Directory not found: {0}")]
+    DirNotFound(String),
+
+    #[error("No NNNN-title.md ADR files found in {0}")]
+    NoAdrFiles(String),
+
+    #[error("Failed to read file: {path}")]
+    FileReadError { path: String },
+
+    #[error("Failed to write file: {path}")]
+    FileWriteError { path: String },
+
+    #[error("Invalid decision log: {}", join_errors(.errors))]
+    InvalidLog { errors: Vec<DecisionLogError> },
+}
+
+fn join_errors(errors: &[DecisionLogError]) -> String {
+    errors
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// MADR section headings and the permanent log sections they map to
+const SECTION_ALIASES: [(&str, &str); 3] = [
+    ("Context and Problem Statement", "Context"),
+    ("Decision Outcome", "Decision"),
+    ("Considered Options", "Options Considered"),
+];
+
+/// `0007-use-pulldown-cmark.md`
+pub fn madr_file_name(adr: &Adr) -> String {
+    let mut slug = String::new();
+    for c in adr.title.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    format!("{:04}-{}.md", adr.number, slug.trim_end_matches('-'))
+}
+
+/// `[7. Use pulldown-cmark](0007-use-pulldown-cmark.md)`, or the bare id when the ADR is
+/// not in the log.
+fn madr_link(log: &PermanentLog, number: u32) -> String {
+    match log.get(number) {
+        Some(adr) => format!("[{}. {}]({})", adr.number, adr.title, madr_file_name(adr)),
+        None => format!("ADR-{number:03}"),
+    }
+}
+
+/// One ADR as an adr-tools / MADR file. Supersession becomes links between files, with
+/// a `Supersedes` line on the superseding ADR as adr-tools writes it.
+pub fn render_madr(adr: &Adr, log: &PermanentLog) -> String {
+    let mut madr = format!("# {}. {}\n\n", adr.number, adr.title);
+    for (name, value) in [("Date", &adr.date), ("Plan", &adr.plan)] {
+        if let Some(value) = value {
+            madr.push_str(&format!("{name}: {value}\n"));
+        }
+    }
+
+    let status = match adr.superseded_by() {
+        Some(by) => format!("Superseded by {}", madr_link(log, by)),
+        None => adr.status.clone().unwrap_or_default(),
+    };
+    madr.push_str(&format!("\n## Status\n\n{status}\n"));
+    for earlier in log
+        .adrs
+        .iter()
+        .filter(|a| a.superseded_by() == Some(adr.number))
+    {
+        madr.push_str(&format!(
+            "\nSupersedes {}\n",
+            madr_link(log, earlier.number)
+        ));
+    }
+
+    for section in &adr.sections {
+        madr.push_str(&format!("\n## {}\n\n{}\n", section.heading, section.body));
+    }
+    madr
+}
+
+/// Parse an adr-tools / MADR file. The number comes from the `NNNN-` file name prefix;
+/// `None` for files without one (README, templates). Date, plan and status may also come
+/// from YAML front matter or `* Status: …` bullets as MADR writes them.
+pub fn parse_madr(file_name: &str, content: &str, default_plan: &str) -> Option<Adr> {
+    let number = file_name
+        .split_once('-')
+        .filter(|(digits, _)| !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit()))
+        .and_then(|(digits, _)| digits.parse().ok())?;
+    let mut adr = Adr {
+        number,
+        ..Default::default()
+    };
+    let mut section: Option<(String, Vec<&str>)> = None;
+    let mut in_front_matter = content.starts_with("---");
+
+    for (index, line) in content.lines().enumerate() {
+        let trimmed = line.trim();
+        if in_front_matter {
+            in_front_matter = index == 0 || trimmed != "---";
+            if in_front_matter {
+                set_madr_field(&mut adr, trimmed);
+            }
+        } else if let Some(heading) = trimmed.strip_prefix("## ") {
+            close_madr_section(&mut adr, section.take());
+            section = Some((heading.trim().to_string(), Vec::new()));
+        } else if let Some((_, lines)) = section.as_mut() {
+            lines.push(line.trim_end());
+        } else if let Some(title) = trimmed.strip_prefix("# ") {
+            adr.title = strip_title_number(title).to_string();
+        } else {
+            set_madr_field(&mut adr, trimmed);
+        }
+    }
+    close_madr_section(&mut adr, section.take());
+
+    if adr.plan.is_none() {
+        adr.plan = Some(default_plan.to_string());
+    }
+    Some(adr)
+}
+
+/// `7. Use pulldown-cmark` -> `Use pulldown-cmark`
+fn strip_title_number(title: &str) -> &str {
+    let title = title.trim();
+    title
+        .split_once(". ")
+        .filter(|(number, _)| number.chars().all(|c| c.is_ascii_digit()))
+        .map_or(title, |(_, rest)| rest.trim())
+}
+
+/// `Date: …`, `* Status: …` or front matter `status: …`
+fn set_madr_field(adr: &mut Adr, line: &str) {
+    let line = line.trim_start_matches(['*', '-']).trim();
+    let Some((name, value)) = line.split_once(':') else {
+        return;
+    };
+    let value = value.trim().trim_matches('"');
+    if value.is_empty() {
+        return;
+    }
+    match name.trim().to_ascii_lowercase().as_str() {
+        "date" => adr.date = Some(value.to_string()),
+        "plan" => adr.plan = Some(value.to_string()),
+        "status" => adr.status = Some(normalize_status(value)),
+        _ => {}
+    }
+}
+
+fn close_madr_section(adr: &mut Adr, section: Option<(String, Vec<&str>)>) {
+    let Some((heading, lines)) = section else {
+        return;
+    };
+    let body = lines.join("\n").trim().to_string();
+
+    if heading.eq_ignore_ascii_case("Status") {
+        // `Supersedes` lines are implied by the superseded ADR's status
+        let status = body
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty() && !line.starts_with("Supersedes"));
+        if let Some(status) = status {
+            adr.status = Some(normalize_status(status));
+        }
+        return;
+    }
+
+    let heading = SECTION_ALIASES
+        .iter()
+        .find(|(madr, _)| madr.eq_ignore_ascii_case(&heading))
+        .map_or(heading.as_str(), |(_, heading)| heading);
+    adr.sections.push(AdrSection {
+        heading: heading.to_string(),
+        body,
+    });
+}
+
+/// `accepted` -> `Accepted`; `Superseded by [2. Title](0002-title.md)` -> `Superseded by ADR-002`
+fn normalize_status(status: &str) -> String {
+    let lower = status.to_ascii_lowercase();
+    if let Some(target) = lower.strip_prefix("superseded by") {
+        let digits: String = target
+            .trim_start_matches(|c: char| !c.is_ascii_digit())
+            .chars()
+            .take_while(|c| c.is_ascii_digit())
+            .collect();
+        if let Ok(number) = digits.parse::<u32>() {
+            return format!("Superseded by ADR-{number:03}");
+        }
+    }
+
+    let mut chars = status.chars();
+    chars.next().map_or_else(String::new, |first| {
+        first.to_uppercase().chain(chars).collect()
+    })
+}
+
+/// Write every ADR of `specs/decision-log.md` to `<dir>/NNNN-title.md`. The log must
+/// pass validation first. Returns the written paths.
+pub fn export_madr(specs_base: &Path, dir: &Path) -> Result<Vec<PathBuf>, MadrError> {
+    let log_path = specs_base.join(DECISION_LOG_FILE);
+    let content = fs::read_to_string(&log_path)
+        .map_err(|_| MadrError::LogNotFound(log_path.display().to_string()))?;
+    let result = validation::validate_permanent_log(&content);
+    if !result.is_success() {
+        return Err(MadrError::InvalidLog {
+            errors: result.errors,
+        });
+    }
+
+    let log = PermanentLog::parse(&content);
+    fs::create_dir_all(dir).map_err(|_| MadrError::FileWriteError {
+        path: dir.display().to_string(),
+    })?;
+    let mut written = Vec::new();
+    for adr in &log.adrs {
+        let path = dir.join(madr_file_name(adr));
+        fs::write(&path, render_madr(adr, &log)).map_err(|_| MadrError::FileWriteError {
+            path: path.display().to_string(),
+        })?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
+/// Build `specs/decision-log.md` from the `NNNN-title.md` files of `dir`, ordered by
+/// number. ADRs without a `Plan:` get `default_plan`. Nothing is written unless the
+/// result passes validation, and an existing log is only replaced with `force`.
+pub fn import_madr(
+    specs_base: &Path,
+    dir: &Path,
+    default_plan: &str,
+    force: bool,
+) -> Result<Vec<String>, MadrError> {
+    let log_path = specs_base.join(DECISION_LOG_FILE);
+    if log_path.exists() && !force {
+        return Err(MadrError::LogExists(log_path.display().to_string()));
+    }
+    let entries =
+        fs::read_dir(dir).map_err(|_| MadrError::DirNotFound(dir.display().to_string()))?;
+
+    let mut adrs = Vec::new();
+    for path in entries.filter_map(|entry| entry.ok().map(|entry| entry.path())) {
+        let Some(file_name) = path.file_name().map(|name| name.to_string_lossy()) else {
+            continue;
+        };
+        if path.extension().is_none_or(|ext| ext != "md") {
+            continue;
+        }
+        let content = fs::read_to_string(&path).map_err(|_| MadrError::FileReadError {
+            path: path.display().to_string(),
+        })?;
+        adrs.extend(parse_madr(&file_name, &content, default_plan));
+    }
+    if adrs.is_empty() {
+        return Err(MadrError::NoAdrFiles(dir.display().to_string()));
+    }
+    adrs.sort_by_key(|adr| adr.number);
+
+    let mut content = PERMANENT_LOG_H1.to_string();
+    for adr in &adrs {
+        content.push_str("\n\n");
+        content.push_str(adr.render().trim_end());
+    }
+    content.push('\n');
+
+    let result = validation::validate_permanent_log(&content);
+    if !result.is_success() {
+        return Err(MadrError::InvalidLog {
+            errors: result.errors,
+        });
+    }
+    fs::create_dir_all(specs_base).map_err(|_| MadrError::FileWriteError {
+        path: specs_base.display().to_string(),
+    })?;
+    fs::write(&log_path, content).map_err(|_| MadrError::FileWriteError {
+        path: log_path.display().to_string(),
+    })?;
+
+    Ok(adrs
+        .iter()
+        .map(|adr| format!("{}: {}", adr.id(), adr.title))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const PERMANENT_LOG: &str = "# Architecture Decision Records
+
+## ADR-001: Use line scanning
+
+**Date:** 2026-01-10
+**Plan:** add-validation
+**Status:** Superseded by ADR-002
+
+### Context
+
+Flat markdown.
+
+### Decision
+
+Scan line by line.
+
+## ADR-002: Use pulldown-cmark
+
+**Date:** 2026-03-02
+**Plan:** change-parser
+**Status:** Accepted
+
+### Context
+
+Line scanning (ADR-001) missed nested lists.
+
+### Decision
+
+Parse with pulldown-cmark.
+
+### Consequences
+
+A new dependency.
+";
+
+    #[test]
+    fn names_files_like_adr_tools() {
+        let log = PermanentLog::parse(PERMANENT_LOG);
+
+        assert_eq!(madr_file_name(&log.adrs[1]), "0002-use-pulldown-cmark.md");
+    }
+
+    #[test]
+    fn renders_supersession_as_links_both_ways() {
+        let log = PermanentLog::parse(PERMANENT_LOG);
+
+        let superseded = render_madr(&log.adrs[0], &log);
+        let current = render_madr(&log.adrs[1], &log);
+
+        assert!(superseded.starts_with(
+            "# 1. Use line scanning\n\nDate: 2026-01-10\nPlan: add-validation\n\n## Status\n\nSuperseded by [2. Use pulldown-cmark](0002-use-pulldown-cmark.md)\n"
+        ));
+        assert!(current.contains(
+            "## Status\n\nAccepted\n\nSupersedes [1. Use line scanning](0001-use-line-scanning.md)\n"
+        ));
+    }
+
+    #[test]
+    fn export_then_import_round_trips() {
+        let tmp = TempDir::new().unwrap();
+        let specs = tmp.path().join("specs");
+        let adr_dir = tmp.path().join("docs/adr");
+        fs::create_dir_all(&specs).unwrap();
+        fs::write(specs.join(DECISION_LOG_FILE), PERMANENT_LOG).unwrap();
+
+        let written = export_madr(&specs, &adr_dir).unwrap();
+        assert_eq!(written.len(), 2);
+
+        let imported = import_madr(&specs, &adr_dir, "imported", true).unwrap();
+        assert_eq!(
+            imported,
+            vec!["ADR-001: Use line scanning", "ADR-002: Use pulldown-cmark"]
+        );
+        let content = fs::read_to_string(specs.join(DECISION_LOG_FILE)).unwrap();
+        assert_eq!(content, PERMANENT_LOG);
+    }
+
+    #[test]
+    fn parses_madr_front_matter_and_sections() {
+        let content = "---\nstatus: superseded by [ADR-0005](0005-use-postgres.md)\ndate: 2024-02-01\n---\n\n# Use MySQL\n\n## Context and Problem Statement\n\nWe need a database.\n\n## Considered Options\n\n* MySQL\n* Postgres\n\n## Decision Outcome\n\nChosen option: MySQL.\n";
+
+        let adr = parse_madr("0003-use-mysql.md", content, "imported").unwrap();
+
+        assert_eq!(adr.number, 3);
+        assert_eq!(adr.title, "Use MySQL");
+        assert_eq!(adr.date.as_deref(), Some("2024-02-01"));
+        assert_eq!(adr.plan.as_deref(), Some("imported"));
+        assert_eq!(adr.status.as_deref(), Some("Superseded by ADR-005"));
+        let headings: Vec<&str> = adr.sections.iter().map(|s| s.heading.as_str()).collect();
+        assert_eq!(headings, vec!["Context", "Options Considered", "Decision"]);
+    }
+
+    #[test]
+    fn skips_files_without_number_prefix() {
+        assert!(parse_madr("README.md", "# ADRs\n", "imported").is_none());
+        assert!(parse_madr("template.md", "# Title\n", "imported").is_none());
+    }
+
+    #[test]
+    fn import_refuses_existing_log_without_force() {
+        let tmp = TempDir::new().unwrap();
+        fs::write(tmp.path().join(DECISION_LOG_FILE), PERMANENT_LOG).unwrap();
+
+        let result = import_madr(tmp.path(), tmp.path(), "imported", false);
+
+        assert!(matches!(result, Err(MadrError::LogExists(_))));
+    }
+
+    #[test]
+    fn import_refuses_invalid_result() {
+        let tmp = TempDir::new().unwrap();
+        let adr_dir = tmp.path().join("adr");
+        fs::create_dir_all(&adr_dir).unwrap();
+        fs::write(
+            adr_dir.join("0001-record-decisions.md"),
+            "# 1. Record decisions\n\nDate: 2024-01-01\n\n## Status\n\nProposed\n\n## Context\n\nx\n\n## Decision\n\ny\n",
+        )
+        .unwrap();
+
+        let result = import_madr(tmp.path(), &adr_dir, "imported", false);
+
+        assert!(matches!(result, Err(MadrError::InvalidLog { .. })));
+        assert!(!tmp.path().join(DECISION_LOG_FILE).exists());
+    }
+}
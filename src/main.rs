@@ -2,7 +2,10 @@ use std::path::PathBuf;
 use std::process::ExitCode;
 
 use clap::Parser;
-use speq_skill::{cli, feature, plan, record, search, tree, validate};
+use speq_skill::{
+    cli, decision_log, feature, madr, plan, plan_status, plan_tasks, promote, record, revert,
+    scaffold, search, tree, validate,
+};
 
 fn main() -> ExitCode {
     let cli = cli::Cli::parse();
@@ -11,7 +14,31 @@ fn main() -> ExitCode {
         cli::Commands::Domain { command } => handle_domain_command(command),
         cli::Commands::Feature { command } => handle_feature_command(command),
         cli::Commands::Plan { command } => handle_plan_command(command),
-        cli::Commands::Record { plan_name } => handle_record_command(&plan_name),
+        cli::Commands::Record {
+            plan_name,
+            features,
+            date,
+            promote_adrs,
+            force,
+            command,
+        } => match (command, plan_name) {
+            (Some(cli::RecordCommands::Revert { archive_name }), _) => {
+                handle_revert_command(&archive_name)
+            }
+            (None, Some(plan_name)) => handle_record_command(
+                &plan_name,
+                record::RecordOptions {
+                    features,
+                    date,
+                    promote_decisions: promote_adrs,
+                    force,
+                },
+            ),
+            (None, None) => {
+                eprintln!("ERROR: Missing plan name (usage: speq record <plan-name>)");
+                ExitCode::from(2)
+            }
+        },
         cli::Commands::Search { command } => handle_search_command(command),
         cli::Commands::DecisionLog { command } => handle_decision_log_command(command),
     }
@@ -77,6 +104,79 @@ fn print_spec_warnings(warnings: &[plan::SpecValidationResult]) {
     }
 }
 
+fn print_plan_summary(summary: &plan::PlanSummary) {
+    println!("Plan: {}", summary.plan);
+    match &summary.approved_by {
+        Some(approval) => println!("Status: {} (approved by {})", summary.status, approval),
+        None => println!("Status: {}", summary.status),
+    }
+    println!(
+        "Tasks: {} of {} done",
+        summary.tasks.done, summary.tasks.total
+    );
+    if summary.features.is_empty() {
+        println!();
+        println!("No delta specs.");
+    }
+    for feature in &summary.features {
+        println!();
+        let kind = if feature.is_new {
+            "new feature"
+        } else {
+            "changed"
+        };
+        println!("{} ({})", feature.feature, kind);
+        for (label, titles) in [
+            ("NEW", &feature.new),
+            ("CHANGED", &feature.changed),
+            ("REMOVED", &feature.removed),
+        ] {
+            for title in titles {
+                println!("  {}: {}", label, title);
+            }
+        }
+        for other in &feature.other {
+            println!("  {}", other);
+        }
+    }
+    println!();
+    if summary.adr_promotions.is_empty() {
+        println!("ADR promotions: none");
+    } else {
+        println!("ADR promotions:");
+        for title in &summary.adr_promotions {
+            println!("  {}", title);
+        }
+    }
+}
+
+fn print_tasks(plan_name: &str, tasks: &[plan_tasks::PlanTask], pending_only: bool) {
+    if tasks.is_empty() {
+        println!("No tasks in plan '{}'.", plan_name);
+        return;
+    }
+    let done = tasks.iter().filter(|task| task.done).count();
+    println!(
+        "Tasks for plan '{}': {} of {} done",
+        plan_name,
+        done,
+        tasks.len()
+    );
+    for task in tasks.iter().filter(|task| !(pending_only && task.done)) {
+        let checkbox = if task.done { "[x]" } else { "[ ]" };
+        let id = task
+            .id
+            .as_deref()
+            .map(|id| format!("{id} "))
+            .unwrap_or_default();
+        let tag = if task.expert { " [expert]" } else { "" };
+        println!("  {} {}{}{}", checkbox, id, task.description, tag);
+        for link in &task.scenarios {
+            println!("        implements {}", link);
+        }
+    }
+}
+
 fn handle_plan_command(command: cli::PlanCommands) -> ExitCode {
     let base = PathBuf::from("specs");
 
@@ -106,6 +206,9 @@ fn handle_plan_command(command: cli::PlanCommands) -> ExitCode {
                     }
 
                     print_spec_warnings(&result.spec_validation_warnings);
+                    for warn in &result.warnings {
+                        println!("  WARN: {}", warn);
+                    }
                     for warn in &result.decision_log_warnings {
                         println!("  WARN (decision-log.md): {}", warn);
                     }
@@ -136,6 +239,9 @@ fn handle_plan_command(command: cli::PlanCommands) -> ExitCode {
                     }
 
                     print_spec_warnings(&result.spec_validation_warnings);
+                    for warn in &result.warnings {
+                        println!("  WARN: {}", warn);
+                    }
                     ExitCode::from(1)
                 }
             }
@@ -144,6 +250,174 @@ fn handle_plan_command(command: cli::PlanCommands) -> ExitCode {
                 ExitCode::from(1)
             }
         },
+        cli::PlanCommands::Approve { plan_name, by } => {
+            let approved = record::record_date(None)
+                .map_err(|e| e.to_string())
+                .and_then(|date| {
+                    plan_status::approve_plan(&base, &plan_name, &by, date)
+                        .map_err(|e| e.to_string())
+                });
+            match approved {
+                Ok(()) => {
+                    println!("Approved plan '{}' (by {}).", plan_name, by.trim());
+                    ExitCode::SUCCESS
+                }
+                Err(e) => {
+                    println!("ERROR: {}", e);
+                    ExitCode::from(1)
+                }
+            }
+        }
+        cli::PlanCommands::Status { plan_name, status } => {
+            let target = match status {
+                cli::PlanStatusTarget::Implemented => plan_status::PlanStatus::Implemented,
+                cli::PlanStatusTarget::Verified => plan_status::PlanStatus::Verified,
+            };
+            match plan_status::advance_plan(&base, &plan_name, target) {
+                Ok(()) => {
+                    println!("Plan '{}' is now {}.", plan_name, target);
+                    ExitCode::SUCCESS
+                }
+                Err(e) => {
+                    println!("ERROR: {}", e);
+                    ExitCode::from(1)
+                }
+            }
+        }
+        cli::PlanCommands::Conflicts => {
+            let overlaps = plan::find_plan_overlaps(&base);
+            if overlaps.is_empty() {
+                println!("No active plans touch the same scenarios.");
+            } else {
+                println!("Found {} overlap(s) between active plans:", overlaps.len());
+                for overlap in &overlaps {
+                    println!("  {}: Scenario '{}'", overlap.feature, overlap.scenario);
+                    for touch in [&overlap.first, &overlap.second] {
+                        println!("    {}: {}", touch.plan, touch.kind);
+                    }
+                }
+                println!(
+                    "Recording both plans of a pair may conflict or overwrite, depending on base snapshots."
+                );
+            }
+            ExitCode::SUCCESS
+        }
+        cli::PlanCommands::Snapshot { plan_name, refresh } => {
+            match record::snapshot_bases(&base, &plan_name, refresh) {
+                Ok(features) => {
+                    if features.is_empty() {
+                        println!("No base snapshots written for plan '{}'.", plan_name);
+                    } else {
+                        println!("Snapshotted base for {} feature(s):", features.len());
+                        for feature in &features {
+                            println!("  {}", feature);
+                        }
+                    }
+                    ExitCode::SUCCESS
+                }
+                Err(e) => {
+                    println!("{}", e);
+                    ExitCode::from(1)
+                }
+            }
+        }
+        cli::PlanCommands::Show { plan_name, format } => {
+            let summary = plan::summarize_plan(&base, &plan_name).map_err(|e| e.to_string());
+            match (summary, format) {
+                (Ok(summary), cli::OutputFormat::Text) => {
+                    print_plan_summary(&summary);
+                    ExitCode::SUCCESS
+                }
+                (Ok(summary), cli::OutputFormat::Json) => {
+                    match serde_json::to_string_pretty(&summary) {
+                        Ok(json) => {
+                            println!("{}", json);
+                            ExitCode::SUCCESS
+                        }
+                        Err(e) => {
+                            println!("{}", e);
+                            ExitCode::from(1)
+                        }
+                    }
+                }
+                (Err(e), _) => {
+                    println!("{}", e);
+                    ExitCode::from(1)
+                }
+            }
+        }
+        cli::PlanCommands::Preview {
+            plan_name,
+            feature,
+            annotate,
+        } => match record::preview_feature(&base, &plan_name, &feature) {
+            Ok(preview) => {
+                if annotate {
+                    print!("{}", preview.annotated());
+                } else {
+                    print!("{}", preview.merged);
+                }
+                ExitCode::SUCCESS
+            }
+            Err(e) => {
+                println!("{}", e);
+                ExitCode::from(1)
+            }
+        },
+        cli::PlanCommands::Tasks { plan_name, pending } => {
+            match plan::load_tasks(&base, &plan_name) {
+                Ok((tasks, errors)) => {
+                    print_tasks(&plan_name, &tasks, pending);
+                    if errors.is_empty() {
+                        return ExitCode::SUCCESS;
+                    }
+                    println!("\nMalformed task lines (not listed):");
+                    for error in &errors {
+                        println!("  ERROR: {}", error);
+                    }
+                    ExitCode::from(1)
+                }
+                Err(e) => {
+                    println!("{}", e);
+                    ExitCode::from(1)
+                }
+            }
+        }
+        cli::PlanCommands::New {
+            plan_name,
+            features,
+        } => {
+            let created = record::record_date(None)
+                .map_err(|e| e.to_string())
+                .and_then(|date| {
+                    scaffold::new_plan(&base, &plan_name, &features, date)
+                        .map_err(|e| e.to_string())
+                });
+            match created {
+                Ok(plan) => {
+                    println!(
+                        "Created plan '{}' in {}:",
+                        plan_name,
+                        plan.plan_dir.display()
+                    );
+                    println!("  plan.md");
+                    println!("  decision-log.md");
+                    for feature in &plan.features {
+                        let kind = if feature.is_new {
+                            "new feature"
+                        } else {
+                            "seeded from permanent spec"
+                        };
+                        println!("  {}/spec.md ({})", feature.feature, kind);
+                    }
+                    ExitCode::SUCCESS
+                }
+                Err(e) => {
+                    println!("ERROR: {}", e);
+                    ExitCode::from(1)
+                }
+            }
+        }
     }
 }
 
@@ -332,85 +606,360 @@ fn print_validation_results(
 fn handle_decision_log_command(command: cli::DecisionLogCommands) -> ExitCode {
     let log_path = PathBuf::from("specs/decision-log.md");
     match command {
-        cli::DecisionLogCommands::Validate => match std::fs::read_to_string(&log_path) {
-            Err(_) => {
-                println!("Error: decision-log.md not found at {}", log_path.display());
-                ExitCode::from(1)
+        cli::DecisionLogCommands::Validate { cross_check } => {
+            match std::fs::read_to_string(&log_path) {
+                Err(_) => {
+                    println!("Error: decision-log.md not found at {}", log_path.display());
+                    ExitCode::from(1)
+                }
+                Ok(content) => {
+                    let mut result = validate::decision_log::validate_permanent_log(&content);
+                    if cross_check {
+                        let plans = validate::decision_log::cross_check_plans(
+                            &PathBuf::from("specs"),
+                            &content,
+                        );
+                        result.errors.extend(plans.errors);
+                        result.warnings.extend(plans.warnings);
+                    }
+                    if result.is_success() {
+                        println!("Permanent decision log validation passed.");
+                        for warn in &result.warnings {
+                            println!("  WARN: {}", warn);
+                        }
+                        ExitCode::SUCCESS
+                    } else {
+                        println!("Permanent decision log validation failed:");
+                        for error in &result.errors {
+                            println!("  ERROR: {}", error);
+                        }
+                        for warn in &result.warnings {
+                            println!("  WARN: {}", warn);
+                        }
+                        ExitCode::from(1)
+                    }
+                }
             }
-            Ok(content) => {
-                let result = validate::decision_log::validate_permanent_log(&content);
-                if result.is_success() {
-                    println!("Permanent decision log validation passed.");
-                    for warn in &result.warnings {
-                        println!("  WARN: {}", warn);
+        }
+        cli::DecisionLogCommands::List { status } => {
+            let Some(log) = load_permanent_log(&log_path) else {
+                return ExitCode::from(1);
+            };
+            let wanted = |adr: &decision_log::Adr| match (&status, &adr.status) {
+                (None, _) => true,
+                (Some(filter), Some(status)) => {
+                    status.to_lowercase().starts_with(&filter.to_lowercase())
+                }
+                (Some(_), None) => false,
+            };
+            for adr in log.adrs.iter().filter(|adr| wanted(adr)) {
+                println!(
+                    "{}  {}  {}  {}",
+                    adr.id(),
+                    adr.date.as_deref().unwrap_or("-"),
+                    adr.status.as_deref().unwrap_or("-"),
+                    adr.title
+                );
+            }
+            ExitCode::SUCCESS
+        }
+        cli::DecisionLogCommands::Show { id } => {
+            let Some(log) = load_permanent_log(&log_path) else {
+                return ExitCode::from(1);
+            };
+            match log.find(&id) {
+                Some(adr) => {
+                    print!("{}", adr.render());
+                    let chain = log.supersession_chain(adr);
+                    if let [_, .., current] = chain.as_slice() {
+                        let ids: Vec<String> = chain.iter().map(|adr| adr.id()).collect();
+                        println!(
+                            "\nSupersession: {} ({})",
+                            ids.join(" → "),
+                            current.status.as_deref().unwrap_or("no status")
+                        );
                     }
                     ExitCode::SUCCESS
-                } else {
-                    println!("Permanent decision log validation failed:");
-                    for error in &result.errors {
-                        println!("  ERROR: {}", error);
+                }
+                None => {
+                    eprintln!("ERROR: {} not found in {}", id, log_path.display());
+                    ExitCode::from(1)
+                }
+            }
+        }
+        cli::DecisionLogCommands::Search { query, limit } => {
+            match search::search_decisions(&query, limit) {
+                Ok(results) if results.is_empty() => {
+                    println!("No matches found.");
+                    ExitCode::SUCCESS
+                }
+                Ok(results) => {
+                    for result in results {
+                        println!(
+                            "{}: {} [{}] (score: {:.3})",
+                            result.id, result.title, result.status, result.score
+                        );
                     }
-                    for warn in &result.warnings {
-                        println!("  WARN: {}", warn);
+                    ExitCode::SUCCESS
+                }
+                Err(e) => {
+                    println!("Error: {}", e);
+                    ExitCode::from(1)
+                }
+            }
+        }
+        cli::DecisionLogCommands::Promote { plan_name, date } => {
+            let base = PathBuf::from("specs");
+            let promoted = record::record_date(date)
+                .map_err(|e| e.to_string())
+                .and_then(|date| {
+                    promote::promote_plan(&base, &plan_name, date).map_err(|e| e.to_string())
+                });
+            match promoted {
+                Ok(adrs) if adrs.is_empty() => {
+                    println!("No decisions to promote from plan '{}'.", plan_name);
+                    ExitCode::SUCCESS
+                }
+                Ok(adrs) => {
+                    println!("Promoted to specs/decision-log.md:");
+                    for adr in &adrs {
+                        println!("  ✓ {}", adr);
                     }
+                    ExitCode::SUCCESS
+                }
+                Err(e) => {
+                    eprintln!("ERROR: {}", e);
                     ExitCode::from(1)
                 }
             }
+        }
+        cli::DecisionLogCommands::Renumber { dry_run } => {
+            handle_renumber_command(&log_path, dry_run)
+        }
+        // Madr is the only layout so far
+        cli::DecisionLogCommands::Export { dir, format: _ } => {
+            match madr::export_madr(&PathBuf::from("specs"), &dir) {
+                Ok(written) => {
+                    println!("Exported {} ADR(s) to {}:", written.len(), dir.display());
+                    for path in &written {
+                        println!("  ✓ {}", path.display());
+                    }
+                    ExitCode::SUCCESS
+                }
+                Err(e) => {
+                    eprintln!("ERROR: {}", e);
+                    ExitCode::from(1)
+                }
+            }
+        }
+        cli::DecisionLogCommands::Import {
+            dir,
+            format: _,
+            plan,
+            force,
+        } => match madr::import_madr(&PathBuf::from("specs"), &dir, &plan, force) {
+            Ok(imported) => {
+                println!("Imported into {}:", log_path.display());
+                for adr in &imported {
+                    println!("  ✓ {}", adr);
+                }
+                ExitCode::SUCCESS
+            }
+            Err(e) => {
+                eprintln!("ERROR: {}", e);
+                ExitCode::from(1)
+            }
         },
     }
 }
 
-fn handle_record_command(plan_name: &str) -> ExitCode {
+fn handle_renumber_command(log_path: &std::path::Path, dry_run: bool) -> ExitCode {
+    let Ok(content) = std::fs::read_to_string(log_path) else {
+        eprintln!("ERROR: decision-log.md not found at {}", log_path.display());
+        return ExitCode::from(1);
+    };
+    let renumbering = decision_log::renumber_adrs(&content);
+    if renumbering.changes.is_empty() {
+        println!("ADR numbering is already sequential.");
+        return ExitCode::SUCCESS;
+    }
+
+    for change in &renumbering.changes {
+        println!("@@ line {} @@", change.line);
+        println!("-{}", change.before);
+        println!("+{}", change.after);
+    }
+    for number in &renumbering.ambiguous {
+        println!(
+            "WARN: ADR-{:03} numbered more than one ADR; references to it now point to the first",
+            number
+        );
+    }
+
+    if dry_run {
+        println!("Dry run: {} not written.", log_path.display());
+        return ExitCode::SUCCESS;
+    }
+    if let Err(e) = std::fs::write(log_path, &renumbering.content) {
+        eprintln!("ERROR: Failed to write {}: {}", log_path.display(), e);
+        return ExitCode::from(1);
+    }
+    println!(
+        "Rewrote {} line(s) in {}.",
+        renumbering.changes.len(),
+        log_path.display()
+    );
+    ExitCode::SUCCESS
+}
+
+fn load_permanent_log(log_path: &std::path::Path) -> Option<decision_log::PermanentLog> {
+    match std::fs::read_to_string(log_path) {
+        Ok(content) => Some(decision_log::PermanentLog::parse(&content)),
+        Err(_) => {
+            eprintln!("ERROR: decision-log.md not found at {}", log_path.display());
+            None
+        }
+    }
+}
+
+fn handle_record_command(plan_name: &str, options: record::RecordOptions) -> ExitCode {
     let base = PathBuf::from("specs");
 
-    match record::record_plan(&base, plan_name) {
-        Ok(features) => {
-            println!("Recorded plan '{}' to specs/_recorded/", plan_name);
-            for feature in &features {
+    match record::record_plan_with(&base, plan_name, &options) {
+        Ok(outcome) => {
+            if outcome.remaining.is_empty() {
+                println!("Recorded plan '{}' to specs/_recorded/", plan_name);
+            } else {
+                println!("Recorded part of plan '{}' to specs/_recorded/", plan_name);
+            }
+            for feature in &outcome.features {
                 println!("  ✓ {}", feature);
             }
+            for operation in &outcome.operations {
+                println!("  ✓ {}", operation);
+            }
+            for change in &outcome.reference_changes {
+                println!("  • {}", change);
+            }
+            for adr in &outcome.adrs {
+                println!("  ✓ {} (specs/decision-log.md)", adr);
+            }
+            for feature in &outcome.without_base {
+                println!(
+                    "Warning: {}: no base.md snapshot, so the delta was applied to the current spec without a three-way merge (run `speq plan snapshot` when planning)",
+                    feature
+                );
+            }
+            if !outcome.remaining.is_empty() {
+                println!("\nLeft in specs/_plans/{}/:", plan_name);
+                for item in &outcome.remaining {
+                    println!("  - {}", item);
+                }
+            }
 
-            // Validate recorded specs
-            println!("\nValidating recorded specs...");
-            let mut has_errors = false;
-            for feature_path in &features {
-                let parts: Vec<&str> = feature_path.splitn(2, '/').collect();
-                if parts.len() == 2 {
-                    let fp = feature::FeaturePath::new(parts[0], parts[1]);
-                    match validate::run_feature(&base, &fp) {
-                        Ok(result) => {
-                            if !result.is_success() {
-                                has_errors = true;
-                                println!("  ✗ {} - {} errors", feature_path, result.errors.len());
-                            } else {
-                                println!("  ✓ {}", feature_path);
-                            }
-                        }
-                        Err(e) => {
-                            has_errors = true;
-                            println!("  ✗ {} - {}", feature_path, e);
-                        }
-                    }
+            // Rebuild search index after successful recording
+            println!("\nRebuilding search index...");
+            match search::index_specs(&base) {
+                Ok(count) => {
+                    println!("Indexed {} scenarios.", count);
+                    ExitCode::SUCCESS
+                }
+                Err(e) => {
+                    println!("Warning: Failed to rebuild index: {}", e);
+                    ExitCode::SUCCESS // Still return success since recording succeeded
                 }
             }
+        }
+        Err(record::RecordError::InvalidMergedSpecs { failures }) => {
+            eprintln!(
+                "ERROR: Plan '{}' not recorded: merged specs would fail validation",
+                plan_name
+            );
+            for failure in &failures {
+                eprintln!();
+                eprintln!("  {} ({}):", failure.delta_path, failure.feature);
+                for error in &failure.errors {
+                    eprintln!("    ERROR: {}", error);
+                }
+            }
+            ExitCode::from(1)
+        }
+        Err(record::RecordError::InvalidFeatureOperations { errors }) => {
+            eprintln!(
+                "ERROR: Plan '{}' not recorded: invalid feature operations",
+                plan_name
+            );
+            for error in &errors {
+                eprintln!("  {}", error);
+            }
+            ExitCode::from(1)
+        }
+        Err(record::RecordError::MergeConflicts { path, conflicts }) => {
+            eprintln!(
+                "ERROR: Plan '{}' not recorded: {} conflicts with changes recorded since the plan's base snapshot",
+                plan_name, path
+            );
+            for conflict in &conflicts {
+                eprintln!();
+                eprintln!("  {}:", conflict.section);
+                eprintln!("{}", conflict.rendered);
+            }
+            ExitCode::from(1)
+        }
+        Err(e) => {
+            eprintln!("ERROR: {}", e);
+            ExitCode::from(1)
+        }
+    }
+}
 
-            if has_errors {
-                ExitCode::from(1)
-            } else {
-                // Rebuild search index after successful recording
-                println!("\nRebuilding search index...");
-                match search::index_specs(&base) {
-                    Ok(count) => {
-                        println!("Indexed {} scenarios.", count);
-                        ExitCode::SUCCESS
-                    }
-                    Err(e) => {
-                        println!("Warning: Failed to rebuild index: {}", e);
-                        ExitCode::SUCCESS // Still return success since recording succeeded
-                    }
+fn handle_revert_command(archive_name: &str) -> ExitCode {
+    let base = PathBuf::from("specs");
+
+    match revert::revert_plan(&base, archive_name) {
+        Ok(outcome) => {
+            println!(
+                "Reverted '{}' and restored plan '{}' to specs/_plans/",
+                archive_name, outcome.plan_name
+            );
+            for feature in &outcome.features {
+                println!("  ✓ {}", feature);
+            }
+            for operation in &outcome.operations {
+                println!("  ✓ {}", operation);
+            }
+            for adr in &outcome.removed_adrs {
+                println!("  ✓ removed {} (specs/decision-log.md)", adr);
+            }
+            for adr in &outcome.kept_adrs {
+                println!(
+                    "Warning: {} stays in specs/decision-log.md because later ADRs follow or cite it",
+                    adr
+                );
+            }
+
+            println!("\nRebuilding search index...");
+            match search::index_specs(&base) {
+                Ok(count) => {
+                    println!("Indexed {} scenarios.", count);
+                    ExitCode::SUCCESS
+                }
+                Err(e) => {
+                    println!("Warning: Failed to rebuild index: {}", e);
+                    ExitCode::SUCCESS
                 }
             }
         }
+        Err(revert::RevertError::LaterChanges { conflicts }) => {
+            eprintln!(
+                "ERROR: '{}' not reverted: later changes touch what it recorded",
+                archive_name
+            );
+            for conflict in &conflicts {
+                eprintln!("  {}", conflict);
+            }
+            ExitCode::from(1)
+        }
         Err(e) => {
             eprintln!("ERROR: {}", e);
             ExitCode::from(1)
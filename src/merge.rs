@@ -0,0 +1,423 @@
+use crate::validate::parser;
+
+/// A region where the plan and the current permanent spec both changed the base differently.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MergeConflict {
+    pub section: String,
+    pub rendered: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MergeOutcome {
+    pub content: String,
+    pub conflicts: Vec<MergeConflict>,
+}
+
+impl MergeOutcome {
+    pub fn is_clean(&self) -> bool {
+        self.conflicts.is_empty()
+    }
+}
+
+struct SpecSections {
+    preamble: String,
+    scenarios: Vec<(String, String)>,
+}
+
+impl SpecSections {
+    fn scenario(&self, title: &str) -> Option<&str> {
+        self.scenarios
+            .iter()
+            .find(|(t, _)| t == title)
+            .map(|(_, text)| text.as_str())
+    }
+}
+
+/// Three-way merge of feature specs at scenario granularity.
+///
+/// `ours` is the plan's intended result (its delta applied to `base`), `theirs` is the
+/// permanent spec as it is now. The preamble (heading, description, Background) and each
+/// scenario are merged independently; a region changed on both sides becomes a conflict.
+pub fn three_way_merge(base: &str, ours: &str, theirs: &str) -> MergeOutcome {
+    let base_sections = split_sections(base);
+    let our_sections = split_sections(ours);
+    let their_sections = split_sections(theirs);
+
+    let mut conflicts = Vec::new();
+
+    let preamble = merge_region(
+        "preamble",
+        Some(&base_sections.preamble),
+        Some(&our_sections.preamble),
+        Some(&their_sections.preamble),
+        &mut conflicts,
+    )
+    .unwrap_or_default();
+
+    // Keep the current order; scenarios only the plan introduced follow the scenario
+    // that precedes them in the plan
+    let mut titles: Vec<&str> = their_sections
+        .scenarios
+        .iter()
+        .map(|(t, _)| t.as_str())
+        .collect();
+    let mut previous: Option<&str> = None;
+    for (title, _) in &our_sections.scenarios {
+        if !titles.contains(&title.as_str()) {
+            let index = previous
+                .and_then(|p| titles.iter().position(|t| *t == p))
+                .map_or(0, |i| i + 1);
+            titles.insert(index, title);
+        }
+        previous = Some(title);
+    }
+
+    let mut chunks = Vec::new();
+    for title in titles {
+        let merged = merge_region(
+            &format!("Scenario '{}'", title),
+            base_sections.scenario(title),
+            our_sections.scenario(title),
+            their_sections.scenario(title),
+            &mut conflicts,
+        );
+        if let Some(chunk) = merged {
+            chunks.push(chunk);
+        }
+    }
+
+    let mut content = preamble.trim_end().to_string();
+    for chunk in chunks {
+        content.push_str("\n\n");
+        content.push_str(chunk.trim_end());
+    }
+    content.push('\n');
+
+    MergeOutcome { content, conflicts }
+}
+
+fn merge_region(
+    section: &str,
+    base: Option<&str>,
+    ours: Option<&str>,
+    theirs: Option<&str>,
+    conflicts: &mut Vec<MergeConflict>,
+) -> Option<String> {
+    let (base_n, ours_n, theirs_n) = (
+        base.map(str::trim_end),
+        ours.map(str::trim_end),
+        theirs.map(str::trim_end),
+    );
+
+    if ours_n == theirs_n || ours_n == base_n {
+        return theirs.map(str::to_string);
+    }
+    if theirs_n == base_n {
+        return ours.map(str::to_string);
+    }
+
+    let rendered = render_conflict(base_n, ours_n, theirs_n);
+    conflicts.push(MergeConflict {
+        section: section.to_string(),
+        rendered: rendered.clone(),
+    });
+    Some(rendered)
+}
+
+fn render_conflict(base: Option<&str>, ours: Option<&str>, theirs: Option<&str>) -> String {
+    let side = |s: Option<&str>| s.map(|t| format!("{}\n", t)).unwrap_or_default();
+    format!(
+        "<<<<<<< current\n{}||||||| base\n{}=======\n{}>>>>>>> plan",
+        side(theirs),
+        side(base),
+        side(ours)
+    )
+}
+
+/// Render `after` with the sections that differ from `before` marked for review.
+///
+/// Sections are the text before the first `##` heading, each `##` section up to the first
+/// scenario, and each scenario. A section new in `after` is wrapped in
+/// `<!-- PREVIEW:NEW -->`, a changed one in `<!-- PREVIEW:CHANGED -->`, and a dropped one
+/// leaves a `<!-- PREVIEW:REMOVED <heading> -->` note where it was.
+pub fn annotate_changes(before: &str, after: &str) -> String {
+    let before_sections = split_review_sections(before);
+    let after_sections = split_review_sections(after);
+    let text_before = |key: &str| {
+        before_sections
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, text)| text.trim_end())
+    };
+    let in_after = |key: &str| after_sections.iter().any(|(k, _)| k == key);
+
+    // Removed sections are noted after the closest preceding section that survives
+    let mut removed_after: Vec<(Option<&str>, &str)> = Vec::new();
+    let mut survivor: Option<&str> = None;
+    for (key, _) in &before_sections {
+        if in_after(key) {
+            survivor = Some(key);
+        } else {
+            removed_after.push((survivor, key));
+        }
+    }
+    let removed_notes = |anchor: Option<&str>| {
+        removed_after
+            .iter()
+            .filter(|(a, _)| *a == anchor)
+            .map(|(_, key)| format!("<!-- PREVIEW:REMOVED {} -->\n\n", key))
+            .collect::<String>()
+    };
+
+    let mut annotated = removed_notes(None);
+    for (key, text) in &after_sections {
+        let body = text.trim_end();
+        let kind = match text_before(key) {
+            None => Some("NEW"),
+            Some(old) if old != body => Some("CHANGED"),
+            Some(_) => None,
+        };
+        match kind {
+            Some(kind) => annotated.push_str(&format!(
+                "<!-- PREVIEW:{kind} -->\n{body}\n<!-- /PREVIEW:{kind} -->\n\n"
+            )),
+            None => annotated.push_str(&format!("{body}\n\n")),
+        }
+        annotated.push_str(&removed_notes(Some(key)));
+    }
+
+    format!("{}\n", annotated.trim_end())
+}
+
+/// `(key, text)` pairs: `""` for the text before the first `##` heading, the heading for
+/// each later part of the preamble, `Scenario: <title>` for scenarios. Blank parts are
+/// left out.
+fn split_review_sections(content: &str) -> Vec<(String, String)> {
+    let sections = split_sections(content);
+    let mut parts: Vec<(String, String)> = vec![(String::new(), String::new())];
+    for line in sections.preamble.split_inclusive('\n') {
+        if line.starts_with("## ") {
+            parts.push((line.trim().to_string(), String::new()));
+        }
+        if let Some((_, text)) = parts.last_mut() {
+            text.push_str(line);
+        }
+    }
+    parts.extend(
+        sections
+            .scenarios
+            .into_iter()
+            .map(|(title, text)| (format!("Scenario: {}", title), text)),
+    );
+    parts.retain(|(_, text)| !text.trim().is_empty());
+    parts
+}
+
+fn split_sections(content: &str) -> SpecSections {
+    let scenarios = parser::parse(content)
+        .map(|parsed| parsed.spec.scenarios)
+        .unwrap_or_default();
+
+    let Some(first) = scenarios.first() else {
+        return SpecSections {
+            preamble: content.to_string(),
+            scenarios: Vec::new(),
+        };
+    };
+
+    // Each scenario owns everything up to the next scenario heading
+    let starts: Vec<usize> = scenarios.iter().map(|s| s.span.start).collect();
+    let sections = scenarios
+        .iter()
+        .enumerate()
+        .map(|(i, s)| {
+            let end = starts.get(i + 1).copied().unwrap_or(content.len());
+            (s.name.clone(), content[s.span.start..end].to_string())
+        })
+        .collect();
+
+    SpecSections {
+        preamble: content[..first.span.start].to_string(),
+        scenarios: sections,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: &str = r#"# Feature: Auth
+
+Users authenticate.
+
+## Background
+
+* Accounts exist.
+
+## Scenarios
+
+### Scenario: Login
+
+* *GIVEN* a user
+* *WHEN* they log in
+* *THEN* the system SHALL grant access
+
+### Scenario: Logout
+
+* *GIVEN* a session
+* *WHEN* they log out
+* *THEN* the system SHALL end the session
+"#;
+
+    #[test]
+    fn identical_sides_merge_cleanly() {
+        let outcome = three_way_merge(BASE, BASE, BASE);
+        assert!(outcome.is_clean());
+        assert_eq!(outcome.content, BASE);
+    }
+
+    #[test]
+    fn keeps_changes_to_different_scenarios_from_both_sides() {
+        let ours = BASE.replace("SHALL grant access", "SHALL grant access within 1s");
+        let theirs = BASE.replace("SHALL end the session", "SHALL end all sessions");
+
+        let outcome = three_way_merge(BASE, &ours, &theirs);
+
+        assert!(outcome.is_clean());
+        assert!(outcome.content.contains("within 1s"));
+        assert!(outcome.content.contains("SHALL end all sessions"));
+    }
+
+    #[test]
+    fn keeps_scenario_added_by_other_plan() {
+        let ours = BASE.replace("SHALL grant access", "SHALL grant access within 1s");
+        let theirs = format!(
+            "{}\n### Scenario: Reset password\n\n* *GIVEN* a user\n* *WHEN* they reset\n* *THEN* the system SHALL send a mail\n",
+            BASE
+        );
+
+        let outcome = three_way_merge(BASE, &ours, &theirs);
+
+        assert!(outcome.is_clean());
+        assert!(outcome.content.contains("within 1s"));
+        assert!(outcome.content.contains("### Scenario: Reset password"));
+    }
+
+    #[test]
+    fn appends_scenarios_added_by_plan() {
+        let ours = format!(
+            "{}\n### Scenario: Lockout\n\n* *GIVEN* failures\n* *WHEN* limit hit\n* *THEN* the system SHALL lock\n",
+            BASE
+        );
+
+        let outcome = three_way_merge(BASE, &ours, BASE);
+
+        assert!(outcome.is_clean());
+        assert!(
+            outcome
+                .content
+                .trim_end()
+                .ends_with("* *THEN* the system SHALL lock")
+        );
+    }
+
+    #[test]
+    fn keeps_plan_placement_of_inserted_scenarios() {
+        let lockout = "### Scenario: Lockout\n\n* *GIVEN* failures\n* *WHEN* limit hit\n* *THEN* the system SHALL lock\n\n";
+        let ours = BASE.replace(
+            "### Scenario: Logout",
+            &format!("{lockout}### Scenario: Logout"),
+        );
+        let theirs = BASE.replace("SHALL end the session", "SHALL end all sessions");
+
+        let outcome = three_way_merge(BASE, &ours, &theirs);
+
+        assert!(outcome.is_clean());
+        let lockout_at = outcome.content.find("### Scenario: Lockout").unwrap();
+        let logout_at = outcome.content.find("### Scenario: Logout").unwrap();
+        assert!(lockout_at < logout_at);
+    }
+
+    #[test]
+    fn reports_conflict_when_both_sides_change_same_scenario() {
+        let ours = BASE.replace("SHALL grant access", "SHALL grant access within 1s");
+        let theirs = BASE.replace("SHALL grant access", "SHALL grant read access");
+
+        let outcome = three_way_merge(BASE, &ours, &theirs);
+
+        assert_eq!(outcome.conflicts.len(), 1);
+        assert_eq!(outcome.conflicts[0].section, "Scenario 'Login'");
+        let rendered = &outcome.conflicts[0].rendered;
+        assert!(rendered.starts_with("<<<<<<< current\n"));
+        assert!(rendered.contains("SHALL grant read access\n||||||| base\n"));
+        assert!(rendered.contains("SHALL grant access\n=======\n"));
+        assert!(rendered.ends_with("within 1s\n>>>>>>> plan"));
+        assert!(outcome.content.contains(rendered));
+    }
+
+    #[test]
+    fn reports_conflict_when_plan_changes_scenario_removed_since() {
+        let ours = BASE.replace("SHALL end the session", "SHALL end all sessions");
+        let theirs = BASE
+            .split("### Scenario: Logout")
+            .next()
+            .unwrap()
+            .to_string();
+
+        let outcome = three_way_merge(BASE, &ours, &theirs);
+
+        assert_eq!(outcome.conflicts.len(), 1);
+        assert_eq!(outcome.conflicts[0].section, "Scenario 'Logout'");
+        assert!(
+            outcome.conflicts[0]
+                .rendered
+                .starts_with("<<<<<<< current\n||||||| base\n")
+        );
+    }
+
+    #[test]
+    fn removal_on_one_side_applies_when_other_side_unchanged() {
+        let ours = BASE
+            .split("### Scenario: Logout")
+            .next()
+            .unwrap()
+            .to_string();
+        let theirs = BASE.replace("Users authenticate.", "Users authenticate with passwords.");
+
+        let outcome = three_way_merge(BASE, &ours, &theirs);
+
+        assert!(outcome.is_clean());
+        assert!(!outcome.content.contains("Logout"));
+        assert!(outcome.content.contains("with passwords"));
+    }
+
+    #[test]
+    fn annotates_new_changed_and_removed_sections() {
+        let after = BASE
+            .replace("Users authenticate.", "Users authenticate with passwords.")
+            .replace(
+                "### Scenario: Logout\n\n* *GIVEN* a session\n* *WHEN* they log out\n* *THEN* the system SHALL end the session\n",
+                "### Scenario: Lockout\n\n* *GIVEN* five failures\n* *WHEN* they log in\n* *THEN* the system SHALL refuse\n",
+            );
+
+        let annotated = annotate_changes(BASE, &after);
+
+        assert!(annotated.starts_with(
+            "<!-- PREVIEW:CHANGED -->\n# Feature: Auth\n\nUsers authenticate with passwords.\n<!-- /PREVIEW:CHANGED -->\n\n## Background"
+        ));
+        assert!(annotated.contains("\n### Scenario: Login\n"));
+        assert!(!annotated.contains("<!-- PREVIEW:CHANGED -->\n### Scenario: Login"));
+        assert!(annotated.contains(
+            "<!-- PREVIEW:REMOVED Scenario: Logout -->\n\n<!-- PREVIEW:NEW -->\n### Scenario: Lockout\n"
+        ));
+        assert!(annotated.ends_with("SHALL refuse\n<!-- /PREVIEW:NEW -->\n"));
+    }
+
+    #[test]
+    fn annotates_everything_as_new_without_a_previous_spec() {
+        let annotated = annotate_changes("", BASE);
+
+        assert!(annotated.starts_with("<!-- PREVIEW:NEW -->\n# Feature: Auth\n"));
+        assert_eq!(annotated.matches("<!-- PREVIEW:NEW -->").count(), 5);
+    }
+}
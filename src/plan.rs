@@ -1,7 +1,16 @@
-use std::path::Path;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 use thiserror::Error;
 
-use crate::record::find_delta_specs;
+use crate::decision_log::parse_plan_decisions;
+use crate::feature_ops;
+use crate::plan_status::{self, PlanStatus};
+use crate::plan_tasks::{self, PlanTask, TaskError};
+use crate::record::{
+    BASE_SNAPSHOT_FILE, DeltaKind, check_delta_targets, find_delta_specs, is_delta_marker,
+    parse_delta_close, parse_delta_open, parse_deltas,
+};
 use crate::validate;
 use crate::validate::report::{ValidationError, ValidationWarning};
 
@@ -15,6 +24,12 @@ pub enum PlanValidationError {
 
     #[error("Failed to read file: {path}")]
     FileReadError { path: String },
+
+    #[error("{path}: {message}")]
+    InvalidDelta { path: String, message: String },
+
+    #[error("{0}")]
+    InvalidPlanMd(String),
 }
 
 #[derive(Debug)]
@@ -49,6 +64,7 @@ pub struct PlanValidationResult {
     pub spec_validation_errors: Vec<SpecValidationResult>,
     pub spec_validation_warnings: Vec<SpecValidationResult>,
     pub decision_log_warnings: Vec<String>,
+    pub warnings: Vec<String>,
 }
 
 impl PlanValidationResult {
@@ -66,6 +82,10 @@ impl PlanValidationResult {
         self.errors.push(error);
     }
 
+    pub fn add_warning(&mut self, warning: String) {
+        self.warnings.push(warning);
+    }
+
     pub fn add_delta_marker_error(&mut self, error: DeltaMarkerError) {
         self.delta_marker_errors.push(error);
     }
@@ -109,6 +129,111 @@ pub fn list_plans(base: &Path) -> Vec<String> {
     plans
 }
 
+/// A plan's delta block for a scenario.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlanTouch {
+    pub plan: String,
+    pub kind: DeltaKind,
+}
+
+/// Two active plans with delta blocks for the same scenario of the same feature. Recording
+/// both may conflict or overwrite, depending on the plans' base snapshots.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlanOverlap {
+    /// `domain/feature`
+    pub feature: String,
+    pub scenario: String,
+    pub first: PlanTouch,
+    pub second: PlanTouch,
+}
+
+impl PlanOverlap {
+    /// This plan's touch and the other plan's, if `plan` is one of the two
+    pub fn sides(&self, plan: &str) -> Option<(&PlanTouch, &PlanTouch)> {
+        if self.first.plan == plan {
+            Some((&self.first, &self.second))
+        } else if self.second.plan == plan {
+            Some((&self.second, &self.first))
+        } else {
+            None
+        }
+    }
+}
+
+impl std::fmt::Display for PlanOverlap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: scenario '{}' is {} by '{}' and {} by '{}'",
+            self.feature,
+            self.scenario,
+            self.first.kind,
+            self.first.plan,
+            self.second.kind,
+            self.second.plan
+        )
+    }
+}
+
+/// Find pairs of active plans whose `NEW`, `CHANGED` or `REMOVED` blocks name the same
+/// scenario of the same feature, ordered by feature, scenario and plan name.
+///
+/// Delta specs that cannot be read or parsed are skipped; `speq plan validate` reports them.
+pub fn find_plan_overlaps(base: &Path) -> Vec<PlanOverlap> {
+    let mut touches: BTreeMap<(String, String), Vec<PlanTouch>> = BTreeMap::new();
+
+    for plan in list_plans(base) {
+        let plan_dir = base.join("_plans").join(&plan);
+        let Ok(delta_specs) = find_delta_specs(&plan_dir) else {
+            continue;
+        };
+        for spec_path in delta_specs {
+            let Some(feature) = spec_path
+                .strip_prefix(&plan_dir)
+                .ok()
+                .and_then(Path::parent)
+                .map(|dir| dir.display().to_string())
+            else {
+                continue;
+            };
+            let Ok(blocks) = read_file(&spec_path).map(|content| parse_deltas(&content)) else {
+                continue;
+            };
+            for block in blocks.into_iter().flatten() {
+                let scenario_kind = matches!(
+                    block.kind,
+                    DeltaKind::New | DeltaKind::Changed | DeltaKind::Removed
+                );
+                let Some(title) = block.scenario_title.filter(|_| scenario_kind) else {
+                    continue;
+                };
+                let plans = touches.entry((feature.clone(), title)).or_default();
+                if !plans.iter().any(|touch| touch.plan == plan) {
+                    plans.push(PlanTouch {
+                        plan: plan.clone(),
+                        kind: block.kind,
+                    });
+                }
+            }
+        }
+    }
+
+    let mut overlaps = Vec::new();
+    for ((feature, scenario), plans) in touches {
+        for (i, first) in plans.iter().enumerate() {
+            for second in &plans[i + 1..] {
+                overlaps.push(PlanOverlap {
+                    feature: feature.clone(),
+                    scenario: scenario.clone(),
+                    first: first.clone(),
+                    second: second.clone(),
+                });
+            }
+        }
+    }
+    overlaps
+}
+
 pub fn validate_plan(
     base: &Path,
     plan_name: &str,
@@ -134,6 +259,40 @@ pub fn validate_plan(
             path: plan_dir.display().to_string(),
         })?;
 
+    // Check plan.md structure, its task list and the scenarios tasks link to
+    let plan_content = read_file(&plan_md)?;
+    let (tasks, mut task_errors) = plan_tasks::parse_tasks(&plan_content);
+    let (structure_errors, structure_warnings) =
+        plan_tasks::check_structure(&plan_content, plan_name, &tasks);
+    task_errors.extend(structure_errors);
+    task_errors.extend(plan_tasks::check_links(base, &plan_dir, &tasks));
+    task_errors.sort_by_key(|error| error.line);
+    if let Err(error) = plan_status::parse_status(&plan_content) {
+        result.add_error(error.to_string());
+    }
+    for error in task_errors {
+        result.add_error(error.to_string());
+    }
+    for warning in structure_warnings {
+        result.add_warning(warning.to_string());
+    }
+
+    // Check feature operations declared in plan.md
+    let (operations, mut operation_errors) = feature_ops::parse_operations(&plan_content);
+    let delta_features: Vec<String> = delta_specs
+        .iter()
+        .filter_map(|spec_path| spec_path.strip_prefix(&plan_dir).ok()?.parent())
+        .map(|feature_dir| feature_dir.display().to_string())
+        .collect();
+    operation_errors.extend(feature_ops::check_operations(
+        base,
+        &operations,
+        &delta_features,
+    ));
+    for error in operation_errors {
+        result.add_error(error.to_string());
+    }
+
     for spec_path in &delta_specs {
         let relative_path = spec_path
             .strip_prefix(&plan_dir)
@@ -149,7 +308,41 @@ pub fn validate_plan(
                 path: spec_path.display().to_string(),
             })?;
 
-        validate_delta_markers(&content, &relative_path, &mut result);
+        // Check delta blocks against the permanent spec they target, which a moved
+        // feature still has at its old path
+        let permanent_spec = spec_path
+            .strip_prefix(&plan_dir)
+            .ok()
+            .and_then(Path::parent)
+            .map(|feature_dir| {
+                let feature = feature_dir.display().to_string();
+                base.join(feature_ops::current_location(&operations, &feature))
+                    .join("spec.md")
+            })
+            .filter(|p| p.exists());
+
+        validate_delta_markers(
+            &content,
+            &relative_path,
+            permanent_spec.is_none(),
+            &mut result,
+        );
+
+        if let Some(permanent_spec) = permanent_spec {
+            let base_snapshot = spec_path.with_file_name(BASE_SNAPSHOT_FILE);
+            let target = if base_snapshot.exists() {
+                warn_if_base_outdated(
+                    &base_snapshot,
+                    &permanent_spec,
+                    &relative_path,
+                    &mut result,
+                )?;
+                base_snapshot
+            } else {
+                permanent_spec
+            };
+            validate_delta_targets(&content, &target, &relative_path, &mut result)?;
+        }
 
         // Apply standard spec validation
         if let Ok(validation_result) = validate::run(spec_path) {
@@ -157,6 +350,16 @@ pub fn validate_plan(
         }
     }
 
+    // Other active plans touching the same scenarios
+    for overlap in find_plan_overlaps(base) {
+        if let Some((ours, theirs)) = overlap.sides(plan_name) {
+            result.add_warning(format!(
+                "{}: scenario '{}' ({} here) is also {} by plan '{}'; recording both may conflict or overwrite, depending on base snapshots",
+                overlap.feature, overlap.scenario, ours.kind, theirs.kind, theirs.plan
+            ));
+        }
+    }
+
     // Check for optional decision log
     let decision_log_path = plan_dir.join("decision-log.md");
     if decision_log_path.exists() {
@@ -167,51 +370,275 @@ pub fn validate_plan(
         })?;
         let log_result = validate::decision_log::validate_plan_log(&content, plan_name);
         for error in log_result.errors {
-            result.add_error(format!("decision-log.md: {}", error));
+            result.add_error(match error.line() {
+                Some(line) => format!("decision-log.md:{}: {}", line, error),
+                None => format!("decision-log.md: {}", error),
+            });
         }
         result
             .decision_log_warnings
-            .extend(log_result.warnings.into_iter().map(|w| w.to_string()));
+            .extend(log_result.warnings.into_iter().map(|w| match w.line() {
+                Some(line) => format!("line {}: {}", line, w),
+                None => w.to_string(),
+            }));
     }
 
     Ok(result)
 }
 
-fn validate_delta_markers(content: &str, file_path: &str, result: &mut PlanValidationResult) {
-    let marker_types = ["NEW", "CHANGED", "REMOVED"];
+/// Read the `## Tasks` checklist of a plan's plan.md, with the task lines that could not
+/// be parsed.
+pub fn load_tasks(
+    base: &Path,
+    plan_name: &str,
+) -> Result<(Vec<PlanTask>, Vec<TaskError>), PlanValidationError> {
+    let plan_dir = existing_plan_dir(base, plan_name)?;
+    Ok(plan_tasks::parse_tasks(&read_file(
+        &plan_dir.join("plan.md"),
+    )?))
+}
+
+/// What a plan changes, per feature, for `speq plan show`.
+#[derive(Debug, Serialize)]
+pub struct PlanSummary {
+    pub plan: String,
+    pub status: PlanStatus,
+    /// `Approved by:` value, e.g. `Dana on 2026-05-02`
+    pub approved_by: Option<String>,
+    pub features: Vec<FeatureSummary>,
+    /// Titles of plan-log design decisions marked `Promotes to ADR: yes`
+    pub adr_promotions: Vec<String>,
+    pub tasks: TaskCount,
+}
 
-    for marker_type in marker_types {
-        let open_marker = format!("<!-- DELTA:{} -->", marker_type);
-        let close_marker = format!("<!-- /DELTA:{} -->", marker_type);
+#[derive(Debug, Serialize)]
+pub struct FeatureSummary {
+    /// `domain/feature` of the delta spec
+    pub feature: String,
+    /// No permanent spec exists yet
+    pub is_new: bool,
+    pub new: Vec<String>,
+    pub changed: Vec<String>,
+    pub removed: Vec<String>,
+    /// Kinds of the feature-level blocks, e.g. `DESCRIPTION` or `BACKGROUND:NEW`
+    pub other: Vec<String>,
+}
 
-        let mut open_positions: Vec<usize> = Vec::new();
+#[derive(Debug, Serialize)]
+pub struct TaskCount {
+    pub done: usize,
+    pub total: usize,
+}
 
-        for (line_num, line) in content.lines().enumerate() {
-            let trimmed = line.trim();
+/// Summarize a plan's delta specs, ADR promotions and tasks without validating them.
+pub fn summarize_plan(base: &Path, plan_name: &str) -> Result<PlanSummary, PlanValidationError> {
+    let plan_dir = existing_plan_dir(base, plan_name)?;
+    let plan_content = read_file(&plan_dir.join("plan.md"))?;
+    let (tasks, _) = plan_tasks::parse_tasks(&plan_content);
+    let (operations, _) = feature_ops::parse_operations(&plan_content);
 
-            // Only match markers that are standalone (start of trimmed line)
-            // This avoids matching markers inside backticks or other inline content
-            if trimmed.starts_with(&open_marker) {
-                open_positions.push(line_num + 1);
+    let mut delta_specs =
+        find_delta_specs(&plan_dir).map_err(|_| PlanValidationError::FileReadError {
+            path: plan_dir.display().to_string(),
+        })?;
+    delta_specs.sort();
+
+    let mut features = Vec::new();
+    for spec_path in &delta_specs {
+        let Some(feature) = spec_path
+            .strip_prefix(&plan_dir)
+            .ok()
+            .and_then(Path::parent)
+            .map(|dir| dir.display().to_string())
+        else {
+            continue;
+        };
+        let blocks = parse_deltas(&read_file(spec_path)?).map_err(|e| {
+            PlanValidationError::InvalidDelta {
+                path: format!("{}/spec.md", feature),
+                message: e.to_string(),
             }
-            if trimmed.starts_with(&close_marker) && open_positions.pop().is_none() {
-                result.add_error(format!(
+        })?;
+        let permanent = base
+            .join(feature_ops::current_location(&operations, &feature))
+            .join("spec.md");
+
+        let mut summary = FeatureSummary {
+            is_new: !permanent.exists(),
+            feature,
+            new: Vec::new(),
+            changed: Vec::new(),
+            removed: Vec::new(),
+            other: Vec::new(),
+        };
+        for block in blocks {
+            let titles = match block.kind {
+                DeltaKind::New => &mut summary.new,
+                DeltaKind::Changed => &mut summary.changed,
+                DeltaKind::Removed => &mut summary.removed,
+                _ => {
+                    summary.other.push(block.kind.to_string());
+                    continue;
+                }
+            };
+            titles.push(block.scenario_title.unwrap_or_default());
+        }
+        features.push(summary);
+    }
+
+    let decision_log = plan_dir.join("decision-log.md");
+    let adr_promotions = if decision_log.exists() {
+        parse_plan_decisions(&read_file(&decision_log)?)
+            .into_iter()
+            .filter(|decision| decision.promotes)
+            .map(|decision| decision.title)
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    Ok(PlanSummary {
+        plan: plan_name.to_string(),
+        status: plan_status::parse_status(&plan_content)
+            .map_err(|e| PlanValidationError::InvalidPlanMd(e.to_string()))?,
+        approved_by: plan_status::parse_approval(&plan_content),
+        features,
+        adr_promotions,
+        tasks: TaskCount {
+            done: tasks.iter().filter(|task| task.done).count(),
+            total: tasks.len(),
+        },
+    })
+}
+
+fn existing_plan_dir(base: &Path, plan_name: &str) -> Result<PathBuf, PlanValidationError> {
+    let plan_dir = base.join("_plans").join(plan_name);
+    if !plan_dir.exists() {
+        return Err(PlanValidationError::PlanNotFound {
+            name: plan_name.to_string(),
+        });
+    }
+    if !plan_dir.join("plan.md").exists() {
+        return Err(PlanValidationError::PlanMdNotFound);
+    }
+    Ok(plan_dir)
+}
+
+fn read_file(path: &Path) -> Result<String, PlanValidationError> {
+    std::fs::read_to_string(path).map_err(|_| PlanValidationError::FileReadError {
+        path: path.display().to_string(),
+    })
+}
+
+fn warn_if_base_outdated(
+    base_snapshot: &Path,
+    permanent_spec: &Path,
+    file_path: &str,
+    result: &mut PlanValidationResult,
+) -> Result<(), PlanValidationError> {
+    if read_file(base_snapshot)? != read_file(permanent_spec)? {
+        result.add_warning(format!(
+            "{}: permanent spec changed since the plan's base snapshot; record will three-way merge",
+            file_path
+        ));
+    }
+    Ok(())
+}
+
+fn validate_delta_targets(
+    content: &str,
+    target_spec: &Path,
+    file_path: &str,
+    result: &mut PlanValidationResult,
+) -> Result<(), PlanValidationError> {
+    // Malformed markers are already reported by validate_delta_markers
+    let Ok(deltas) = parse_deltas(content) else {
+        return Ok(());
+    };
+
+    let existing = read_file(target_spec)?;
+    let errors = match check_delta_targets(&existing, &deltas) {
+        Ok(errors) => errors,
+        Err(e) => {
+            result.add_error(format!(
+                "{}: permanent spec {} could not be parsed: {}",
+                file_path,
+                target_spec.display(),
+                e
+            ));
+            return Ok(());
+        }
+    };
+
+    for error in errors {
+        result.add_error(format!("{}:{}: {}", file_path, error.line, error));
+    }
+
+    Ok(())
+}
+
+/// Check marker pairing. Empty blocks of a new feature's delta are warned about, since
+/// record drops them; against a permanent spec the target check reports them as errors.
+fn validate_delta_markers(
+    content: &str,
+    file_path: &str,
+    is_new_feature: bool,
+    result: &mut PlanValidationResult,
+) {
+    // Kind, line and whether the block has any content yet
+    let mut open_markers: Vec<(DeltaKind, usize, bool)> = Vec::new();
+    // Lines of unplaced DELTA:NEW markers, the slot `speq plan new` leaves for new scenarios
+    let mut new_slots: Vec<usize> = Vec::new();
+
+    for (line_num, line) in content.lines().enumerate() {
+        // Only match markers that are standalone (start of trimmed line)
+        // This avoids matching markers inside backticks or other inline content
+        let trimmed = line.trim();
+
+        if let Some((kind, attributes)) = parse_delta_open(trimmed) {
+            if kind == DeltaKind::New && attributes.is_empty() {
+                new_slots.push(line_num + 1);
+            }
+            open_markers.push((kind, line_num + 1, false));
+        } else if let Some(kind) = parse_delta_close(trimmed) {
+            match open_markers.iter().rposition(|(open, _, _)| *open == kind) {
+                Some(index) => {
+                    let (kind, open_line, filled) = open_markers.remove(index);
+                    if !filled && is_new_feature && !new_slots.contains(&open_line) {
+                        result.add_warning(format!(
+                            "{}:{}: Empty DELTA:{} block is ignored; fill it in or remove it",
+                            file_path, open_line, kind
+                        ));
+                    }
+                }
+                None => result.add_error(format!(
                     "{}:{}: Found closing DELTA:{} without matching open marker",
                     file_path,
                     line_num + 1,
-                    marker_type
-                ));
+                    kind
+                )),
+            }
+        } else if is_delta_marker(trimmed) {
+            result.add_error(format!(
+                "{}:{}: Unrecognized delta marker: {}",
+                file_path,
+                line_num + 1,
+                trimmed
+            ));
+        } else if !trimmed.is_empty() {
+            for (_, _, filled) in &mut open_markers {
+                *filled = true;
             }
         }
+    }
 
-        // Report any unclosed markers
-        for line_num in open_positions {
-            result.add_delta_marker_error(DeltaMarkerError {
-                file_path: file_path.to_string(),
-                marker_type: marker_type.to_string(),
-                line_number: line_num,
-            });
-        }
+    // Report any unclosed markers
+    for (kind, line_num, _) in open_markers {
+        result.add_delta_marker_error(DeltaMarkerError {
+            file_path: file_path.to_string(),
+            marker_type: kind.to_string(),
+            line_number: line_num,
+        });
     }
 }
 
@@ -224,7 +651,11 @@ mod tests {
     fn create_plan(tmp: &TempDir, plan_name: &str) -> std::path::PathBuf {
         let plan_dir = tmp.path().join("specs/_plans").join(plan_name);
         fs::create_dir_all(&plan_dir).unwrap();
-        fs::write(plan_dir.join("plan.md"), "# Plan\n").unwrap();
+        fs::write(
+            plan_dir.join("plan.md"),
+            "# Plan\n\n## Tasks\n\n- [ ] Implement the plan\n",
+        )
+        .unwrap();
         plan_dir
     }
 
@@ -385,6 +816,144 @@ Description.
         assert_eq!(result.delta_marker_errors[0].line_number, 4);
     }
 
+    #[test]
+    fn warns_on_empty_delta_block() {
+        let tmp = TempDir::new().unwrap();
+        let plan_dir = create_plan(&tmp, "empty-block-test");
+        let spec_dir = plan_dir.join("test/feature");
+        fs::create_dir_all(&spec_dir).unwrap();
+        fs::write(
+            spec_dir.join("spec.md"),
+            "line 1\n<!-- DELTA:CHANGED -->\n\n<!-- /DELTA:CHANGED -->\n<!-- DELTA:NEW after=\"A\" -->\n<!-- /DELTA:NEW -->\n",
+        )
+        .unwrap();
+
+        let result = validate_plan(&tmp.path().join("specs"), "empty-block-test").unwrap();
+        assert!(result.delta_marker_errors.is_empty());
+        assert!(
+            result
+                .warnings
+                .iter()
+                .any(|w| w.contains("spec.md:2: Empty DELTA:CHANGED block is ignored"))
+        );
+        assert!(
+            result
+                .warnings
+                .iter()
+                .any(|w| w.contains("spec.md:5: Empty DELTA:NEW block is ignored"))
+        );
+    }
+
+    #[test]
+    fn rejects_empty_block_against_permanent_spec() {
+        let tmp = TempDir::new().unwrap();
+        let plan_dir = create_plan(&tmp, "empty-changed-test");
+        let feature_dir = tmp.path().join("specs/test/feature");
+        fs::create_dir_all(&feature_dir).unwrap();
+        fs::write(
+            feature_dir.join("spec.md"),
+            "# Feature: Test\n\n## Scenarios\n\n### Scenario: Login\n",
+        )
+        .unwrap();
+        let spec_dir = plan_dir.join("test/feature");
+        fs::create_dir_all(&spec_dir).unwrap();
+        fs::write(
+            spec_dir.join("spec.md"),
+            "<!-- DELTA:CHANGED -->\n<!-- /DELTA:CHANGED -->\n",
+        )
+        .unwrap();
+
+        let result = validate_plan(&tmp.path().join("specs"), "empty-changed-test").unwrap();
+        assert!(result.errors.iter().any(|e| {
+            e.contains("test/feature/spec.md:1: DELTA:CHANGED block has no '### Scenario:' title")
+        }));
+        assert!(!result.warnings.iter().any(|w| w.contains("Empty DELTA")));
+    }
+
+    #[test]
+    fn accepts_empty_new_scenario_slot() {
+        let tmp = TempDir::new().unwrap();
+        let plan_dir = create_plan(&tmp, "empty-slot-test");
+        let spec_dir = plan_dir.join("test/feature");
+        fs::create_dir_all(&spec_dir).unwrap();
+        fs::write(
+            spec_dir.join("spec.md"),
+            "line 1\n\n<!-- DELTA:NEW -->\n<!-- /DELTA:NEW -->\n",
+        )
+        .unwrap();
+
+        let result = validate_plan(&tmp.path().join("specs"), "empty-slot-test").unwrap();
+        assert!(result.delta_marker_errors.is_empty());
+        assert!(
+            !result.warnings.iter().any(|w| w.contains("Empty DELTA")),
+            "warnings: {:?}",
+            result.warnings
+        );
+    }
+
+    #[test]
+    fn error_on_changed_delta_targeting_missing_scenario() {
+        let tmp = TempDir::new().unwrap();
+        let feature_dir = tmp.path().join("specs/test/feature");
+        fs::create_dir_all(&feature_dir).unwrap();
+        fs::write(
+            feature_dir.join("spec.md"),
+            "# Feature: Test\n\n## Scenarios\n\n### Scenario: Login succeeds\n\n* *GIVEN* a user\n",
+        )
+        .unwrap();
+
+        let plan_dir = create_plan(&tmp, "missing-target");
+        let spec_dir = plan_dir.join("test/feature");
+        fs::create_dir_all(&spec_dir).unwrap();
+        fs::write(
+            spec_dir.join("spec.md"),
+            "## Scenarios\n\n<!-- DELTA:CHANGED -->\n### Scenario: Login succeds\n\n* *GIVEN* a user\n<!-- /DELTA:CHANGED -->\n",
+        )
+        .unwrap();
+
+        let result = validate_plan(&tmp.path().join("specs"), "missing-target").unwrap();
+        assert!(!result.is_success());
+        assert_eq!(
+            result.errors,
+            vec![
+                "test/feature/spec.md:3: DELTA:CHANGED targets scenario 'Login succeds' which does not exist in the permanent spec (did you mean 'Login succeeds'?)"
+                    .to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn checks_targets_against_base_snapshot_and_warns_when_outdated() {
+        let tmp = TempDir::new().unwrap();
+        let feature_dir = tmp.path().join("specs/test/feature");
+        fs::create_dir_all(&feature_dir).unwrap();
+        // Another plan already renamed the scenario in the permanent spec
+        fs::write(
+            feature_dir.join("spec.md"),
+            "# Feature: Test\n\n## Scenarios\n\n### Scenario: Sign in\n\n* *GIVEN* a user\n",
+        )
+        .unwrap();
+
+        let plan_dir = create_plan(&tmp, "based-plan");
+        let spec_dir = plan_dir.join("test/feature");
+        fs::create_dir_all(&spec_dir).unwrap();
+        fs::write(
+            spec_dir.join("base.md"),
+            "# Feature: Test\n\n## Scenarios\n\n### Scenario: Login\n\n* *GIVEN* a user\n",
+        )
+        .unwrap();
+        fs::write(
+            spec_dir.join("spec.md"),
+            "<!-- DELTA:REMOVED -->\n### Scenario: Login\n<!-- /DELTA:REMOVED -->\n",
+        )
+        .unwrap();
+
+        let result = validate_plan(&tmp.path().join("specs"), "based-plan").unwrap();
+        assert!(result.errors.is_empty(), "errors: {:?}", result.errors);
+        assert_eq!(result.warnings.len(), 1);
+        assert!(result.warnings[0].contains("changed since the plan's base snapshot"));
+    }
+
     #[test]
     fn includes_spec_validation_errors() {
         let tmp = TempDir::new().unwrap();
@@ -443,4 +1012,104 @@ Description.
         assert!(result.is_success());
         assert!(!result.spec_validation_warnings.is_empty());
     }
+
+    #[test]
+    fn summarizes_deltas_promotions_and_tasks() {
+        let tmp = TempDir::new().unwrap();
+        let permanent = tmp.path().join("specs/auth/login");
+        fs::create_dir_all(&permanent).unwrap();
+        fs::write(
+            permanent.join("spec.md"),
+            "# Feature: Login\n\n## Scenarios\n\n### Scenario: Guest login\n",
+        )
+        .unwrap();
+        let plan_dir = create_plan(&tmp, "change-login");
+        fs::create_dir_all(plan_dir.join("auth/login")).unwrap();
+        fs::write(
+            plan_dir.join("auth/login/spec.md"),
+            "<!-- DELTA:NEW -->\n### Scenario: Lockout\n<!-- /DELTA:NEW -->\n\n<!-- DELTA:REMOVED -->\n### Scenario: Guest login\n<!-- /DELTA:REMOVED -->\n\n<!-- DELTA:DESCRIPTION -->\nUsers log in.\n<!-- /DELTA:DESCRIPTION -->\n",
+        )
+        .unwrap();
+        fs::create_dir_all(plan_dir.join("billing/invoices")).unwrap();
+        fs::write(
+            plan_dir.join("billing/invoices/spec.md"),
+            "<!-- DELTA:NEW -->\n### Scenario: Export\n<!-- /DELTA:NEW -->\n",
+        )
+        .unwrap();
+        fs::write(
+            plan_dir.join("decision-log.md"),
+            "# Decision Log: change-login\n\n## Design Decisions\n\n### [1] Count failures\n\n- **Promotes to ADR:** yes\n\n### [2] Keep guests\n\n- **Promotes to ADR:** no\n",
+        )
+        .unwrap();
+
+        let summary = summarize_plan(&tmp.path().join("specs"), "change-login").unwrap();
+
+        assert_eq!(summary.features.len(), 2);
+        let login = &summary.features[0];
+        assert_eq!(login.feature, "auth/login");
+        assert!(!login.is_new);
+        assert_eq!(login.new, vec!["Lockout"]);
+        assert_eq!(login.removed, vec!["Guest login"]);
+        assert!(login.changed.is_empty());
+        assert_eq!(login.other, vec!["DESCRIPTION"]);
+        assert_eq!(summary.features[1].feature, "billing/invoices");
+        assert!(summary.features[1].is_new);
+        assert_eq!(summary.adr_promotions, vec!["Count failures"]);
+        assert_eq!((summary.tasks.done, summary.tasks.total), (0, 1));
+    }
+
+    fn write_delta(tmp: &TempDir, plan_name: &str, feature: &str, delta: &str) {
+        let spec_dir = tmp
+            .path()
+            .join("specs/_plans")
+            .join(plan_name)
+            .join(feature);
+        fs::create_dir_all(&spec_dir).unwrap();
+        fs::write(spec_dir.join("spec.md"), delta).unwrap();
+    }
+
+    #[test]
+    fn finds_plans_touching_the_same_scenario() {
+        let tmp = TempDir::new().unwrap();
+        create_plan(&tmp, "change-login");
+        create_plan(&tmp, "fix-login");
+        create_plan(&tmp, "add-signup");
+        write_delta(
+            &tmp,
+            "change-login",
+            "auth/login",
+            "<!-- DELTA:CHANGED -->\n### Scenario: Login\n<!-- /DELTA:CHANGED -->\n<!-- DELTA:NEW -->\n### Scenario: Lockout\n<!-- /DELTA:NEW -->\n",
+        );
+        write_delta(
+            &tmp,
+            "fix-login",
+            "auth/login",
+            "<!-- DELTA:REMOVED -->\n### Scenario: Login\n<!-- /DELTA:REMOVED -->\n",
+        );
+        // Same title in another feature is no overlap
+        write_delta(
+            &tmp,
+            "add-signup",
+            "auth/signup",
+            "<!-- DELTA:NEW -->\n### Scenario: Login\n<!-- /DELTA:NEW -->\n",
+        );
+
+        let overlaps = find_plan_overlaps(&tmp.path().join("specs"));
+
+        assert_eq!(overlaps.len(), 1);
+        assert_eq!(
+            overlaps[0].to_string(),
+            "auth/login: scenario 'Login' is CHANGED by 'change-login' and REMOVED by 'fix-login'"
+        );
+        let (ours, theirs) = overlaps[0].sides("fix-login").unwrap();
+        assert_eq!(
+            (ours.kind.clone(), theirs.plan.as_str()),
+            (DeltaKind::Removed, "change-login")
+        );
+        assert!(overlaps[0].sides("add-signup").is_none());
+
+        let result = validate_plan(&tmp.path().join("specs"), "fix-login").unwrap();
+        assert!(result.warnings.iter().any(|w| w
+            == "auth/login: scenario 'Login' (REMOVED here) is also CHANGED by plan 'change-login'; recording both may conflict or overwrite, depending on base snapshots"));
+    }
 }
@@ -0,0 +1,500 @@
+use std::fs;
+use std::path::Path;
+
+use chrono::NaiveDate;
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::plan;
+
+/// plan.md line holding the lifecycle status, placed under the `# Plan:` heading
+pub const STATUS_PREFIX: &str = "Status:";
+
+/// plan.md line `speq plan approve` writes next to the status
+pub const APPROVED_BY_PREFIX: &str = "Approved by:";
+
+/// Written by `/speq:implement` once build, tests and review have run
+pub const VERIFICATION_REPORT_FILE: &str = "verification-report.md";
+
+/// Where a plan is in its lifecycle. A plan.md without a status line is a draft.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PlanStatus {
+    Draft,
+    Approved,
+    Implemented,
+    Verified,
+}
+
+impl PlanStatus {
+    const ALL: [PlanStatus; 4] = [
+        PlanStatus::Draft,
+        PlanStatus::Approved,
+        PlanStatus::Implemented,
+        PlanStatus::Verified,
+    ];
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|status| status.to_string() == value)
+    }
+
+    /// The status a plan moves to from this one, if any
+    pub fn next(self) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .skip_while(|status| *status != self)
+            .nth(1)
+    }
+}
+
+impl std::fmt::Display for PlanStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PlanStatus::Draft => write!(f, "draft"),
+            PlanStatus::Approved => write!(f, "approved"),
+            PlanStatus::Implemented => write!(f, "implemented"),
+            PlanStatus::Verified => write!(f, "verified"),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum PlanStatusError {
+    #[error(
+        "plan.md:{line}: Invalid status '{value}' (expected draft, approved, implemented or verified)"
+    )]
+    InvalidStatus { line: usize, value: String },
+
+    #[error("Plan '{plan}' is {status}; only a draft plan can be approved")]
+    NotDraft { plan: String, status: PlanStatus },
+
+    #[error("Plan '{0}' fails validation; run `speq plan validate {0}`")]
+    PlanInvalid(String),
+
+    #[error("Approver name must not be empty")]
+    MissingApprover,
+
+    #[error(
+        "Plan '{plan}' is {status} and cannot become {target}; plans go draft, approved (`speq plan approve`), implemented, verified"
+    )]
+    InvalidTransition {
+        plan: String,
+        status: PlanStatus,
+        target: PlanStatus,
+    },
+
+    #[error("Plan '{0}' has no 'Approved by:' line; approve it with `speq plan approve`")]
+    NotApproved(String),
+
+    #[error("Plan '{plan}' is {status}; record needs a verified plan")]
+    NotVerified { plan: String, status: PlanStatus },
+
+    #[error("Plan '{0}' has no verification-report.md")]
+    ReportMissing(String),
+
+    #[error("verification-report.md: {0}")]
+    ReportInvalid(String),
+
+    #[error(transparent)]
+    Plan(#[from] plan::PlanValidationError),
+
+    #[error("Failed to read file: {path}")]
+    FileReadError { path: String },
+
+    #[error("Failed to write file: {path}")]
+    FileWriteError { path: String },
+}
+
+/// Read the status line of plan.md; the header ends at the first `##` heading.
+/// Returns `Draft` when there is no status line.
+pub fn parse_status(plan_md: &str) -> Result<PlanStatus, PlanStatusError> {
+    for (index, line) in plan_md.lines().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("## ") {
+            break;
+        }
+        if let Some(value) = trimmed.strip_prefix(STATUS_PREFIX) {
+            let value = value.trim();
+            return PlanStatus::parse(value).ok_or_else(|| PlanStatusError::InvalidStatus {
+                line: index + 1,
+                value: value.to_string(),
+            });
+        }
+    }
+    Ok(PlanStatus::Draft)
+}
+
+/// Read the `Approved by:` value of plan.md, e.g. `Dana on 2026-05-02`.
+pub fn parse_approval(plan_md: &str) -> Option<String> {
+    plan_md
+        .lines()
+        .map(str::trim)
+        .take_while(|line| !line.starts_with("## "))
+        .find_map(|line| line.strip_prefix(APPROVED_BY_PREFIX))
+        .map(|value| value.trim().to_string())
+}
+
+/// Set the status line, and the approval line if given, in the plan.md header.
+/// Existing lines are replaced in place; otherwise they go after the `# Plan` heading.
+pub fn set_status(plan_md: &str, status: PlanStatus, approved_by: Option<&str>) -> String {
+    let mut lines: Vec<String> = plan_md.lines().map(str::to_string).collect();
+    let header_end = lines
+        .iter()
+        .position(|line| line.trim().starts_with("## "))
+        .unwrap_or(lines.len());
+    let is_status_line = |line: &String| {
+        let line = line.trim();
+        line.starts_with(STATUS_PREFIX) || line.starts_with(APPROVED_BY_PREFIX)
+    };
+
+    let approval = approved_by
+        .map(str::to_string)
+        .or_else(|| parse_approval(plan_md));
+    let mut block = vec![format!("{STATUS_PREFIX} {status}")];
+    if let Some(approval) = approval {
+        block.push(format!("{APPROVED_BY_PREFIX} {approval}"));
+    }
+
+    match lines[..header_end].iter().position(is_status_line) {
+        Some(at) => {
+            let mut index = 0;
+            lines.retain(|line| {
+                index += 1;
+                index > header_end || !is_status_line(line)
+            });
+            lines.splice(at..at, block);
+        }
+        None => {
+            let at = lines[..header_end]
+                .iter()
+                .position(|line| line.starts_with("# "))
+                .map_or(0, |heading| heading + 1);
+            if at > 0 {
+                block.insert(0, String::new());
+            }
+            if lines.get(at).is_some_and(|line| !line.trim().is_empty()) {
+                block.push(String::new());
+            }
+            lines.splice(at..at, block);
+        }
+    }
+
+    let mut content = lines.join("\n");
+    content.push('\n');
+    content
+}
+
+/// Mark a draft plan that passes `speq plan validate` as approved by `by` on `date`.
+pub fn approve_plan(
+    specs_base: &Path,
+    plan_name: &str,
+    by: &str,
+    date: NaiveDate,
+) -> Result<(), PlanStatusError> {
+    let by = by.trim();
+    if by.is_empty() {
+        return Err(PlanStatusError::MissingApprover);
+    }
+    if !plan::validate_plan(specs_base, plan_name)?.is_success() {
+        return Err(PlanStatusError::PlanInvalid(plan_name.to_string()));
+    }
+
+    let plan_md = specs_base.join("_plans").join(plan_name).join("plan.md");
+    let content = read_file(&plan_md)?;
+    let status = parse_status(&content)?;
+    if status != PlanStatus::Draft {
+        return Err(PlanStatusError::NotDraft {
+            plan: plan_name.to_string(),
+            status,
+        });
+    }
+
+    let approval = format!("{} on {}", by, date.format("%Y-%m-%d"));
+    let updated = set_status(&content, PlanStatus::Approved, Some(&approval));
+    fs::write(&plan_md, updated).map_err(|_| PlanStatusError::FileWriteError {
+        path: plan_md.display().to_string(),
+    })
+}
+
+/// Move an approved plan to `implemented`, or an implemented one to `verified`. A plan is
+/// only verified when it holds a passing verification report.
+pub fn advance_plan(
+    specs_base: &Path,
+    plan_name: &str,
+    target: PlanStatus,
+) -> Result<(), PlanStatusError> {
+    let plan_dir = specs_base.join("_plans").join(plan_name);
+    if !plan_dir.exists() {
+        return Err(plan::PlanValidationError::PlanNotFound {
+            name: plan_name.to_string(),
+        }
+        .into());
+    }
+    let plan_md = plan_dir.join("plan.md");
+    let content = read_file(&plan_md)?;
+    let status = parse_status(&content)?;
+    if target == PlanStatus::Approved || status.next() != Some(target) {
+        return Err(PlanStatusError::InvalidTransition {
+            plan: plan_name.to_string(),
+            status,
+            target,
+        });
+    }
+    if target == PlanStatus::Verified {
+        check_report(&plan_dir, plan_name)?;
+    }
+
+    fs::write(&plan_md, set_status(&content, target, None)).map_err(|_| {
+        PlanStatusError::FileWriteError {
+            path: plan_md.display().to_string(),
+        }
+    })
+}
+
+/// Check that a plan may be recorded: it was approved, its status is `verified` and it
+/// holds a verification report whose result is `pass`.
+pub fn check_ready_to_record(plan_dir: &Path, plan_name: &str) -> Result<(), PlanStatusError> {
+    let plan_md = plan_dir.join("plan.md");
+    let content = if plan_md.exists() {
+        read_file(&plan_md)?
+    } else {
+        String::new()
+    };
+    let status = parse_status(&content)?;
+    if status != PlanStatus::Verified {
+        return Err(PlanStatusError::NotVerified {
+            plan: plan_name.to_string(),
+            status,
+        });
+    }
+    if parse_approval(&content).is_none_or(|approval| approval.is_empty()) {
+        return Err(PlanStatusError::NotApproved(plan_name.to_string()));
+    }
+
+    check_report(plan_dir, plan_name)
+}
+
+fn check_report(plan_dir: &Path, plan_name: &str) -> Result<(), PlanStatusError> {
+    let report = plan_dir.join(VERIFICATION_REPORT_FILE);
+    if !report.exists() {
+        return Err(PlanStatusError::ReportMissing(plan_name.to_string()));
+    }
+    check_verification_report(&read_file(&report)?).map_err(PlanStatusError::ReportInvalid)
+}
+
+/// A report starts with a `# Verification Report` heading and states `Result: pass`
+/// (or `fail`).
+pub fn check_verification_report(content: &str) -> Result<(), String> {
+    let heading = content.lines().map(str::trim).find(|line| !line.is_empty());
+    if !heading.is_some_and(|line| line.starts_with("# Verification Report")) {
+        return Err("must start with a '# Verification Report' heading".to_string());
+    }
+
+    let result = content
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("Result:"))
+        .map(|value| value.trim().to_ascii_lowercase());
+    match result.as_deref() {
+        Some("pass") => Ok(()),
+        Some("fail") => Err("verification failed (Result: fail)".to_string()),
+        Some(other) => Err(format!("invalid result '{other}' (expected pass or fail)")),
+        None => Err("missing 'Result: pass' line".to_string()),
+    }
+}
+
+fn read_file(path: &Path) -> Result<String, PlanStatusError> {
+    fs::read_to_string(path).map_err(|_| PlanStatusError::FileReadError {
+        path: path.display().to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const PLAN: &str = "# Plan: add-lockout\n\n## Tasks\n\n- [ ] Count failures\n";
+
+    fn date() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 5, 2).unwrap()
+    }
+
+    #[test]
+    fn parses_status_from_header() {
+        assert_eq!(parse_status(PLAN).unwrap(), PlanStatus::Draft);
+        assert_eq!(
+            parse_status("# Plan\n\nStatus: verified\n").unwrap(),
+            PlanStatus::Verified
+        );
+        // A status line below the header is plan text, not the status
+        assert_eq!(
+            parse_status("# Plan\n\n## Notes\n\nStatus: verified\n").unwrap(),
+            PlanStatus::Draft
+        );
+        assert!(matches!(
+            parse_status("# Plan\nStatus: Done\n"),
+            Err(PlanStatusError::InvalidStatus { line: 2, value }) if value == "Done"
+        ));
+    }
+
+    #[test]
+    fn sets_status_after_heading_and_replaces_it() {
+        let approved = set_status(PLAN, PlanStatus::Approved, Some("Dana on 2026-05-02"));
+        assert_eq!(
+            approved,
+            "# Plan: add-lockout\n\nStatus: approved\nApproved by: Dana on 2026-05-02\n\n## Tasks\n\n- [ ] Count failures\n"
+        );
+        assert_eq!(
+            parse_approval(&approved).as_deref(),
+            Some("Dana on 2026-05-02")
+        );
+
+        let verified = set_status(&approved, PlanStatus::Verified, None);
+        assert_eq!(
+            verified,
+            "# Plan: add-lockout\n\nStatus: verified\nApproved by: Dana on 2026-05-02\n\n## Tasks\n\n- [ ] Count failures\n"
+        );
+    }
+
+    #[test]
+    fn approves_only_valid_draft_plans() {
+        let tmp = TempDir::new().unwrap();
+        let plan_dir = tmp.path().join("_plans/add-lockout");
+        fs::create_dir_all(&plan_dir).unwrap();
+        fs::write(plan_dir.join("plan.md"), PLAN).unwrap();
+
+        assert!(matches!(
+            approve_plan(tmp.path(), "add-lockout", " ", date()),
+            Err(PlanStatusError::MissingApprover)
+        ));
+        approve_plan(tmp.path(), "add-lockout", "Dana", date()).unwrap();
+
+        let content = fs::read_to_string(plan_dir.join("plan.md")).unwrap();
+        assert_eq!(parse_status(&content).unwrap(), PlanStatus::Approved);
+        assert!(matches!(
+            approve_plan(tmp.path(), "add-lockout", "Dana", date()),
+            Err(PlanStatusError::NotDraft {
+                status: PlanStatus::Approved,
+                ..
+            })
+        ));
+
+        fs::write(plan_dir.join("plan.md"), "Not a plan\n").unwrap();
+        assert!(matches!(
+            approve_plan(tmp.path(), "add-lockout", "Dana", date()),
+            Err(PlanStatusError::PlanInvalid(_))
+        ));
+    }
+
+    #[test]
+    fn record_needs_verified_status_and_passing_report() {
+        let tmp = TempDir::new().unwrap();
+        let plan_dir = tmp.path();
+        fs::write(plan_dir.join("plan.md"), "# Plan\n\nStatus: implemented\n").unwrap();
+        assert!(matches!(
+            check_ready_to_record(plan_dir, "p"),
+            Err(PlanStatusError::NotVerified {
+                status: PlanStatus::Implemented,
+                ..
+            })
+        ));
+
+        fs::write(plan_dir.join("plan.md"), "# Plan\n\nStatus: verified\n").unwrap();
+        assert!(matches!(
+            check_ready_to_record(plan_dir, "p"),
+            Err(PlanStatusError::NotApproved(_))
+        ));
+
+        fs::write(
+            plan_dir.join("plan.md"),
+            "# Plan\n\nStatus: verified\nApproved by: Dana on 2026-05-02\n",
+        )
+        .unwrap();
+        assert!(matches!(
+            check_ready_to_record(plan_dir, "p"),
+            Err(PlanStatusError::ReportMissing(_))
+        ));
+
+        let report = plan_dir.join(VERIFICATION_REPORT_FILE);
+        fs::write(&report, "# Verification Report: p\n\nResult: fail\n").unwrap();
+        assert!(matches!(
+            check_ready_to_record(plan_dir, "p"),
+            Err(PlanStatusError::ReportInvalid(reason)) if reason.contains("Result: fail")
+        ));
+
+        fs::write(&report, "# Verification Report: p\n\nResult: PASS\n").unwrap();
+        assert!(check_ready_to_record(plan_dir, "p").is_ok());
+    }
+
+    #[test]
+    fn advances_status_in_order() {
+        let tmp = TempDir::new().unwrap();
+        let plan_dir = tmp.path().join("_plans/add-lockout");
+        fs::create_dir_all(&plan_dir).unwrap();
+        fs::write(plan_dir.join("plan.md"), PLAN).unwrap();
+        let status =
+            || parse_status(&fs::read_to_string(plan_dir.join("plan.md")).unwrap()).unwrap();
+
+        assert!(matches!(
+            advance_plan(tmp.path(), "add-lockout", PlanStatus::Implemented),
+            Err(PlanStatusError::InvalidTransition {
+                status: PlanStatus::Draft,
+                ..
+            })
+        ));
+        approve_plan(tmp.path(), "add-lockout", "Dana", date()).unwrap();
+        assert!(matches!(
+            advance_plan(tmp.path(), "add-lockout", PlanStatus::Verified),
+            Err(PlanStatusError::InvalidTransition { .. })
+        ));
+        advance_plan(tmp.path(), "add-lockout", PlanStatus::Implemented).unwrap();
+        assert_eq!(status(), PlanStatus::Implemented);
+
+        assert!(matches!(
+            advance_plan(tmp.path(), "add-lockout", PlanStatus::Verified),
+            Err(PlanStatusError::ReportMissing(_))
+        ));
+        fs::write(
+            plan_dir.join(VERIFICATION_REPORT_FILE),
+            "# Verification Report\n\nResult: fail\n",
+        )
+        .unwrap();
+        assert!(matches!(
+            advance_plan(tmp.path(), "add-lockout", PlanStatus::Verified),
+            Err(PlanStatusError::ReportInvalid(_))
+        ));
+        assert_eq!(status(), PlanStatus::Implemented);
+
+        fs::write(
+            plan_dir.join(VERIFICATION_REPORT_FILE),
+            "# Verification Report\n\nResult: pass\n",
+        )
+        .unwrap();
+        advance_plan(tmp.path(), "add-lockout", PlanStatus::Verified).unwrap();
+        let content = fs::read_to_string(plan_dir.join("plan.md")).unwrap();
+        assert_eq!(parse_status(&content).unwrap(), PlanStatus::Verified);
+        assert_eq!(
+            parse_approval(&content).as_deref(),
+            Some("Dana on 2026-05-02")
+        );
+        assert!(check_ready_to_record(&plan_dir, "add-lockout").is_ok());
+    }
+
+    #[test]
+    fn checks_report_shape() {
+        assert!(check_verification_report("Result: pass\n").is_err());
+        assert_eq!(
+            check_verification_report("# Verification Report\n\nAll good.\n"),
+            Err("missing 'Result: pass' line".to_string())
+        );
+        assert!(check_verification_report("# Verification Report\nResult: maybe\n").is_err());
+    }
+
+    #[test]
+    fn accepts_documented_report_template() {
+        let report = "# Verification Report: add-user-auth\n\nResult: pass\n\n## Checks\n\n* Build: pass\n* Tests: pass (212 passed)\n* Lint: pass\n\n## Review Findings\n\n* None outstanding\n";
+        assert_eq!(check_verification_report(report), Ok(()));
+    }
+}
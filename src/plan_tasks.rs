@@ -0,0 +1,473 @@
+use std::fs;
+use std::path::Path;
+
+use crate::feature::FeaturePath;
+use crate::validate::parser;
+
+/// plan.md section holding the implementation checklist, one task per line:
+/// `- [ ] 2.1 Add lockout counter [expert] [auth/login: Lock out after failures]`
+pub const TASKS_HEADING: &str = "## Tasks";
+
+/// Tag routing a task to the expert implementer
+pub const EXPERT_TAG: &str = "expert";
+
+/// A task's claim to implement a scenario: `[<domain>/<feature>: <Scenario title>]`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScenarioLink {
+    pub feature: FeaturePath,
+    pub scenario: String,
+}
+
+impl std::fmt::Display for ScenarioLink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.feature, self.scenario)
+    }
+}
+
+/// A checklist item of the `## Tasks` section.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlanTask {
+    pub line: usize,
+    /// Leading number such as `2.1`, if the task has one
+    pub id: Option<String>,
+    /// Task text without its id and tags
+    pub description: String,
+    pub done: bool,
+    pub expert: bool,
+    pub scenarios: Vec<ScenarioLink>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaskError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl std::fmt::Display for TaskError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "plan.md:{}: {}", self.line, self.message)
+    }
+}
+
+/// Parse the `## Tasks` section of plan.md.
+///
+/// `###` headings group tasks without ending the section. Top-level bullets must be
+/// `- [ ]` or `- [x]` checkboxes; indented bullets without a checkbox are notes.
+pub fn parse_tasks(plan_md: &str) -> (Vec<PlanTask>, Vec<TaskError>) {
+    let mut tasks = Vec::new();
+    let mut errors = Vec::new();
+    let mut in_section = false;
+
+    for (index, line) in plan_md.lines().enumerate() {
+        let trimmed = line.trim();
+        let line_number = index + 1;
+
+        if trimmed.starts_with('#') {
+            if !trimmed.starts_with("###") {
+                in_section = trimmed == TASKS_HEADING;
+            }
+            continue;
+        }
+        if !in_section {
+            continue;
+        }
+
+        let Some(item) = trimmed
+            .strip_prefix("- ")
+            .or_else(|| trimmed.strip_prefix("* "))
+        else {
+            continue;
+        };
+        let Some((done, rest)) = parse_checkbox(item) else {
+            if !line.starts_with([' ', '\t']) {
+                errors.push(TaskError {
+                    line: line_number,
+                    message: "task needs a '[ ]' or '[x]' checkbox".to_string(),
+                });
+            }
+            continue;
+        };
+
+        match parse_task_text(rest) {
+            Ok((id, description, expert, scenarios)) => tasks.push(PlanTask {
+                line: line_number,
+                id,
+                description,
+                done,
+                expert,
+                scenarios,
+            }),
+            Err(message) => errors.push(TaskError {
+                line: line_number,
+                message,
+            }),
+        }
+    }
+
+    (tasks, errors)
+}
+
+fn parse_checkbox(item: &str) -> Option<(bool, &str)> {
+    let done = match item.get(..3)? {
+        "[ ]" => false,
+        "[x]" | "[X]" => true,
+        _ => return None,
+    };
+    Some((done, item[3..].trim()))
+}
+
+type TaskText = (Option<String>, String, bool, Vec<ScenarioLink>);
+
+/// Split task text into id, description, `[expert]` flag and scenario links.
+/// Other brackets, markdown links (`[text](url)`) and code spans stay part of the description.
+fn parse_task_text(text: &str) -> Result<TaskText, String> {
+    let mut description = String::new();
+    let mut expert = false;
+    let mut scenarios = Vec::new();
+    let mut rest = text;
+
+    while let Some(start) = rest.find(['[', '`']) {
+        if rest[start..].starts_with('`') {
+            // A code span runs to the next backtick run of the same length
+            let ticks = rest[start..].len() - rest[start..].trim_start_matches('`').len();
+            let fence = &rest[start..start + ticks];
+            let end = rest[start + ticks..]
+                .find(fence)
+                .map_or(start + ticks, |offset| start + 2 * ticks + offset);
+            description.push_str(&rest[..end]);
+            rest = &rest[end..];
+            continue;
+        }
+        let Some(close) = rest[start..].find(']').map(|offset| start + offset) else {
+            break;
+        };
+        let tag = rest[start + 1..close].trim();
+        let is_markdown_link = rest[close + 1..].starts_with('(');
+        if is_markdown_link {
+            description.push_str(&rest[..=close]);
+        } else if tag == EXPERT_TAG {
+            description.push_str(&rest[..start]);
+            expert = true;
+        } else if let Some(link) = parse_link(tag)? {
+            description.push_str(&rest[..start]);
+            scenarios.push(link);
+        } else {
+            description.push_str(&rest[..=close]);
+        }
+        rest = &rest[close + 1..];
+    }
+    description.push_str(rest);
+
+    let description = description.split_whitespace().collect::<Vec<_>>().join(" ");
+    let (id, description) = match description.split_once(' ') {
+        Some((first, remainder)) if is_task_id(first) => {
+            (Some(first.to_string()), remainder.to_string())
+        }
+        _ if is_task_id(&description) => (Some(description.clone()), String::new()),
+        _ => (None, description),
+    };
+    if description.is_empty() {
+        return Err("task has no description".to_string());
+    }
+
+    Ok((id, description, expert, scenarios))
+}
+
+/// `<domain>/<feature>: <Scenario>`; brackets of any other shape are not links
+fn parse_link(tag: &str) -> Result<Option<ScenarioLink>, String> {
+    let Some((feature, scenario)) = tag.split_once(':') else {
+        return Ok(None);
+    };
+    let feature = feature.trim();
+    if feature.contains(char::is_whitespace) {
+        return Ok(None);
+    }
+    let Some(feature) = FeaturePath::parse(feature) else {
+        return Ok(None);
+    };
+    let scenario = scenario.trim();
+    if scenario.is_empty() {
+        return Err(format!("link to {feature} names no scenario"));
+    }
+    Ok(Some(ScenarioLink {
+        feature,
+        scenario: scenario.to_string(),
+    }))
+}
+
+/// `2`, `2.1`, `2.1.` and the like
+fn is_task_id(word: &str) -> bool {
+    let word = word.trim_end_matches('.');
+    !word.is_empty()
+        && word
+            .split('.')
+            .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// Check plan.md outside the task list: a `# Plan` or `# Plan: <name>` heading first,
+/// a `## Tasks` section holding at least one task, and no repeated `##` sections.
+/// Returns errors and warnings.
+pub fn check_structure(
+    plan_md: &str,
+    plan_name: &str,
+    tasks: &[PlanTask],
+) -> (Vec<TaskError>, Vec<TaskError>) {
+    let mut errors = Vec::new();
+    let mut warnings = Vec::new();
+
+    match plan_md
+        .lines()
+        .enumerate()
+        .find(|(_, line)| !line.trim().is_empty())
+    {
+        Some((index, line)) => {
+            let heading = line.trim();
+            match heading.strip_prefix("# Plan") {
+                Some("") => {}
+                Some(rest) if rest.starts_with(':') => {
+                    let name = rest[1..].trim();
+                    if name != plan_name {
+                        warnings.push(TaskError {
+                            line: index + 1,
+                            message: format!(
+                                "heading names plan '{name}' but the directory is '{plan_name}'"
+                            ),
+                        });
+                    }
+                }
+                _ => errors.push(TaskError {
+                    line: index + 1,
+                    message: format!("expected '# Plan: {plan_name}' heading, found '{heading}'"),
+                }),
+            }
+        }
+        None => errors.push(TaskError {
+            line: 1,
+            message: "plan.md is empty".to_string(),
+        }),
+    }
+
+    let mut sections: Vec<&str> = Vec::new();
+    for (index, line) in plan_md.lines().enumerate() {
+        let heading = line.trim();
+        if heading.starts_with("## ") {
+            if sections.contains(&heading) {
+                warnings.push(TaskError {
+                    line: index + 1,
+                    message: format!("section '{heading}' appears more than once"),
+                });
+            }
+            sections.push(heading);
+        }
+    }
+
+    match plan_md
+        .lines()
+        .position(|line| line.trim() == TASKS_HEADING)
+    {
+        None => warnings.push(TaskError {
+            line: 1,
+            message: format!("no '{TASKS_HEADING}' section"),
+        }),
+        Some(index) if tasks.is_empty() => warnings.push(TaskError {
+            line: index + 1,
+            message: format!("'{TASKS_HEADING}' has no '- [ ]' tasks"),
+        }),
+        Some(_) => {}
+    }
+
+    (errors, warnings)
+}
+
+/// Check that each scenario link names a scenario of the plan's delta for that feature
+/// or of the permanent spec.
+pub fn check_links(specs_base: &Path, plan_dir: &Path, tasks: &[PlanTask]) -> Vec<TaskError> {
+    let mut errors = Vec::new();
+
+    for task in tasks {
+        for link in &task.scenarios {
+            let delta = plan_dir
+                .join(&link.feature.domain)
+                .join(&link.feature.feature)
+                .join("spec.md");
+            let permanent = link.feature.spec_path(specs_base);
+            if !delta.exists() && !permanent.exists() {
+                errors.push(TaskError {
+                    line: task.line,
+                    message: format!(
+                        "task links to {} which neither the plan nor the specs define",
+                        link.feature
+                    ),
+                });
+                continue;
+            }
+            let defined = [delta, permanent].iter().any(|path| {
+                fs::read_to_string(path).is_ok_and(|content| has_scenario(&content, &link.scenario))
+            });
+            if !defined {
+                errors.push(TaskError {
+                    line: task.line,
+                    message: format!(
+                        "task links to unknown scenario '{}' in {}",
+                        link.scenario, link.feature
+                    ),
+                });
+            }
+        }
+    }
+
+    errors
+}
+
+/// Whether the spec or delta in `content` defines a scenario titled exactly `title`, read
+/// with the same parser the delta target check uses.
+fn has_scenario(content: &str, title: &str) -> bool {
+    parser::parse(content).is_ok_and(|parsed| parsed.spec.scenarios.iter().any(|s| s.name == title))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const PLAN: &str = "# Plan: add-lockout
+
+## Summary
+
+Lock accounts after failed logins.
+
+## Tasks
+
+### 1. Counter
+
+- [x] 1.1 Count failures [auth/login: Lock out after failures]
+- [ ] 1.2 Make the counter lock-free [expert]
+  - see [the notes](notes.md)
+* [X] 1.3 Document it
+
+## Notes
+
+- [ ] not a task outside the section
+";
+
+    #[test]
+    fn parses_tasks_with_tags_and_links() {
+        let (tasks, errors) = parse_tasks(PLAN);
+
+        assert!(errors.is_empty(), "{errors:?}");
+        assert_eq!(tasks.len(), 3);
+        assert_eq!(
+            tasks[0],
+            PlanTask {
+                line: 11,
+                id: Some("1.1".to_string()),
+                description: "Count failures".to_string(),
+                done: true,
+                expert: false,
+                scenarios: vec![ScenarioLink {
+                    feature: FeaturePath::new("auth", "login"),
+                    scenario: "Lock out after failures".to_string(),
+                }],
+            }
+        );
+        assert!(tasks[1].expert && !tasks[1].done);
+        assert_eq!(tasks[1].description, "Make the counter lock-free");
+        assert!(tasks[2].done);
+    }
+
+    #[test]
+    fn keeps_markdown_links_in_description() {
+        let (tasks, errors) = parse_tasks("## Tasks\n\n- [ ] Follow [the guide](guide.md)\n");
+
+        assert!(errors.is_empty());
+        assert_eq!(tasks[0].description, "Follow [the guide](guide.md)");
+        assert_eq!(tasks[0].id, None);
+    }
+
+    #[test]
+    fn keeps_other_brackets_and_code_spans_in_description() {
+        let plan = "## Tasks\n\n- [ ] [WIP] Return `map[key]` from slot [0] [note: later] [auth/login: Login works]\n- [ ] Parse `` [expert] `` markers\n";
+
+        let (tasks, errors) = parse_tasks(plan);
+
+        assert!(errors.is_empty(), "{errors:?}");
+        assert_eq!(
+            tasks[0].description,
+            "[WIP] Return `map[key]` from slot [0] [note: later]"
+        );
+        assert_eq!(tasks[0].scenarios.len(), 1);
+        assert_eq!(tasks[1].description, "Parse `` [expert] `` markers");
+        assert!(!tasks[1].expert);
+    }
+
+    #[test]
+    fn reports_malformed_tasks() {
+        let plan = "## Tasks\n\n- Missing checkbox\n- [ ] 2.2\n- [ ] Link [auth/login: ]\n";
+
+        let (tasks, errors) = parse_tasks(plan);
+
+        assert!(tasks.is_empty());
+        let lines: Vec<usize> = errors.iter().map(|e| e.line).collect();
+        assert_eq!(lines, vec![3, 4, 5]);
+        assert!(errors[1].message.contains("no description"));
+        assert!(errors[2].message.contains("names no scenario"));
+    }
+
+    #[test]
+    fn checks_heading_and_sections() {
+        let (tasks, _) = parse_tasks(PLAN);
+        let (errors, warnings) = check_structure(PLAN, "add-lockout", &tasks);
+        assert!(errors.is_empty() && warnings.is_empty());
+
+        let (errors, warnings) = check_structure("Intro\n\n## Tasks\n", "add-lockout", &[]);
+        assert!(errors[0].message.contains("expected '# Plan: add-lockout'"));
+        assert!(warnings[0].message.contains("has no '- [ ]' tasks"));
+
+        let (_, warnings) = check_structure("# Plan: other\n", "add-lockout", &[]);
+        let messages: Vec<&str> = warnings.iter().map(|w| w.message.as_str()).collect();
+        assert!(messages[0].contains("names plan 'other'"));
+        assert!(messages[1].contains("no '## Tasks' section"));
+    }
+
+    #[test]
+    fn checks_scenario_links() {
+        let tmp = TempDir::new().unwrap();
+        let plan_dir = tmp.path().join("_plans/add-lockout");
+        fs::create_dir_all(plan_dir.join("auth/login")).unwrap();
+        fs::write(
+            plan_dir.join("auth/login/spec.md"),
+            "<!-- DELTA:NEW -->\n### Scenario: Lock out after failures\n<!-- /DELTA:NEW -->\n",
+        )
+        .unwrap();
+        let plan = "## Tasks\n- [ ] A [auth/login: Lock out after failures]\n- [ ] B [auth/login: Unlock]\n- [ ] C [auth/signup: Sign up]\n";
+        let (tasks, _) = parse_tasks(plan);
+
+        let errors = check_links(tmp.path(), &plan_dir, &tasks);
+
+        let lines: Vec<usize> = errors.iter().map(|e| e.line).collect();
+        assert_eq!(lines, vec![3, 4]);
+        assert!(errors[0].message.contains("unknown scenario 'Unlock'"));
+        assert!(errors[1].message.contains("auth/signup which neither"));
+    }
+
+    #[test]
+    fn scenario_links_need_an_exact_parsed_title() {
+        let tmp = TempDir::new().unwrap();
+        let plan_dir = tmp.path().join("_plans/add-lockout");
+        fs::create_dir_all(plan_dir.join("auth/login")).unwrap();
+        fs::write(
+            plan_dir.join("auth/login/spec.md"),
+            "<!-- DELTA:NEW -->\n### Scenario: Lock out after failures\n\n```markdown\n### Scenario: Unlock\n```\n<!-- /DELTA:NEW -->\n",
+        )
+        .unwrap();
+        let plan = "## Tasks\n- [ ] A [auth/login: Lock out]\n- [ ] B [auth/login: Unlock]\n";
+        let (tasks, _) = parse_tasks(plan);
+
+        let errors = check_links(tmp.path(), &plan_dir, &tasks);
+
+        let lines: Vec<usize> = errors.iter().map(|e| e.line).collect();
+        assert_eq!(lines, vec![2, 3]);
+        assert!(errors[0].message.contains("unknown scenario 'Lock out'"));
+        assert!(errors[1].message.contains("unknown scenario 'Unlock'"));
+    }
+}
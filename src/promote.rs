@@ -0,0 +1,300 @@
+use std::fs;
+use std::path::Path;
+
+use chrono::NaiveDate;
+use thiserror::Error;
+
+use crate::decision_log::{
+    Adr, AdrSection, DECISION_LOG_FILE, PERMANENT_LOG_H1, PermanentLog, PlanDecision,
+    parse_adr_heading, parse_plan_decisions,
+};
+use crate::validate::decision_log::{self as validation, DecisionLogError};
+
+#[derive(Error, Debug)]
+pub enum PromoteError {
+    #[error("Plan not found: {0}")]
+    PlanNotFound(String),
+
+    #[error("Plan '{0}' has no decision-log.md")]
+    NoPlanLog(String),
+
+    #[error("Failed to read file: {path}")]
+    FileReadError { path: String },
+
+    #[error("Failed to write file: {path}")]
+    FileWriteError { path: String },
+
+    #[error("Promoting would leave an invalid decision log: {}", .errors.iter().map(ToString::to_string).collect::<Vec<_>>().join("; "))]
+    InvalidResult { errors: Vec<DecisionLogError> },
+}
+
+/// The permanent log with the promoted decisions appended.
+#[derive(Debug)]
+pub struct Promotion {
+    pub content: String,
+    /// Headings of the new ADRs, e.g. `ADR-004: Use line scanning`
+    pub promoted: Vec<String>,
+}
+
+/// Append every decision marked `Promotes to ADR: yes` to the permanent log as the next
+/// sequential ADRs. Decisions already promoted from the same plan (same title) are skipped.
+pub fn promote_decisions(
+    permanent: Option<&str>,
+    plan_log: &str,
+    plan_name: &str,
+    date: NaiveDate,
+) -> Result<Promotion, PromoteError> {
+    let mut content = match permanent {
+        Some(existing) => existing.trim_end().to_string(),
+        None => PERMANENT_LOG_H1.to_string(),
+    };
+    let existing = PermanentLog::parse(&content);
+    let mut next = existing.last_number() + 1;
+    let mut promoted = Vec::new();
+
+    for decision in parse_plan_decisions(plan_log) {
+        let already_promoted = existing
+            .adrs
+            .iter()
+            .any(|adr| adr.title == decision.title && adr.plan.as_deref() == Some(plan_name));
+        if !decision.promotes || already_promoted {
+            continue;
+        }
+
+        content.push_str("\n\n");
+        content.push_str(
+            promoted_adr(next, &decision, plan_name, date)
+                .render()
+                .trim_end(),
+        );
+        promoted.push(format!("ADR-{:03}: {}", next, decision.title));
+        next += 1;
+    }
+    content.push('\n');
+
+    let result = validation::validate_permanent_log(&content);
+    if !result.is_success() {
+        return Err(PromoteError::InvalidResult {
+            errors: result.errors,
+        });
+    }
+
+    Ok(Promotion { content, promoted })
+}
+
+/// Promote a plan's decisions into `specs/decision-log.md`. Returns the new ADR headings.
+pub fn promote_plan(
+    specs_base: &Path,
+    plan_name: &str,
+    date: NaiveDate,
+) -> Result<Vec<String>, PromoteError> {
+    let plan_dir = specs_base.join("_plans").join(plan_name);
+    if !plan_dir.exists() {
+        return Err(PromoteError::PlanNotFound(plan_name.to_string()));
+    }
+
+    let plan_log = plan_dir.join(DECISION_LOG_FILE);
+    if !plan_log.exists() {
+        return Err(PromoteError::NoPlanLog(plan_name.to_string()));
+    }
+
+    let promotion = prepare_promotion(specs_base, &plan_log, plan_name, date)?;
+    write_promotion(specs_base, &promotion)?;
+    Ok(promotion.promoted)
+}
+
+/// Build the promoted permanent log from a plan log file without writing anything.
+pub fn prepare_promotion(
+    specs_base: &Path,
+    plan_log: &Path,
+    plan_name: &str,
+    date: NaiveDate,
+) -> Result<Promotion, PromoteError> {
+    let permanent_path = specs_base.join(DECISION_LOG_FILE);
+    let permanent = permanent_path
+        .exists()
+        .then(|| read_file(&permanent_path))
+        .transpose()?;
+
+    promote_decisions(permanent.as_deref(), &read_file(plan_log)?, plan_name, date)
+}
+
+pub fn write_promotion(specs_base: &Path, promotion: &Promotion) -> Result<(), PromoteError> {
+    if promotion.promoted.is_empty() {
+        return Ok(());
+    }
+
+    let path = specs_base.join(DECISION_LOG_FILE);
+    fs::write(&path, &promotion.content).map_err(|_| PromoteError::FileWriteError {
+        path: path.display().to_string(),
+    })
+}
+
+/// The permanent log without the ADRs numbered `numbers`, used when a recording that
+/// promoted them is reverted.
+///
+/// Returns `None` unless they are still the last ADRs and no remaining ADR mentions them,
+/// so a revert never leaves a numbering gap or a dangling reference.
+pub fn remove_promoted_adrs(content: &str, numbers: &[u32]) -> Option<String> {
+    let log = PermanentLog::parse(content);
+    let kept = log.adrs.len().checked_sub(numbers.len())?;
+    let (remaining, removed) = log.adrs.split_at(kept);
+    let mut wanted = numbers.to_vec();
+    wanted.sort_unstable();
+    if removed.iter().map(|adr| adr.number).collect::<Vec<_>>() != wanted {
+        return None;
+    }
+    if removed
+        .iter()
+        .any(|adr| remaining.iter().any(|other| other.mentions(&adr.id())))
+    {
+        return None;
+    }
+
+    let first = wanted.first()?;
+    let cut = content
+        .lines()
+        .position(|line| parse_adr_heading(line) == Some(*first))?;
+    let kept: Vec<&str> = content.lines().take(cut).collect();
+    Some(format!("{}\n", kept.join("\n").trim_end()))
+}
+
+fn read_file(path: &Path) -> Result<String, PromoteError> {
+    fs::read_to_string(path).map_err(|_| PromoteError::FileReadError {
+        path: path.display().to_string(),
+    })
+}
+
+fn promoted_adr(number: u32, decision: &PlanDecision, plan_name: &str, date: NaiveDate) -> Adr {
+    let section = |heading: &str, body: &str| AdrSection {
+        heading: heading.to_string(),
+        body: body.to_string(),
+    };
+    let context = decision
+        .rationale
+        .clone()
+        .unwrap_or_else(|| format!("Promoted from the decision log of plan `{plan_name}`."));
+
+    let mut sections = vec![
+        section("Context", &context),
+        section(
+            "Decision",
+            decision.decision.as_deref().unwrap_or(&decision.title),
+        ),
+    ];
+    if let Some(alternatives) = &decision.alternatives {
+        sections.push(section("Options Considered", alternatives));
+    }
+
+    Adr {
+        number,
+        title: decision.title.clone(),
+        date: Some(date.format("%Y-%m-%d").to_string()),
+        plan: Some(plan_name.to_string()),
+        status: Some("Accepted".to_string()),
+        sections,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PLAN_LOG: &str = r#"# Decision Log: add-auth
+
+Date: 2026-04-27
+
+## Interview
+
+**Q:** Which tokens?
+**A:** JWT.
+
+## Design Decisions
+
+### [1] Use JWT for sessions
+
+- **Decision:** Issue signed JWTs
+  with a 15 minute expiry.
+- **Alternatives:** Server-side sessions.
+- **Rationale:** Stateless services scale out.
+- **Promotes to ADR:** yes
+
+### [2] Name the cookie `sid`
+
+- **Decision:** Cookie name `sid`.
+- **Promotes to ADR:** no
+
+## Review Findings
+"#;
+
+    const PERMANENT_LOG: &str = "# Architecture Decision Records\n\n## ADR-001: Use line-oriented state machine\n\n**Date:** 2026-04-01\n**Plan:** add-decision-log-validation\n**Status:** Accepted\n\n### Context\n\nFlat markdown.\n\n### Decision\n\nLine scanning.\n";
+
+    fn date() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 5, 2).unwrap()
+    }
+
+    #[test]
+    fn promotes_marked_decisions_as_next_adr() {
+        let promotion =
+            promote_decisions(Some(PERMANENT_LOG), PLAN_LOG, "add-auth", date()).unwrap();
+
+        assert_eq!(promotion.promoted, vec!["ADR-002: Use JWT for sessions"]);
+        assert!(promotion.content.ends_with(
+            "## ADR-002: Use JWT for sessions\n\n**Date:** 2026-05-02\n**Plan:** add-auth\n**Status:** Accepted\n\n### Context\n\nStateless services scale out.\n\n### Decision\n\nIssue signed JWTs with a 15 minute expiry.\n\n### Options Considered\n\nServer-side sessions.\n"
+        ));
+        assert!(!promotion.content.contains("Name the cookie"));
+    }
+
+    #[test]
+    fn creates_permanent_log_when_missing() {
+        let promotion = promote_decisions(None, PLAN_LOG, "add-auth", date()).unwrap();
+
+        assert!(
+            promotion.content.starts_with(
+                "# Architecture Decision Records\n\n## ADR-001: Use JWT for sessions\n"
+            )
+        );
+    }
+
+    #[test]
+    fn skips_decisions_already_promoted_from_plan() {
+        let first = promote_decisions(Some(PERMANENT_LOG), PLAN_LOG, "add-auth", date()).unwrap();
+
+        let second = promote_decisions(Some(&first.content), PLAN_LOG, "add-auth", date()).unwrap();
+
+        assert!(second.promoted.is_empty());
+        assert_eq!(second.content, first.content);
+    }
+
+    #[test]
+    fn removes_promoted_adrs_at_end_of_log() {
+        let promotion =
+            promote_decisions(Some(PERMANENT_LOG), PLAN_LOG, "add-auth", date()).unwrap();
+
+        assert_eq!(
+            remove_promoted_adrs(&promotion.content, &[2]).as_deref(),
+            Some(PERMANENT_LOG)
+        );
+    }
+
+    #[test]
+    fn keeps_promoted_adrs_followed_or_mentioned_by_others() {
+        let promotion =
+            promote_decisions(Some(PERMANENT_LOG), PLAN_LOG, "add-auth", date()).unwrap();
+
+        assert_eq!(remove_promoted_adrs(&promotion.content, &[1]), None);
+        let mentioned = promotion
+            .content
+            .replace("Flat markdown.", "Flat markdown, see ADR-002.");
+        assert_eq!(remove_promoted_adrs(&mentioned, &[2]), None);
+    }
+
+    #[test]
+    fn refuses_to_extend_invalid_permanent_log() {
+        let broken = PERMANENT_LOG.replace("**Status:** Accepted", "**Status:** Pending");
+
+        let result = promote_decisions(Some(&broken), PLAN_LOG, "add-auth", date());
+
+        assert!(matches!(result, Err(PromoteError::InvalidResult { .. })));
+    }
+}
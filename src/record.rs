@@ -1,9 +1,38 @@
 use std::fs;
-use std::path::Path;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
 
-use chrono::Local;
+use chrono::{DateTime, Local, NaiveDate};
 use thiserror::Error;
 
+use crate::decision_log::DECISION_LOG_FILE;
+use crate::feature_ops::{
+    self, FeatureOperation, OperationError, PlannedOperation, ReferenceChange,
+};
+use crate::merge::{self, MergeConflict};
+use crate::plan_status::{self, PlanStatusError};
+use crate::promote::{self, PromoteError, Promotion};
+use crate::validate::{parser, report::ValidationError, rules};
+
+/// Snapshot of the permanent spec a delta was written against, stored next to the delta.
+pub const BASE_SNAPSHOT_FILE: &str = "base.md";
+
+/// Report of applied feature operations, written into the archive of plans that have any.
+pub const OPERATIONS_REPORT_FILE: &str = "feature-operations.md";
+
+/// Archive directory holding `<domain>/<feature>/before.md` and `after.md` for every
+/// permanent spec a recording changed, so the recording can be reverted.
+pub const RECORD_IMAGES_DIR: &str = "record-images";
+pub const PRE_IMAGE_FILE: &str = "before.md";
+pub const POST_IMAGE_FILE: &str = "after.md";
+
+/// Written into every archive, naming the plan and the features the recording touched.
+pub const ARCHIVE_MANIFEST_FILE: &str = "manifest.md";
+
+/// Written into the archive when only part of a plan was recorded, listing what was
+/// recorded and what was left in the plan.
+pub const PARTIAL_RECORD_FILE: &str = "partial-record.md";
+
 #[derive(Error, Debug)]
 pub enum RecordError {
     #[error("Plan not found: {0}")]
@@ -23,6 +52,294 @@ pub enum RecordError {
 
     #[error("Malformed delta marker at line {line}: {content}")]
     MalformedDelta { line: usize, content: String },
+
+    #[error("Merged specs would fail validation; nothing was recorded")]
+    InvalidMergedSpecs { failures: Vec<MergedSpecFailure> },
+
+    #[error("{}", format_target_errors(.errors))]
+    UnresolvedDeltaTargets { errors: Vec<DeltaTargetError> },
+
+    #[error("Permanent spec could not be parsed: {0}")]
+    UnparsablePermanentSpec(ValidationError),
+
+    #[error("{path}: {} merge conflict(s) with changes recorded since the plan's base snapshot", .conflicts.len())]
+    MergeConflicts {
+        path: String,
+        conflicts: Vec<MergeConflict>,
+    },
+
+    #[error("{}", .errors.iter().map(ToString::to_string).collect::<Vec<_>>().join("; "))]
+    InvalidFeatureOperations { errors: Vec<OperationError> },
+
+    #[error("Failed to apply feature operations: {0}")]
+    FeatureOperationFailed(String),
+
+    #[error("Failed to promote decisions: {0}")]
+    PromotionFailed(PromoteError),
+
+    #[error("Invalid SOURCE_DATE_EPOCH '{0}': expected seconds since 1970-01-01 UTC")]
+    InvalidSourceDateEpoch(String),
+
+    #[error("Plan '{plan}' has no delta spec or feature operation for: {}", .features.join(", "))]
+    UnknownFeatures { plan: String, features: Vec<String> },
+
+    #[error("{0}; use --force to record anyway")]
+    NotReady(PlanStatusError),
+
+    #[error("Plan '{plan}' has no delta spec for {feature}")]
+    NoDeltaSpec { plan: String, feature: String },
+
+    #[error("{path}: {source}")]
+    InDeltaFile {
+        path: String,
+        source: Box<RecordError>,
+    },
+}
+
+/// A delta block that cannot be applied to the permanent spec.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeltaTargetError {
+    pub line: usize,
+    pub kind: DeltaKind,
+    pub problem: DeltaTargetProblem,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DeltaTargetProblem {
+    MissingTitle,
+    NotFound {
+        title: String,
+        suggestions: Vec<String>,
+    },
+    Ambiguous {
+        title: String,
+        count: usize,
+    },
+    DuplicateTarget {
+        title: String,
+        first_line: usize,
+    },
+    AlreadyExists {
+        title: String,
+    },
+    MissingSection {
+        section: &'static str,
+    },
+    MissingReplacement,
+    Repeated {
+        first_line: usize,
+    },
+    MissingAnchor {
+        placement: &'static str,
+        anchor: String,
+        suggestions: Vec<String>,
+    },
+    ConflictingPlacement,
+    RemovedAnchor {
+        placement: &'static str,
+        anchor: String,
+        removed_line: usize,
+    },
+    UnsupportedPlacement,
+}
+
+impl std::fmt::Display for DeltaTargetError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let kind = &self.kind;
+        let noun = kind.target_noun();
+        match &self.problem {
+            DeltaTargetProblem::MissingTitle => {
+                write!(f, "DELTA:{kind} block has no {}", kind.target_hint())
+            }
+            DeltaTargetProblem::NotFound { title, suggestions } => {
+                write!(
+                    f,
+                    "DELTA:{kind} targets {noun} '{title}' which does not exist in the permanent spec"
+                )?;
+                write_suggestions(f, suggestions)
+            }
+            DeltaTargetProblem::Ambiguous { title, count } => write!(
+                f,
+                "DELTA:{kind} targets {noun} '{title}' which appears {count} times in the permanent spec"
+            ),
+            DeltaTargetProblem::DuplicateTarget { title, first_line } => write!(
+                f,
+                "DELTA:{kind} targets {noun} '{title}' which is already targeted by the block at line {first_line}"
+            ),
+            DeltaTargetProblem::AlreadyExists { title } => write!(
+                f,
+                "DELTA:{kind} adds {noun} '{title}' which already exists in the permanent spec (use DELTA:{} to modify it)",
+                kind.changed_kind()
+            ),
+            DeltaTargetProblem::MissingSection { section } => write!(
+                f,
+                "DELTA:{kind} needs a '{section}' section in the permanent spec"
+            ),
+            DeltaTargetProblem::MissingReplacement => write!(
+                f,
+                "DELTA:{kind} block must contain exactly one replacement '* ' bullet"
+            ),
+            DeltaTargetProblem::Repeated { first_line } => write!(
+                f,
+                "DELTA:{kind} repeats the block at line {first_line}; only one is allowed per delta file"
+            ),
+            DeltaTargetProblem::MissingAnchor {
+                placement,
+                anchor,
+                suggestions,
+            } => {
+                write!(
+                    f,
+                    "DELTA:{kind} is placed {placement} scenario '{anchor}' which does not exist in the permanent spec"
+                )?;
+                write_suggestions(f, suggestions)
+            }
+            DeltaTargetProblem::ConflictingPlacement => write!(
+                f,
+                "DELTA:{kind} block cannot have both after= and before= attributes"
+            ),
+            DeltaTargetProblem::RemovedAnchor {
+                placement,
+                anchor,
+                removed_line,
+            } => write!(
+                f,
+                "DELTA:{kind} is placed {placement} scenario '{anchor}' which the DELTA:REMOVED block at line {removed_line} removes"
+            ),
+            DeltaTargetProblem::UnsupportedPlacement => write!(
+                f,
+                "DELTA:{kind} block cannot have after= or before= attributes; only DELTA:NEW is placed"
+            ),
+        }
+    }
+}
+
+fn write_suggestions(f: &mut std::fmt::Formatter<'_>, suggestions: &[String]) -> std::fmt::Result {
+    if suggestions.is_empty() {
+        return Ok(());
+    }
+    let quoted: Vec<String> = suggestions.iter().map(|s| format!("'{s}'")).collect();
+    write!(f, " (did you mean {}?)", quoted.join(" or "))
+}
+
+fn format_target_errors(errors: &[DeltaTargetError]) -> String {
+    errors
+        .iter()
+        .map(|e| format!("line {}: {}", e.line, e))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// Validation errors for one feature, reported against the plan's delta file, or against
+/// the permanent spec when only its references to a moved feature were rewritten.
+#[derive(Debug)]
+pub struct MergedSpecFailure {
+    pub delta_path: String,
+    pub feature: String,
+    pub errors: Vec<ValidationError>,
+}
+
+/// Which parts of a plan `record_plan_with` records.
+#[derive(Debug, Default, Clone)]
+pub struct RecordOptions {
+    /// `domain/feature` paths to record; empty records the whole plan
+    pub features: Vec<String>,
+    /// Date stamped on the archive and promoted ADRs; see `record_date` for the fallback
+    pub date: Option<NaiveDate>,
+    /// Promote `Promotes to ADR: yes` decisions from the plan's decision log
+    pub promote_decisions: bool,
+    /// Record even if the plan is not verified or lacks a passing verification report
+    pub force: bool,
+}
+
+/// The date a recording is stamped with: `explicit` if given, else the UTC date of
+/// `SOURCE_DATE_EPOCH` when set, else today in local time.
+pub fn record_date(explicit: Option<NaiveDate>) -> Result<NaiveDate, RecordError> {
+    if let Some(date) = explicit {
+        return Ok(date);
+    }
+
+    match std::env::var("SOURCE_DATE_EPOCH") {
+        Ok(epoch) => date_from_epoch(&epoch),
+        Err(_) => Ok(Local::now().date_naive()),
+    }
+}
+
+fn date_from_epoch(epoch: &str) -> Result<NaiveDate, RecordError> {
+    epoch
+        .trim()
+        .parse::<i64>()
+        .ok()
+        .and_then(|seconds| DateTime::from_timestamp(seconds, 0))
+        .map(|timestamp| timestamp.date_naive())
+        .ok_or_else(|| RecordError::InvalidSourceDateEpoch(epoch.to_string()))
+}
+
+/// A feature's spec as `speq record` would write it, merged in memory.
+#[derive(Debug)]
+pub struct FeaturePreview {
+    pub feature: String,
+    /// The permanent spec before the plan; `None` for a new feature
+    pub current: Option<String>,
+    pub merged: String,
+}
+
+impl FeaturePreview {
+    /// The merged spec with the sections the plan changes marked for review
+    pub fn annotated(&self) -> String {
+        merge::annotate_changes(self.current.as_deref().unwrap_or_default(), &self.merged)
+    }
+}
+
+/// What `record_plan` changed in the permanent specs.
+#[derive(Debug)]
+pub struct RecordOutcome {
+    pub features: Vec<String>,
+    pub operations: Vec<FeatureOperation>,
+    pub reference_changes: Vec<ReferenceChange>,
+    pub archive_dir: PathBuf,
+    /// Delta features and operations left in the plan by a partial record
+    pub remaining: Vec<String>,
+    /// Headings of ADRs promoted into `specs/decision-log.md`
+    pub adrs: Vec<String>,
+    /// Existing features merged without a `base.md` snapshot, i.e. two-way
+    pub without_base: Vec<String>,
+}
+
+/// The deltas and operations of a plan that one recording applies.
+struct Selection {
+    delta_specs: Vec<PathBuf>,
+    operations: Vec<PlannedOperation>,
+    remaining: Vec<String>,
+}
+
+struct PendingSpec {
+    delta_path: PathBuf,
+    target_spec: PathBuf,
+    /// Where the feature's permanent spec is before recording (differs for moved features)
+    current_spec: PathBuf,
+    feature: String,
+    merged: String,
+    /// Merged into an existing spec without a base snapshot
+    without_base: bool,
+}
+
+/// Where `rewrite_plan_references` takes a spec's content from
+enum ReferenceSource {
+    /// Index of a merged spec
+    Pending(usize),
+    /// A permanent spec the plan has no delta for
+    Permanent { spec_path: PathBuf, content: String },
+}
+
+/// A permanent spec without a delta whose references to a moved feature change.
+struct RewrittenSpec {
+    /// `domain/feature` after recording
+    feature: String,
+    /// Where the spec is before recording (differs for moved features)
+    current_spec: PathBuf,
+    original: String,
+    content: String,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -30,6 +347,73 @@ pub enum DeltaKind {
     New,
     Changed,
     Removed,
+    /// Replaces the text between the `# Feature:` heading and the next heading
+    Description,
+    /// Replaces the `# Feature:` heading
+    Renamed,
+    BackgroundNew,
+    /// Replaces the Background fact named by the `was` attribute
+    BackgroundChanged,
+    BackgroundRemoved,
+}
+
+impl DeltaKind {
+    const ALL: [DeltaKind; 8] = [
+        DeltaKind::New,
+        DeltaKind::Changed,
+        DeltaKind::Removed,
+        DeltaKind::Description,
+        DeltaKind::Renamed,
+        DeltaKind::BackgroundNew,
+        DeltaKind::BackgroundChanged,
+        DeltaKind::BackgroundRemoved,
+    ];
+
+    fn from_marker(name: &str) -> Option<DeltaKind> {
+        Self::ALL.into_iter().find(|kind| kind.to_string() == name)
+    }
+
+    fn target_noun(&self) -> &'static str {
+        match self {
+            DeltaKind::New | DeltaKind::Changed | DeltaKind::Removed => "scenario",
+            DeltaKind::Description => "feature description",
+            DeltaKind::Renamed => "feature heading",
+            _ => "background fact",
+        }
+    }
+
+    /// What a block of this kind must contain to identify its target
+    fn target_hint(&self) -> &'static str {
+        match self {
+            DeltaKind::New | DeltaKind::Changed | DeltaKind::Removed => "'### Scenario:' title",
+            DeltaKind::Description => "description text",
+            DeltaKind::Renamed => "'# Feature:' heading",
+            DeltaKind::BackgroundChanged => "was=\"...\" attribute naming the fact to replace",
+            _ => "'* ' bullet",
+        }
+    }
+
+    fn changed_kind(&self) -> DeltaKind {
+        match self {
+            DeltaKind::BackgroundNew => DeltaKind::BackgroundChanged,
+            _ => DeltaKind::Changed,
+        }
+    }
+}
+
+impl std::fmt::Display for DeltaKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DeltaKind::New => write!(f, "NEW"),
+            DeltaKind::Changed => write!(f, "CHANGED"),
+            DeltaKind::Removed => write!(f, "REMOVED"),
+            DeltaKind::Description => write!(f, "DESCRIPTION"),
+            DeltaKind::Renamed => write!(f, "RENAMED"),
+            DeltaKind::BackgroundNew => write!(f, "BACKGROUND:NEW"),
+            DeltaKind::BackgroundChanged => write!(f, "BACKGROUND:CHANGED"),
+            DeltaKind::BackgroundRemoved => write!(f, "BACKGROUND:REMOVED"),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -37,64 +421,162 @@ pub struct DeltaBlock {
     pub kind: DeltaKind,
     pub content: String,
     pub scenario_title: Option<String>,
+    /// `key="value"` pairs from the opening marker
+    pub attributes: Vec<(String, String)>,
+    /// 1-based line of the opening marker in the delta file
+    pub line: usize,
+}
+
+impl DeltaBlock {
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Where a NEW scenario goes relative to an existing one, from its `after=` or `before=`
+    pub fn placement(&self) -> Option<Placement<'_>> {
+        match (self.attribute("after"), self.attribute("before")) {
+            (Some(anchor), _) => Some(Placement::After(anchor)),
+            (None, Some(anchor)) => Some(Placement::Before(anchor)),
+            (None, None) => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Placement<'a> {
+    After(&'a str),
+    Before(&'a str),
+}
+
+impl Placement<'_> {
+    fn anchor(&self) -> &str {
+        match self {
+            Placement::After(anchor) | Placement::Before(anchor) => anchor,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Placement::After(_) => "after",
+            Placement::Before(_) => "before",
+        }
+    }
+}
+
+pub fn record_plan(specs_base: &Path, plan_name: &str) -> Result<RecordOutcome, RecordError> {
+    record_plan_with(specs_base, plan_name, &RecordOptions::default())
 }
 
-pub fn record_plan(specs_base: &Path, plan_name: &str) -> Result<Vec<String>, RecordError> {
+/// Record a plan, or only the features selected in `options`.
+///
+/// A partial record moves the recorded deltas into the archive and leaves the rest of
+/// the plan in `_plans/`; the plan itself is archived once nothing is left in it.
+pub fn record_plan_with(
+    specs_base: &Path,
+    plan_name: &str,
+    options: &RecordOptions,
+) -> Result<RecordOutcome, RecordError> {
     let plan_dir = specs_base.join("_plans").join(plan_name);
-    let date_prefix = Local::now().format("%Y-%m-%d").to_string();
-    let recorded_dir = specs_base
-        .join("_recorded")
-        .join(format!("{}-{}", date_prefix, plan_name));
+    let date = record_date(options.date)?;
+    let date_prefix = date.format("%Y-%m-%d").to_string();
+    let recorded_dir = unique_archive_dir(specs_base, &date_prefix, plan_name);
 
     if !plan_dir.exists() {
         return Err(RecordError::PlanNotFound(plan_name.to_string()));
     }
+    if !options.force {
+        plan_status::check_ready_to_record(&plan_dir, plan_name).map_err(RecordError::NotReady)?;
+    }
 
-    let mut recorded_features = Vec::new();
+    let operations = load_operations(specs_base, &plan_dir)?;
+    let Selection {
+        delta_specs,
+        operations,
+        remaining,
+    } = select_plan_parts(plan_name, &plan_dir, operations, &options.features)?;
+
+    // Merge every delta and rewrite every reference in memory first, so nothing is written
+    // unless all of them are valid
+    let mut pending = merge_plan_specs(specs_base, &plan_dir, &delta_specs, &operations)?;
+    let (rewritten, reference_changes) =
+        rewrite_plan_references(specs_base, &mut pending, &operations)?;
+
+    let merged = pending
+        .iter()
+        .map(|spec| (&spec.delta_path, &spec.feature, &spec.merged));
+    let references = rewritten
+        .iter()
+        .map(|spec| (&spec.current_spec, &spec.feature, &spec.content));
+    let failures: Vec<MergedSpecFailure> = merged
+        .chain(references)
+        .filter_map(|(path, feature, content)| {
+            let errors = validate_merged_spec(content);
+            (!errors.is_empty()).then(|| MergedSpecFailure {
+                delta_path: path.display().to_string(),
+                feature: feature.clone(),
+                errors,
+            })
+        })
+        .collect();
+
+    if !failures.is_empty() {
+        return Err(RecordError::InvalidMergedSpecs { failures });
+    }
 
-    // Find all spec.md files in the plan
-    let delta_specs = find_delta_specs(&plan_dir)?;
+    let promotion = if options.promote_decisions {
+        prepare_promotion(specs_base, &plan_dir, plan_name, date)?
+    } else {
+        None
+    };
 
-    for delta_path in delta_specs {
-        let relative = delta_path
-            .strip_prefix(&plan_dir)
-            .unwrap()
-            .parent()
-            .unwrap();
+    let mut images = collect_pre_images(specs_base, &pending, &operations, &rewritten)?;
 
-        // Determine target path (domain/feature structure)
-        let target_dir = specs_base.join(relative);
-        let target_spec = target_dir.join("spec.md");
+    let operation_failed = |e: std::io::Error| RecordError::FeatureOperationFailed(e.to_string());
+    feature_ops::apply_operations(specs_base, &operations).map_err(operation_failed)?;
 
-        // Read delta content
-        let delta_content =
-            fs::read_to_string(&delta_path).map_err(|_| RecordError::FileReadError {
-                path: delta_path.display().to_string(),
-            })?;
+    let mut recorded_features = Vec::new();
+    let without_base: Vec<String> = pending
+        .iter()
+        .filter(|spec| spec.without_base)
+        .map(|spec| spec.feature.clone())
+        .collect();
 
-        let merged = if target_spec.exists() {
-            // Merge with existing spec
-            let existing =
-                fs::read_to_string(&target_spec).map_err(|_| RecordError::FileReadError {
-                    path: target_spec.display().to_string(),
-                })?;
-            merge_delta(&existing, &delta_content)?
-        } else {
-            // New feature - just strip markers
-            strip_delta_markers(&delta_content)
-        };
+    for spec in pending {
+        let target_dir = spec.target_spec.parent().unwrap();
 
         // Create target directory if needed
-        fs::create_dir_all(&target_dir).map_err(|_| RecordError::DirCreateError {
+        fs::create_dir_all(target_dir).map_err(|_| RecordError::DirCreateError {
             path: target_dir.display().to_string(),
         })?;
 
         // Write merged spec
-        fs::write(&target_spec, merged).map_err(|_| RecordError::FileWriteError {
-            path: target_spec.display().to_string(),
+        fs::write(&spec.target_spec, spec.merged).map_err(|_| RecordError::FileWriteError {
+            path: spec.target_spec.display().to_string(),
+        })?;
+
+        recorded_features.push(spec.feature);
+    }
+
+    for spec in &rewritten {
+        let spec_path = specs_base.join(&spec.feature).join("spec.md");
+        fs::write(&spec_path, &spec.content).map_err(|_| RecordError::FileWriteError {
+            path: spec_path.display().to_string(),
         })?;
+    }
+
+    if let Some(promotion) = &promotion {
+        promote::write_promotion(specs_base, promotion).map_err(RecordError::PromotionFailed)?;
+    }
 
-        recorded_features.push(relative.display().to_string());
+    for image in &mut images {
+        let spec_path = specs_base.join(&image.feature).join("spec.md");
+        image.after = spec_path
+            .exists()
+            .then(|| read_file(&spec_path))
+            .transpose()?;
     }
 
     // Archive the plan
@@ -104,699 +586,3004 @@ pub fn record_plan(specs_base: &Path, plan_name: &str) -> Result<Vec<String>, Re
         }
     })?;
 
-    fs::rename(&plan_dir, &recorded_dir).map_err(|_| RecordError::DirMoveError {
-        from: plan_dir.display().to_string(),
-        to: recorded_dir.display().to_string(),
+    if remaining.is_empty() {
+        fs::rename(&plan_dir, &recorded_dir).map_err(|_| RecordError::DirMoveError {
+            from: plan_dir.display().to_string(),
+            to: recorded_dir.display().to_string(),
+        })?;
+    } else {
+        archive_partial(&plan_dir, &recorded_dir, &recorded_features, &operations)?;
+        let report_path = recorded_dir.join(PARTIAL_RECORD_FILE);
+        let report = render_partial_report(plan_name, &recorded_features, &operations, &remaining);
+        fs::write(&report_path, report).map_err(|_| RecordError::FileWriteError {
+            path: report_path.display().to_string(),
+        })?;
+    }
+
+    write_images(&recorded_dir.join(RECORD_IMAGES_DIR), &images)?;
+
+    let manifest = ArchiveManifest {
+        plan_name: plan_name.to_string(),
+        recorded: date_prefix,
+        features: images.into_iter().map(|image| image.feature).collect(),
+        adrs: promotion
+            .as_ref()
+            .map(|p| p.promoted.clone())
+            .unwrap_or_default(),
+    };
+    let manifest_path = recorded_dir.join(ARCHIVE_MANIFEST_FILE);
+    fs::write(&manifest_path, manifest.render()).map_err(|_| RecordError::FileWriteError {
+        path: manifest_path.display().to_string(),
     })?;
 
-    Ok(recorded_features)
-}
+    let operations: Vec<FeatureOperation> = operations.into_iter().map(|p| p.operation).collect();
+    if !operations.is_empty() {
+        let report_path = recorded_dir.join(OPERATIONS_REPORT_FILE);
+        let report = render_operations_report(plan_name, &operations, &reference_changes);
+        fs::write(&report_path, report).map_err(|_| RecordError::FileWriteError {
+            path: report_path.display().to_string(),
+        })?;
+    }
 
-pub fn find_delta_specs(plan_dir: &Path) -> Result<Vec<std::path::PathBuf>, RecordError> {
-    let mut specs = Vec::new();
-    find_delta_specs_recursive(plan_dir, &mut specs)?;
-    Ok(specs)
+    Ok(RecordOutcome {
+        features: recorded_features,
+        operations,
+        reference_changes,
+        archive_dir: recorded_dir,
+        remaining,
+        adrs: promotion.map(|p| p.promoted).unwrap_or_default(),
+        without_base,
+    })
 }
 
-fn find_delta_specs_recursive(
-    dir: &Path,
-    specs: &mut Vec<std::path::PathBuf>,
-) -> Result<(), RecordError> {
-    let entries = fs::read_dir(dir).map_err(|_| RecordError::FileReadError {
-        path: dir.display().to_string(),
-    })?;
-
-    for entry in entries.flatten() {
-        let path = entry.path();
-        if path.is_dir() {
-            find_delta_specs_recursive(&path, specs)?;
-        } else if path.file_name().is_some_and(|n| n == "spec.md") {
-            specs.push(path);
-        }
+/// Merge one feature's delta the way `record_plan` would, without writing anything.
+///
+/// Uses the base snapshot and the plan's feature operations like a real record, so the
+/// preview fails where record would.
+pub fn preview_feature(
+    specs_base: &Path,
+    plan_name: &str,
+    feature: &str,
+) -> Result<FeaturePreview, RecordError> {
+    let plan_dir = specs_base.join("_plans").join(plan_name);
+    if !plan_dir.exists() {
+        return Err(RecordError::PlanNotFound(plan_name.to_string()));
+    }
+    let delta_path = plan_dir.join(feature).join("spec.md");
+    if !delta_path.exists() {
+        return Err(RecordError::NoDeltaSpec {
+            plan: plan_name.to_string(),
+            feature: feature.to_string(),
+        });
     }
 
-    Ok(())
+    let operations = load_operations(specs_base, &plan_dir)?;
+    let mut pending = merge_plan_specs(specs_base, &plan_dir, &[delta_path], &operations)?;
+    let Some(spec) = pending.pop() else {
+        return Err(RecordError::NoDeltaSpec {
+            plan: plan_name.to_string(),
+            feature: feature.to_string(),
+        });
+    };
+    let current = spec
+        .current_spec
+        .exists()
+        .then(|| read_file(&spec.current_spec))
+        .transpose()?;
+
+    Ok(FeaturePreview {
+        feature: spec.feature,
+        current,
+        merged: spec.merged,
+    })
 }
 
-pub fn parse_deltas(content: &str) -> Result<Vec<DeltaBlock>, RecordError> {
-    let mut deltas = Vec::new();
-    let mut current_kind: Option<DeltaKind> = None;
-    let mut current_content = String::new();
-    let mut in_delta = false;
+/// The permanent decision log with the plan's promoted decisions, if the plan has a log.
+fn prepare_promotion(
+    specs_base: &Path,
+    plan_dir: &Path,
+    plan_name: &str,
+    date: NaiveDate,
+) -> Result<Option<Promotion>, RecordError> {
+    let plan_log = plan_dir.join(DECISION_LOG_FILE);
+    if !plan_log.exists() {
+        return Ok(None);
+    }
 
-    for (line_num, line) in content.lines().enumerate() {
-        let trimmed = line.trim();
+    promote::prepare_promotion(specs_base, &plan_log, plan_name, date)
+        .map(Some)
+        .map_err(RecordError::PromotionFailed)
+}
 
-        if let Some(kind) = parse_delta_open(trimmed) {
-            if in_delta {
-                return Err(RecordError::MalformedDelta {
-                    line: line_num + 1,
-                    content: line.to_string(),
-                });
-            }
-            in_delta = true;
-            current_kind = Some(kind);
-            current_content.clear();
-        } else if let Some(close_kind) = parse_delta_close(trimmed) {
-            if !in_delta {
-                return Err(RecordError::MalformedDelta {
-                    line: line_num + 1,
-                    content: line.to_string(),
-                });
-            }
+/// Narrow the plan to the selected features; an empty selection keeps everything.
+///
+/// An operation is recorded together with every feature it touches, so selecting either
+/// end of a MOVE also records the delta at the other end.
+fn select_plan_parts(
+    plan_name: &str,
+    plan_dir: &Path,
+    operations: Vec<PlannedOperation>,
+    features: &[String],
+) -> Result<Selection, RecordError> {
+    let delta_specs = find_delta_specs(plan_dir)?;
+    if features.is_empty() {
+        return Ok(Selection {
+            delta_specs,
+            operations,
+            remaining: Vec::new(),
+        });
+    }
 
-            if current_kind.as_ref() != Some(&close_kind) {
-                return Err(RecordError::MalformedDelta {
-                    line: line_num + 1,
-                    content: line.to_string(),
-                });
-            }
+    let unknown: Vec<String> = features
+        .iter()
+        .filter(|feature| {
+            !delta_specs
+                .iter()
+                .any(|path| delta_feature(plan_dir, path) == **feature)
+                && !operations.iter().any(|p| p.operation.involves(feature))
+        })
+        .cloned()
+        .collect();
+    if !unknown.is_empty() {
+        return Err(RecordError::UnknownFeatures {
+            plan: plan_name.to_string(),
+            features: unknown,
+        });
+    }
 
-            let content_trimmed = current_content.trim().to_string();
-            let scenario_title = extract_scenario_title(&content_trimmed);
+    let (operations, remaining_operations): (Vec<_>, Vec<_>) = operations
+        .into_iter()
+        .partition(|p| features.iter().any(|feature| p.operation.involves(feature)));
+    let selected = |feature: &str| {
+        features.iter().any(|f| f == feature)
+            || operations.iter().any(|p| p.operation.involves(feature))
+    };
+    let (delta_specs, remaining_specs): (Vec<_>, Vec<_>) = delta_specs
+        .into_iter()
+        .partition(|path| selected(&delta_feature(plan_dir, path)));
+
+    let mut remaining: Vec<String> = remaining_specs
+        .iter()
+        .map(|path| delta_feature(plan_dir, path))
+        .collect();
+    remaining.extend(remaining_operations.iter().map(|p| p.operation.to_string()));
+
+    Ok(Selection {
+        delta_specs,
+        operations,
+        remaining,
+    })
+}
 
-            deltas.push(DeltaBlock {
-                kind: current_kind.take().unwrap(),
-                content: content_trimmed,
-                scenario_title,
-            });
+/// Move the recorded deltas into the archive and leave the rest of the plan in place.
+///
+/// The archive keeps plan.md as it was recorded; the plan's own copy loses the bullets of
+/// the operations just applied so they are not applied twice.
+fn archive_partial(
+    plan_dir: &Path,
+    recorded_dir: &Path,
+    features: &[String],
+    operations: &[PlannedOperation],
+) -> Result<(), RecordError> {
+    fs::create_dir_all(recorded_dir).map_err(|_| RecordError::DirCreateError {
+        path: recorded_dir.display().to_string(),
+    })?;
 
-            in_delta = false;
-            current_content.clear();
-        } else if in_delta {
-            if !current_content.is_empty() {
-                current_content.push('\n');
-            }
-            current_content.push_str(line);
-        }
+    for feature in features {
+        let from = plan_dir.join(feature);
+        let to = recorded_dir.join(feature);
+        let parent = to.parent().unwrap();
+        fs::create_dir_all(parent).map_err(|_| RecordError::DirCreateError {
+            path: parent.display().to_string(),
+        })?;
+        fs::rename(&from, &to).map_err(|_| RecordError::DirMoveError {
+            from: from.display().to_string(),
+            to: to.display().to_string(),
+        })?;
+        // Drop the domain directory once its last delta is recorded
+        let _ = fs::remove_dir(from.parent().unwrap());
     }
 
-    if in_delta {
-        return Err(RecordError::MalformedDelta {
-            line: content.lines().count(),
-            content: current_content,
-        });
+    let plan_md = plan_dir.join("plan.md");
+    if !plan_md.exists() {
+        return Ok(());
     }
 
-    Ok(deltas)
-}
+    let content = read_file(&plan_md)?;
+    let archived = recorded_dir.join("plan.md");
+    fs::write(&archived, &content).map_err(|_| RecordError::FileWriteError {
+        path: archived.display().to_string(),
+    })?;
 
-fn parse_delta_open(line: &str) -> Option<DeltaKind> {
-    if line == "<!-- DELTA:NEW -->" {
-        Some(DeltaKind::New)
-    } else if line == "<!-- DELTA:CHANGED -->" {
-        Some(DeltaKind::Changed)
-    } else if line == "<!-- DELTA:REMOVED -->" {
-        Some(DeltaKind::Removed)
-    } else {
-        None
+    if !operations.is_empty() {
+        let lines: Vec<usize> = operations.iter().map(|p| p.line).collect();
+        fs::write(
+            &plan_md,
+            feature_ops::remove_operation_lines(&content, &lines),
+        )
+        .map_err(|_| RecordError::FileWriteError {
+            path: plan_md.display().to_string(),
+        })?;
     }
+
+    Ok(())
 }
 
-fn parse_delta_close(line: &str) -> Option<DeltaKind> {
-    if line == "<!-- /DELTA:NEW -->" {
-        Some(DeltaKind::New)
-    } else if line == "<!-- /DELTA:CHANGED -->" {
-        Some(DeltaKind::Changed)
-    } else if line == "<!-- /DELTA:REMOVED -->" {
-        Some(DeltaKind::Removed)
-    } else {
-        None
+fn render_partial_report(
+    plan_name: &str,
+    features: &[String],
+    operations: &[PlannedOperation],
+    remaining: &[String],
+) -> String {
+    let mut report = format!("# Partial Record: {}\n\n## Recorded\n\n", plan_name);
+    for feature in features {
+        report.push_str(&format!("* {}\n", feature));
+    }
+    for planned in operations {
+        report.push_str(&format!("* {}\n", planned.operation));
     }
-}
 
-fn extract_scenario_title(content: &str) -> Option<String> {
-    for line in content.lines() {
-        let trimmed = line.trim();
-        if trimmed.starts_with("### Scenario:") {
-            return Some(
-                trimmed
-                    .trim_start_matches("### Scenario:")
-                    .trim()
-                    .to_string(),
-            );
-        }
+    report.push_str("\n## Left in Plan\n\n");
+    for item in remaining {
+        report.push_str(&format!("* {}\n", item));
     }
-    None
-}
 
-pub fn merge_delta(existing: &str, delta: &str) -> Result<String, RecordError> {
-    let deltas = parse_deltas(delta)?;
+    report
+}
 
-    if deltas.is_empty() {
-        return Ok(existing.to_string());
-    }
+/// `_recorded/<date>-<plan>`, suffixed `-2`, `-3`, … when that name is already taken,
+/// e.g. by a plan of the same name recorded earlier the same day.
+fn unique_archive_dir(specs_base: &Path, date_prefix: &str, plan_name: &str) -> PathBuf {
+    let recorded = specs_base.join("_recorded");
+    let name = format!("{}-{}", date_prefix, plan_name);
+    std::iter::once(name.clone())
+        .chain((2..).map(|n| format!("{}-{}", name, n)))
+        .map(|candidate| recorded.join(candidate))
+        .find(|dir| !dir.exists())
+        .unwrap()
+}
 
-    let mut result = existing.to_string();
+/// The plan an archive holds and the permanent specs its recording touched.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArchiveManifest {
+    pub plan_name: String,
+    /// Recording date, `YYYY-MM-DD`
+    pub recorded: String,
+    /// `domain/feature` paths after recording; deleted features keep their old path
+    pub features: Vec<String>,
+    /// Headings of the ADRs `--promote-adrs` appended, e.g. `ADR-004: Use line scanning`
+    pub adrs: Vec<String>,
+}
 
-    for delta_block in deltas {
-        match delta_block.kind {
-            DeltaKind::New => {
-                // Append new scenario before the final empty lines
-                let trimmed = result.trim_end();
-                result = format!("{}\n\n{}\n", trimmed, delta_block.content);
-            }
-            DeltaKind::Changed => {
-                if let Some(title) = &delta_block.scenario_title {
-                    result = replace_scenario(&result, title, &delta_block.content);
-                }
-            }
-            DeltaKind::Removed => {
-                if let Some(title) = &delta_block.scenario_title {
-                    result = remove_scenario(&result, title);
-                }
+impl ArchiveManifest {
+    pub fn render(&self) -> String {
+        let mut manifest = format!(
+            "# Recorded Plan: {}\n\n* Recorded: {}\n\n## Features\n\n",
+            self.plan_name, self.recorded
+        );
+        for feature in &self.features {
+            manifest.push_str(&format!("* {}\n", feature));
+        }
+        if !self.adrs.is_empty() {
+            manifest.push_str("\n## Promoted ADRs\n\n");
+            for adr in &self.adrs {
+                manifest.push_str(&format!("* {}\n", adr));
             }
         }
+        manifest
     }
 
-    Ok(result)
-}
-
-fn replace_scenario(content: &str, title: &str, replacement: &str) -> String {
-    let pattern = format!("### Scenario: {}", title);
-    let lines: Vec<&str> = content.lines().collect();
-    let mut result = Vec::new();
-    let mut i = 0;
-
-    while i < lines.len() {
-        if lines[i].trim().starts_with(&pattern) {
-            // Skip old scenario until next heading or end
-            while i < lines.len() {
-                i += 1;
-                if i >= lines.len() {
-                    break;
-                }
-                let next_trimmed = lines[i].trim();
-                if next_trimmed.starts_with("### ") || next_trimmed.starts_with("## ") {
-                    break;
+    pub fn parse(content: &str) -> Option<Self> {
+        let mut lines = content.lines();
+        let plan_name = lines.next()?.strip_prefix("# Recorded Plan: ")?.trim();
+        let mut recorded = None;
+        let mut features = Vec::new();
+        let mut adrs = Vec::new();
+        let mut section = "";
+
+        for line in lines {
+            if let Some(heading) = line.strip_prefix("## ") {
+                section = heading.trim();
+            } else if let Some(item) = line.strip_prefix("* ") {
+                match (section, item.strip_prefix("Recorded: ")) {
+                    ("", Some(date)) => recorded = Some(date.trim().to_string()),
+                    ("Features", _) => features.push(item.trim().to_string()),
+                    ("Promoted ADRs", _) => adrs.push(item.trim().to_string()),
+                    _ => {}
                 }
             }
-            // Insert replacement
-            result.push(replacement);
-            // Skip empty lines that might be between scenarios
-            while i < lines.len() && lines[i].trim().is_empty() {
-                i += 1;
-            }
-        } else {
-            result.push(lines[i]);
-            i += 1;
         }
-    }
 
-    result.join("\n")
+        Some(ArchiveManifest {
+            plan_name: plan_name.to_string(),
+            recorded: recorded?,
+            features,
+            adrs,
+        })
+    }
 }
 
-fn remove_scenario(content: &str, title: &str) -> String {
-    let pattern = format!("### Scenario: {}", title);
-    let lines: Vec<&str> = content.lines().collect();
-    let mut result = Vec::new();
-    let mut i = 0;
-    let mut last_was_empty = false;
+/// The plan an archive directory under `specs/_recorded/` holds. Suffixed archive names
+/// (`…-2`) only resolve through the manifest; older archives fall back to the directory
+/// name without its `YYYY-MM-DD-` prefix.
+pub fn recorded_plan_name(archive_dir: &Path) -> String {
+    if let Some(manifest) = fs::read_to_string(archive_dir.join(ARCHIVE_MANIFEST_FILE))
+        .ok()
+        .and_then(|content| ArchiveManifest::parse(&content))
+    {
+        return manifest.plan_name;
+    }
 
-    while i < lines.len() {
-        if lines[i].trim().starts_with(&pattern) {
-            // Skip scenario until next heading
-            while i < lines.len() {
-                i += 1;
-                if i >= lines.len() {
-                    break;
-                }
-                let next_trimmed = lines[i].trim();
-                if next_trimmed.starts_with("### ") || next_trimmed.starts_with("## ") {
-                    break;
-                }
-            }
-            // Skip trailing empty lines
-            while i < lines.len() && lines[i].trim().is_empty() {
-                i += 1;
+    let archive_name = archive_dir
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let dated = archive_name
+        .get(..11)
+        .filter(|prefix| prefix.ends_with('-'))
+        .is_some_and(|prefix| NaiveDate::parse_from_str(&prefix[..10], "%Y-%m-%d").is_ok());
+    if dated {
+        archive_name[11..].to_string()
+    } else {
+        archive_name
+    }
+}
+
+/// A permanent spec before and after recording; `None` when it does not exist.
+struct FeatureImage {
+    feature: String,
+    before: Option<String>,
+    after: Option<String>,
+}
+
+/// Capture every permanent spec the recording is about to change, keyed by the
+/// feature path it will have afterwards (deleted features keep their own path).
+fn collect_pre_images(
+    specs_base: &Path,
+    pending: &[PendingSpec],
+    operations: &[PlannedOperation],
+    rewritten: &[RewrittenSpec],
+) -> Result<Vec<FeatureImage>, RecordError> {
+    let read_existing = |path: &Path| path.exists().then(|| read_file(path)).transpose();
+    let mut images = Vec::new();
+
+    for spec in pending {
+        images.push(FeatureImage {
+            feature: spec.feature.clone(),
+            before: read_existing(&spec.current_spec)?,
+            after: None,
+        });
+    }
+
+    for planned in operations {
+        let source = planned.operation.source();
+        let feature = planned
+            .operation
+            .destination()
+            .unwrap_or(source)
+            .to_string();
+        if images.iter().any(|image| image.feature == feature) {
+            continue;
+        }
+        images.push(FeatureImage {
+            feature,
+            before: read_existing(&source.spec_path(specs_base))?,
+            after: None,
+        });
+    }
+
+    for spec in rewritten {
+        if images.iter().any(|image| image.feature == spec.feature) {
+            continue;
+        }
+        images.push(FeatureImage {
+            feature: spec.feature.clone(),
+            before: Some(spec.original.clone()),
+            after: None,
+        });
+    }
+
+    Ok(images)
+}
+
+/// Point references to moved features at their new paths, in the merged specs and in every
+/// other permanent spec as it will be after the operations. Returns the other specs whose
+/// content changes and every reference change.
+fn rewrite_plan_references(
+    specs_base: &Path,
+    pending: &mut [PendingSpec],
+    operations: &[PlannedOperation],
+) -> Result<(Vec<RewrittenSpec>, Vec<ReferenceChange>), RecordError> {
+    if operations.is_empty() {
+        return Ok((Vec::new(), Vec::new()));
+    }
+
+    // Each spec at its path after recording, with where its content comes from
+    let mut sources: Vec<(String, ReferenceSource)> = Vec::new();
+    for feature in crate::feature::discover_features(specs_base) {
+        let Some(target) = feature_ops::final_location(operations, &feature.to_string()) else {
+            continue;
+        };
+        if pending.iter().any(|spec| spec.feature == target) {
+            continue;
+        }
+        let spec_path = feature.spec_path(specs_base);
+        let content = read_file(&spec_path)?;
+        sources.push((target, ReferenceSource::Permanent { spec_path, content }));
+    }
+    sources.extend(
+        pending
+            .iter()
+            .enumerate()
+            .map(|(index, spec)| (spec.feature.clone(), ReferenceSource::Pending(index))),
+    );
+    sources.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut specs: Vec<(String, String)> = sources
+        .iter()
+        .map(|(feature, source)| {
+            let content = match source {
+                ReferenceSource::Pending(index) => pending[*index].merged.clone(),
+                ReferenceSource::Permanent { content, .. } => content.clone(),
+            };
+            (feature.clone(), content)
+        })
+        .collect();
+    let changes = feature_ops::rewrite_spec_references(&mut specs, operations);
+
+    let mut rewritten = Vec::new();
+    for ((feature, content), (_, source)) in specs.into_iter().zip(sources) {
+        match source {
+            ReferenceSource::Pending(index) => pending[index].merged = content,
+            ReferenceSource::Permanent {
+                spec_path,
+                content: original,
+            } => {
+                if content != original {
+                    rewritten.push(RewrittenSpec {
+                        feature,
+                        current_spec: spec_path,
+                        original,
+                        content,
+                    });
+                }
+            }
+        }
+    }
+
+    Ok((rewritten, changes))
+}
+
+fn write_images(images_dir: &Path, images: &[FeatureImage]) -> Result<(), RecordError> {
+    for image in images {
+        let dir = images_dir.join(&image.feature);
+        fs::create_dir_all(&dir).map_err(|_| RecordError::DirCreateError {
+            path: dir.display().to_string(),
+        })?;
+
+        let files = [
+            (PRE_IMAGE_FILE, &image.before),
+            (POST_IMAGE_FILE, &image.after),
+        ];
+        for (name, content) in files {
+            let Some(content) = content else { continue };
+            let path = dir.join(name);
+            fs::write(&path, content).map_err(|_| RecordError::FileWriteError {
+                path: path.display().to_string(),
+            })?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Parse the plan's feature operations and check them against the permanent specs.
+fn load_operations(
+    specs_base: &Path,
+    plan_dir: &Path,
+) -> Result<Vec<PlannedOperation>, RecordError> {
+    let plan_md = plan_dir.join("plan.md");
+    if !plan_md.exists() {
+        return Ok(Vec::new());
+    }
+
+    let (operations, mut errors) = feature_ops::parse_operations(&read_file(&plan_md)?);
+    let delta_features: Vec<String> = find_delta_specs(plan_dir)?
+        .iter()
+        .map(|delta_path| delta_feature(plan_dir, delta_path))
+        .collect();
+    errors.extend(feature_ops::check_operations(
+        specs_base,
+        &operations,
+        &delta_features,
+    ));
+
+    if !errors.is_empty() {
+        return Err(RecordError::InvalidFeatureOperations { errors });
+    }
+    Ok(operations)
+}
+
+/// The `domain/feature` path a delta spec in the plan directory targets
+fn delta_feature(plan_dir: &Path, delta_path: &Path) -> String {
+    delta_path
+        .strip_prefix(plan_dir)
+        .ok()
+        .and_then(Path::parent)
+        .map(|relative| relative.display().to_string())
+        .unwrap_or_default()
+}
+
+fn render_operations_report(
+    plan_name: &str,
+    operations: &[FeatureOperation],
+    reference_changes: &[ReferenceChange],
+) -> String {
+    let mut report = format!("# Feature Operations: {}\n\n## Applied\n\n", plan_name);
+    for operation in operations {
+        report.push_str(&format!("* {}\n", operation));
+    }
+
+    report.push_str("\n## Cross-references\n\n");
+    if reference_changes.is_empty() {
+        report.push_str("* None\n");
+    }
+    for change in reference_changes {
+        report.push_str(&format!("* {}\n", change));
+    }
+
+    report
+}
+
+fn merge_plan_specs(
+    specs_base: &Path,
+    plan_dir: &Path,
+    delta_specs: &[PathBuf],
+    operations: &[PlannedOperation],
+) -> Result<Vec<PendingSpec>, RecordError> {
+    let mut pending = Vec::new();
+
+    for delta_path in delta_specs {
+        let delta_path = delta_path.clone();
+        let feature = delta_feature(plan_dir, &delta_path);
+
+        // Determine target path (domain/feature structure); a moved feature's
+        // current spec still lives at its old path
+        let target_spec = specs_base.join(&feature).join("spec.md");
+        let current_spec = specs_base
+            .join(feature_ops::current_location(operations, &feature))
+            .join("spec.md");
+
+        // Read delta content
+        let delta_content =
+            fs::read_to_string(&delta_path).map_err(|_| RecordError::FileReadError {
+                path: delta_path.display().to_string(),
+            })?;
+
+        let without_base =
+            current_spec.exists() && !delta_path.with_file_name(BASE_SNAPSHOT_FILE).exists();
+        let merged = if current_spec.exists() {
+            // Merge with existing spec
+            merge_into_existing(&delta_path, &current_spec, &delta_content)?
+        } else {
+            // New feature - just strip markers
+            strip_delta_markers(&delta_content)
+        };
+
+        pending.push(PendingSpec {
+            feature,
+            delta_path,
+            target_spec,
+            current_spec,
+            merged,
+            without_base,
+        });
+    }
+
+    Ok(pending)
+}
+
+fn merge_into_existing(
+    delta_path: &Path,
+    target_spec: &Path,
+    delta_content: &str,
+) -> Result<String, RecordError> {
+    let in_delta_file = |e: RecordError| RecordError::InDeltaFile {
+        path: delta_path.display().to_string(),
+        source: Box::new(e),
+    };
+
+    let existing = read_file(target_spec)?;
+
+    // Without a base snapshot the delta is applied to the spec as it is now
+    let base_path = delta_path.with_file_name(BASE_SNAPSHOT_FILE);
+    if !base_path.exists() {
+        return merge_delta(&existing, delta_content).map_err(in_delta_file);
+    }
+
+    let base = read_file(&base_path)?;
+    let planned = merge_delta(&base, delta_content).map_err(in_delta_file)?;
+    if existing == base {
+        return Ok(planned);
+    }
+
+    let outcome = merge::three_way_merge(&base, &planned, &existing);
+    if !outcome.is_clean() {
+        return Err(RecordError::MergeConflicts {
+            path: delta_path.display().to_string(),
+            conflicts: outcome.conflicts,
+        });
+    }
+
+    Ok(outcome.content)
+}
+
+fn read_file(path: &Path) -> Result<String, RecordError> {
+    fs::read_to_string(path).map_err(|_| RecordError::FileReadError {
+        path: path.display().to_string(),
+    })
+}
+
+/// Snapshot the current permanent spec next to each delta as the plan's merge base.
+///
+/// Existing snapshots are kept unless `refresh` is set. Returns the features snapshotted.
+pub fn snapshot_bases(
+    specs_base: &Path,
+    plan_name: &str,
+    refresh: bool,
+) -> Result<Vec<String>, RecordError> {
+    let plan_dir = specs_base.join("_plans").join(plan_name);
+    if !plan_dir.exists() {
+        return Err(RecordError::PlanNotFound(plan_name.to_string()));
+    }
+
+    let plan_md = plan_dir.join("plan.md");
+    let operations = if plan_md.exists() {
+        feature_ops::parse_operations(&read_file(&plan_md)?).0
+    } else {
+        Vec::new()
+    };
+
+    let mut snapshotted = Vec::new();
+
+    for delta_path in find_delta_specs(&plan_dir)? {
+        let feature = delta_feature(&plan_dir, &delta_path);
+        let target_spec = specs_base
+            .join(feature_ops::current_location(&operations, &feature))
+            .join("spec.md");
+        let base_path = delta_path.with_file_name(BASE_SNAPSHOT_FILE);
+
+        if !target_spec.exists() || (base_path.exists() && !refresh) {
+            continue;
+        }
+
+        fs::copy(&target_spec, &base_path).map_err(|_| RecordError::FileWriteError {
+            path: base_path.display().to_string(),
+        })?;
+        snapshotted.push(feature);
+    }
+
+    Ok(snapshotted)
+}
+
+fn validate_merged_spec(content: &str) -> Vec<ValidationError> {
+    match parser::parse(content) {
+        Ok(parsed) => rules::validate(&parsed.spec).errors,
+        Err(e) => vec![e],
+    }
+}
+
+pub fn find_delta_specs(plan_dir: &Path) -> Result<Vec<PathBuf>, RecordError> {
+    let mut specs = Vec::new();
+    find_delta_specs_recursive(plan_dir, &mut specs)?;
+    Ok(specs)
+}
+
+fn find_delta_specs_recursive(dir: &Path, specs: &mut Vec<PathBuf>) -> Result<(), RecordError> {
+    let entries = fs::read_dir(dir).map_err(|_| RecordError::FileReadError {
+        path: dir.display().to_string(),
+    })?;
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            find_delta_specs_recursive(&path, specs)?;
+        } else if path.file_name().is_some_and(|n| n == "spec.md") {
+            specs.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+pub fn parse_deltas(content: &str) -> Result<Vec<DeltaBlock>, RecordError> {
+    let mut deltas = Vec::new();
+    let mut current_kind: Option<DeltaKind> = None;
+    let mut current_attributes = Vec::new();
+    let mut current_content = String::new();
+    let mut current_line = 0;
+    let mut in_delta = false;
+
+    for (line_num, line) in content.lines().enumerate() {
+        let trimmed = line.trim();
+
+        if let Some((kind, attributes)) = parse_delta_open(trimmed) {
+            if in_delta {
+                return Err(RecordError::MalformedDelta {
+                    line: line_num + 1,
+                    content: line.to_string(),
+                });
+            }
+            in_delta = true;
+            current_kind = Some(kind);
+            current_attributes = attributes;
+            current_line = line_num + 1;
+            current_content.clear();
+        } else if let Some(close_kind) = parse_delta_close(trimmed) {
+            if !in_delta {
+                return Err(RecordError::MalformedDelta {
+                    line: line_num + 1,
+                    content: line.to_string(),
+                });
+            }
+
+            if current_kind.as_ref() != Some(&close_kind) {
+                return Err(RecordError::MalformedDelta {
+                    line: line_num + 1,
+                    content: line.to_string(),
+                });
+            }
+
+            let content_trimmed = current_content.trim().to_string();
+            let scenario_title = extract_scenario_title(&content_trimmed);
+            let kind = current_kind.take().unwrap();
+            let attributes = std::mem::take(&mut current_attributes);
+
+            // The unplaced empty NEW slot `speq plan new` leaves changes nothing; any other
+            // empty block is kept so the target check reports it
+            let is_new_slot =
+                kind == DeltaKind::New && attributes.is_empty() && content_trimmed.is_empty();
+            if !is_new_slot {
+                deltas.push(DeltaBlock {
+                    kind,
+                    content: content_trimmed,
+                    scenario_title,
+                    attributes,
+                    line: current_line,
+                });
+            }
+
+            in_delta = false;
+            current_content.clear();
+        } else if is_delta_marker(trimmed) {
+            return Err(RecordError::MalformedDelta {
+                line: line_num + 1,
+                content: line.to_string(),
+            });
+        } else if in_delta {
+            if !current_content.is_empty() {
+                current_content.push('\n');
+            }
+            current_content.push_str(line);
+        }
+    }
+
+    if in_delta {
+        return Err(RecordError::MalformedDelta {
+            line: content.lines().count(),
+            content: current_content,
+        });
+    }
+
+    Ok(deltas)
+}
+
+/// Parse an opening marker `<!-- DELTA:KIND key="value" ... -->` into its kind and attributes.
+pub fn parse_delta_open(line: &str) -> Option<(DeltaKind, Vec<(String, String)>)> {
+    let inner = line.strip_prefix("<!-- DELTA:")?.strip_suffix("-->")?;
+    let (name, rest) = inner.split_once(' ').unwrap_or((inner, ""));
+    let kind = DeltaKind::from_marker(name)?;
+    Some((kind, parse_attributes(rest)?))
+}
+
+pub fn parse_delta_close(line: &str) -> Option<DeltaKind> {
+    let inner = line.strip_prefix("<!-- /DELTA:")?.strip_suffix("-->")?;
+    DeltaKind::from_marker(inner.trim())
+}
+
+/// Whether a line looks like a delta marker, whether or not it is well-formed
+pub fn is_delta_marker(line: &str) -> bool {
+    line.starts_with("<!-- DELTA:") || line.starts_with("<!-- /DELTA:")
+}
+
+fn parse_attributes(mut rest: &str) -> Option<Vec<(String, String)>> {
+    let mut attributes = Vec::new();
+
+    loop {
+        rest = rest.trim_start();
+        if rest.is_empty() {
+            return Some(attributes);
+        }
+
+        let (key, quoted) = rest.split_once("=\"")?;
+        let valid_key = !key.is_empty()
+            && key
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !valid_key {
+            return None;
+        }
+
+        let (value, remainder) = quoted.split_once('"')?;
+        attributes.push((key.to_string(), value.to_string()));
+        rest = remainder;
+    }
+}
+
+fn extract_scenario_title(content: &str) -> Option<String> {
+    let parsed = parser::parse(content).ok()?;
+    parsed
+        .spec
+        .scenarios
+        .into_iter()
+        .find(|s| content[s.span.clone()].starts_with("### Scenario:"))
+        .map(|s| s.name)
+}
+
+pub fn merge_delta(existing: &str, delta: &str) -> Result<String, RecordError> {
+    let deltas = parse_deltas(delta)?;
+
+    if deltas.is_empty() {
+        return Ok(existing.to_string());
+    }
+
+    let errors =
+        check_delta_targets(existing, &deltas).map_err(RecordError::UnparsablePermanentSpec)?;
+    if !errors.is_empty() {
+        return Err(RecordError::UnresolvedDeltaTargets { errors });
+    }
+
+    let result = deltas
+        .iter()
+        .fold(existing.to_string(), |result, delta_block| {
+            apply_delta(&result, delta_block)
+        });
+
+    Ok(result)
+}
+
+fn apply_delta(content: &str, delta: &DeltaBlock) -> String {
+    let title = delta.scenario_title.as_deref();
+    match (&delta.kind, title) {
+        (DeltaKind::New, _) => insert_scenario(content, &delta.content, delta.placement()),
+        (DeltaKind::Changed, Some(title)) => replace_scenario(content, title, &delta.content),
+        (DeltaKind::Removed, Some(title)) => remove_scenario(content, title),
+        (DeltaKind::Description, _) => replace_description(content, &delta.content),
+        (DeltaKind::Renamed, _) => rename_feature(content, &delta.content),
+        (DeltaKind::BackgroundNew, _) => add_background_facts(content, &delta.content),
+        (DeltaKind::BackgroundChanged, _) => match delta.attribute("was") {
+            Some(was) => replace_background_fact(content, was, &delta.content),
+            None => content.to_string(),
+        },
+        (DeltaKind::BackgroundRemoved, _) => parser::bullet_items(&delta.content)
+            .iter()
+            .fold(content.to_string(), |result, fact| {
+                remove_background_fact(&result, &fact.text)
+            }),
+        _ => content.to_string(),
+    }
+}
+
+/// Check delta blocks against the permanent spec in `existing`: CHANGED and REMOVED blocks
+/// must name exactly one existing scenario or Background fact, NEW blocks must not reuse an
+/// existing one, and feature-level blocks need the section they replace. A permanent spec
+/// that cannot be parsed is returned as the error instead of checking any block against it.
+pub fn check_delta_targets(
+    existing: &str,
+    deltas: &[DeltaBlock],
+) -> Result<Vec<DeltaTargetError>, ValidationError> {
+    let targets = SpecTargets {
+        removed: deltas
+            .iter()
+            .filter(|delta| delta.kind == DeltaKind::Removed)
+            .filter_map(|delta| Some((delta.scenario_title.clone()?, delta.line)))
+            .collect(),
+        ..SpecTargets::parse(existing)?
+    };
+    let mut claimed = ClaimedTargets::default();
+    let mut errors = Vec::new();
+
+    for delta in deltas {
+        let placed = delta.attribute("after").is_some() || delta.attribute("before").is_some();
+        let problems: Vec<DeltaTargetProblem> = match delta.kind {
+            _ if placed && delta.kind != DeltaKind::New => {
+                vec![DeltaTargetProblem::UnsupportedPlacement]
+            }
+            DeltaKind::New | DeltaKind::Changed | DeltaKind::Removed => {
+                check_scenario_delta(delta, &targets, &mut claimed)
+                    .into_iter()
+                    .collect()
+            }
+            DeltaKind::Description | DeltaKind::Renamed => {
+                check_feature_delta(delta, &targets, &mut claimed)
+                    .into_iter()
+                    .collect()
+            }
+            _ => check_background_delta(delta, &targets, &mut claimed),
+        };
+
+        errors.extend(problems.into_iter().map(|problem| DeltaTargetError {
+            line: delta.line,
+            kind: delta.kind.clone(),
+            problem,
+        }));
+    }
+
+    Ok(errors)
+}
+
+/// What a permanent spec offers for delta blocks to target
+#[derive(Default)]
+struct SpecTargets {
+    scenarios: Vec<String>,
+    facts: Vec<String>,
+    has_heading: bool,
+    has_background: bool,
+    /// Scenarios the delta's own REMOVED blocks delete, with the block's line
+    removed: Vec<(String, usize)>,
+}
+
+impl SpecTargets {
+    fn parse(content: &str) -> Result<Self, ValidationError> {
+        let spec = parser::parse(content)?.spec;
+        Ok(Self {
+            scenarios: spec.scenarios.into_iter().map(|s| s.name).collect(),
+            facts: spec.background_facts.into_iter().map(|f| f.text).collect(),
+            has_heading: spec.heading_span.is_some(),
+            has_background: spec.background_span.is_some(),
+            removed: Vec::new(),
+        })
+    }
+}
+
+/// Targets already named by earlier blocks in the same delta file, with the block's line
+#[derive(Default)]
+struct ClaimedTargets(Vec<(&'static str, String, usize)>);
+
+impl ClaimedTargets {
+    /// Record `title` as targeted at `line`, returning the line of an earlier claim
+    fn claim(&mut self, noun: &'static str, title: &str, line: usize) -> Option<usize> {
+        let earlier = self
+            .0
+            .iter()
+            .find(|(n, t, _)| *n == noun && t == title)
+            .map(|(_, _, l)| *l);
+        self.0.push((noun, title.to_string(), line));
+        earlier
+    }
+
+    fn contains(&self, noun: &str, title: &str) -> bool {
+        self.0.iter().any(|(n, t, _)| *n == noun && t == title)
+    }
+}
+
+fn check_scenario_delta(
+    delta: &DeltaBlock,
+    targets: &SpecTargets,
+    claimed: &mut ClaimedTargets,
+) -> Option<DeltaTargetProblem> {
+    if delta.content.is_empty() {
+        return Some(DeltaTargetProblem::MissingTitle);
+    }
+    if delta.kind == DeltaKind::New
+        && let Some(problem) = check_placement(delta, targets, claimed)
+    {
+        return Some(problem);
+    }
+
+    let Some(title) = &delta.scenario_title else {
+        return (delta.kind != DeltaKind::New).then_some(DeltaTargetProblem::MissingTitle);
+    };
+
+    let count = targets.scenarios.iter().filter(|t| *t == title).count();
+    let earlier = claimed.claim("scenario", title, delta.line);
+
+    if delta.kind == DeltaKind::New {
+        check_new_target(title, count, earlier)
+    } else {
+        check_existing_target(title, count, earlier, &targets.scenarios)
+    }
+}
+
+/// The anchor of an `after=`/`before=` placement must be an existing scenario or one added
+/// by an earlier NEW block in the same delta file, and no REMOVED block may delete it.
+fn check_placement(
+    delta: &DeltaBlock,
+    targets: &SpecTargets,
+    claimed: &ClaimedTargets,
+) -> Option<DeltaTargetProblem> {
+    if delta.attribute("after").is_some() && delta.attribute("before").is_some() {
+        return Some(DeltaTargetProblem::ConflictingPlacement);
+    }
+
+    let placement = delta.placement()?;
+    let anchor = placement.anchor();
+    if let Some((_, line)) = targets.removed.iter().find(|(title, _)| title == anchor) {
+        return Some(DeltaTargetProblem::RemovedAnchor {
+            placement: placement.name(),
+            anchor: anchor.to_string(),
+            removed_line: *line,
+        });
+    }
+    let exists =
+        targets.scenarios.iter().any(|t| t == anchor) || claimed.contains("scenario", anchor);
+    (!exists).then(|| DeltaTargetProblem::MissingAnchor {
+        placement: placement.name(),
+        anchor: anchor.to_string(),
+        suggestions: suggest_titles(anchor, &targets.scenarios),
+    })
+}
+
+fn check_feature_delta(
+    delta: &DeltaBlock,
+    targets: &SpecTargets,
+    claimed: &mut ClaimedTargets,
+) -> Option<DeltaTargetProblem> {
+    if !targets.has_heading {
+        return Some(DeltaTargetProblem::MissingSection {
+            section: "# Feature:",
+        });
+    }
+
+    let has_target = match delta.kind {
+        DeltaKind::Renamed => feature_heading_line(&delta.content).is_some(),
+        _ => !delta.content.trim().is_empty(),
+    };
+    if !has_target {
+        return Some(DeltaTargetProblem::MissingTitle);
+    }
+
+    claimed
+        .claim(delta.kind.target_noun(), "", delta.line)
+        .map(|first_line| DeltaTargetProblem::Repeated { first_line })
+}
+
+fn check_background_delta(
+    delta: &DeltaBlock,
+    targets: &SpecTargets,
+    claimed: &mut ClaimedTargets,
+) -> Vec<DeltaTargetProblem> {
+    if !targets.has_background {
+        return vec![DeltaTargetProblem::MissingSection {
+            section: "## Background",
+        }];
+    }
+
+    let facts = parser::bullet_items(&delta.content);
+
+    if delta.kind == DeltaKind::BackgroundChanged {
+        let Some(was) = delta.attribute("was") else {
+            return vec![DeltaTargetProblem::MissingTitle];
+        };
+        if facts.len() != 1 {
+            return vec![DeltaTargetProblem::MissingReplacement];
+        }
+        return check_fact(was, delta, targets, claimed)
+            .into_iter()
+            .collect();
+    }
+
+    if facts.is_empty() {
+        return vec![DeltaTargetProblem::MissingTitle];
+    }
+
+    facts
+        .iter()
+        .filter_map(|fact| check_fact(&fact.text, delta, targets, claimed))
+        .collect()
+}
+
+fn check_fact(
+    fact: &str,
+    delta: &DeltaBlock,
+    targets: &SpecTargets,
+    claimed: &mut ClaimedTargets,
+) -> Option<DeltaTargetProblem> {
+    let count = targets.facts.iter().filter(|f| *f == fact).count();
+    let earlier = claimed.claim("background fact", fact, delta.line);
+
+    if delta.kind == DeltaKind::BackgroundNew {
+        check_new_target(fact, count, earlier)
+    } else {
+        check_existing_target(fact, count, earlier, &targets.facts)
+    }
+}
+
+fn check_new_target(
+    title: &str,
+    count: usize,
+    earlier: Option<usize>,
+) -> Option<DeltaTargetProblem> {
+    match (count, earlier) {
+        (0, None) => None,
+        (0, Some(first_line)) => Some(DeltaTargetProblem::DuplicateTarget {
+            title: title.to_string(),
+            first_line,
+        }),
+        _ => Some(DeltaTargetProblem::AlreadyExists {
+            title: title.to_string(),
+        }),
+    }
+}
+
+fn check_existing_target(
+    title: &str,
+    count: usize,
+    earlier: Option<usize>,
+    existing_titles: &[String],
+) -> Option<DeltaTargetProblem> {
+    match (count, earlier) {
+        (0, _) => Some(DeltaTargetProblem::NotFound {
+            title: title.to_string(),
+            suggestions: suggest_titles(title, existing_titles),
+        }),
+        (1, None) => None,
+        (1, Some(first_line)) => Some(DeltaTargetProblem::DuplicateTarget {
+            title: title.to_string(),
+            first_line,
+        }),
+        (count, _) => Some(DeltaTargetProblem::Ambiguous {
+            title: title.to_string(),
+            count,
+        }),
+    }
+}
+
+const MAX_SUGGESTIONS: usize = 3;
+
+fn suggest_titles(title: &str, candidates: &[String]) -> Vec<String> {
+    let needle = title.to_lowercase();
+    let mut scored: Vec<(usize, &String)> = candidates
+        .iter()
+        .filter_map(|candidate| {
+            let lower = candidate.to_lowercase();
+            let distance = edit_distance(&needle, &lower);
+            let threshold = needle.chars().count().max(lower.chars().count()) / 3;
+            let related = lower.contains(&needle) || needle.contains(&lower);
+            (distance <= threshold.max(1) || related).then_some((distance, candidate))
+        })
+        .collect();
+
+    scored.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| a.1.cmp(b.1)));
+    scored
+        .into_iter()
+        .take(MAX_SUGGESTIONS)
+        .map(|(_, candidate)| candidate.clone())
+        .collect()
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b_chars: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b_chars.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b_chars.len() + 1];
+        for (j, cb) in b_chars.iter().enumerate() {
+            let cost = usize::from(ca != *cb);
+            current[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(current[j] + 1);
+        }
+        prev = current;
+    }
+
+    prev[b_chars.len()]
+}
+
+pub fn find_scenario_span(content: &str, title: &str) -> Option<Range<usize>> {
+    let parsed = parser::parse(content).ok()?;
+    parsed
+        .spec
+        .scenarios
+        .into_iter()
+        .find(|s| s.name == title)
+        .map(|s| s.span)
+}
+
+pub fn insert_scenario(content: &str, scenario: &str, placement: Option<Placement<'_>>) -> String {
+    let span = placement.and_then(|p| find_scenario_span(content, p.anchor()));
+
+    match (placement, span) {
+        (Some(Placement::After(_)), Some(span)) => {
+            let before = content[..span.end].trim_end();
+            let after = &content[span.end..];
+            if after.trim().is_empty() {
+                format!("{}\n\n{}\n", before, scenario)
+            } else {
+                format!("{}\n\n{}\n\n{}", before, scenario, after)
+            }
+        }
+        (Some(Placement::Before(_)), Some(span)) => format!(
+            "{}{}\n\n{}",
+            &content[..span.start],
+            scenario,
+            &content[span.start..]
+        ),
+        // Append new scenario before the final empty lines
+        _ => format!("{}\n\n{}\n", content.trim_end(), scenario),
+    }
+}
+
+pub fn replace_scenario(content: &str, title: &str, replacement: &str) -> String {
+    let Some(span) = find_scenario_span(content, title) else {
+        return content.to_string();
+    };
+
+    let before = &content[..span.start];
+    let after = &content[span.end..];
+    if after.trim().is_empty() {
+        format!("{}{}\n", before, replacement)
+    } else {
+        format!("{}{}\n\n{}", before, replacement, after)
+    }
+}
+
+pub fn remove_scenario(content: &str, title: &str) -> String {
+    let Some(span) = find_scenario_span(content, title) else {
+        return content.to_string();
+    };
+
+    let before = content[..span.start].trim_end();
+    let after = &content[span.end..];
+    match (before.is_empty(), after.trim().is_empty()) {
+        (true, _) => after.to_string(),
+        (false, true) => format!("{}\n", before),
+        (false, false) => format!("{}\n\n{}", before, after),
+    }
+}
+
+/// The `# Feature:` line of a RENAMED block
+fn feature_heading_line(content: &str) -> Option<&str> {
+    let parsed = parser::parse(content).ok()?;
+    parsed.spec.feature_name.as_ref()?;
+    let span = parsed.spec.heading_span?;
+    Some(content[span].trim_end())
+}
+
+pub fn replace_description(content: &str, description: &str) -> String {
+    let Some(span) = parser::parse(content)
+        .ok()
+        .and_then(|parsed| parsed.spec.description_span)
+    else {
+        return content.to_string();
+    };
+
+    let before = content[..span.start].trim_end();
+    let after = &content[span.end..];
+    if after.trim().is_empty() {
+        format!("{}\n\n{}\n", before, description)
+    } else {
+        format!("{}\n\n{}\n\n{}", before, description, after)
+    }
+}
+
+pub fn rename_feature(content: &str, heading_block: &str) -> String {
+    let span = parser::parse(content)
+        .ok()
+        .and_then(|parsed| parsed.spec.heading_span);
+    match (span, feature_heading_line(heading_block)) {
+        (Some(span), Some(heading)) => {
+            let old_heading = content[span.clone()].trim_end();
+            format!(
+                "{}{}{}",
+                &content[..span.start],
+                heading,
+                &content[span.start + old_heading.len()..]
+            )
+        }
+        _ => content.to_string(),
+    }
+}
+
+fn add_background_facts(content: &str, facts: &str) -> String {
+    let Ok(parsed) = parser::parse(content) else {
+        return content.to_string();
+    };
+
+    // Insert after the last fact, or directly below the heading of an empty Background
+    let insert_at = match (
+        parsed.spec.background_facts.last(),
+        parsed.spec.background_span,
+    ) {
+        (Some(fact), _) => fact.span.end,
+        (None, Some(span)) => span.end,
+        (None, None) => return content.to_string(),
+    };
+
+    let before = content[..insert_at].trim_end();
+    let after = content[insert_at..].trim_start_matches('\n');
+    let separator = if parsed.spec.background_facts.is_empty() {
+        "\n\n"
+    } else {
+        "\n"
+    };
+    if after.is_empty() {
+        format!("{}{}{}\n", before, separator, facts.trim())
+    } else {
+        format!("{}{}{}\n\n{}", before, separator, facts.trim(), after)
+    }
+}
+
+fn find_background_fact(content: &str, fact: &str) -> Option<Range<usize>> {
+    let parsed = parser::parse(content).ok()?;
+    parsed
+        .spec
+        .background_facts
+        .into_iter()
+        .find(|f| f.text == fact)
+        .map(|f| f.span)
+}
+
+fn replace_background_fact(content: &str, fact: &str, replacement: &str) -> String {
+    let Some(span) = find_background_fact(content, fact) else {
+        return content.to_string();
+    };
+
+    format!(
+        "{}{}\n{}",
+        &content[..span.start],
+        replacement.trim(),
+        &content[span.end..]
+    )
+}
+
+fn remove_background_fact(content: &str, fact: &str) -> String {
+    let Some(span) = find_background_fact(content, fact) else {
+        return content.to_string();
+    };
+
+    let before = &content[..span.start];
+    let after = &content[span.end..];
+    if before.ends_with("\n\n") {
+        format!("{}{}", before, after.trim_start_matches('\n'))
+    } else {
+        format!("{}{}", before, after)
+    }
+}
+
+fn is_removal(kind: &DeltaKind) -> bool {
+    matches!(kind, DeltaKind::Removed | DeltaKind::BackgroundRemoved)
+}
+
+pub fn strip_delta_markers(content: &str) -> String {
+    let mut result = Vec::new();
+    let mut in_removed = false;
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+
+        // Track REMOVED blocks to skip their content
+        if let Some((kind, _)) = parse_delta_open(trimmed) {
+            in_removed = is_removal(&kind);
+            continue;
+        }
+        if parse_delta_close(trimmed).is_some() {
+            in_removed = false;
+            continue;
+        }
+
+        // Skip content inside REMOVED blocks
+        if in_removed {
+            continue;
+        }
+
+        result.push(line);
+    }
+
+    // Clean up multiple consecutive empty lines
+    let joined = result.join("\n");
+    let mut clean = String::new();
+    let mut last_was_empty = false;
+
+    for line in joined.lines() {
+        let is_empty = line.trim().is_empty();
+        if is_empty && last_was_empty {
+            continue;
+        }
+        if !clean.is_empty() {
+            clean.push('\n');
+        }
+        clean.push_str(line);
+        last_was_empty = is_empty;
+    }
+
+    clean.push('\n');
+    clean
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    /// Record past the verification gate; most tests cover merging and archiving
+    fn record_unchecked(specs: &Path, plan_name: &str) -> Result<RecordOutcome, RecordError> {
+        let options = RecordOptions {
+            force: true,
+            ..Default::default()
+        };
+        record_plan_with(specs, plan_name, &options)
+    }
+
+    #[test]
+    fn parses_new_delta() {
+        let content = r#"## Scenarios
+
+<!-- DELTA:NEW -->
+### Scenario: User logs in
+
+* *GIVEN* a user exists
+* *WHEN* they log in
+* *THEN* they SHALL be authenticated
+<!-- /DELTA:NEW -->
+"#;
+
+        let deltas = parse_deltas(content).unwrap();
+        assert_eq!(deltas.len(), 1);
+        assert_eq!(deltas[0].kind, DeltaKind::New);
+        assert_eq!(deltas[0].scenario_title, Some("User logs in".to_string()));
+    }
+
+    #[test]
+    fn parses_changed_delta() {
+        let content = r#"<!-- DELTA:CHANGED -->
+### Scenario: Old feature
+
+Updated content
+<!-- /DELTA:CHANGED -->"#;
+
+        let deltas = parse_deltas(content).unwrap();
+        assert_eq!(deltas.len(), 1);
+        assert_eq!(deltas[0].kind, DeltaKind::Changed);
+    }
+
+    #[test]
+    fn parses_removed_delta() {
+        let content = r#"<!-- DELTA:REMOVED -->
+### Scenario: Deprecated feature
+<!-- /DELTA:REMOVED -->"#;
+
+        let deltas = parse_deltas(content).unwrap();
+        assert_eq!(deltas.len(), 1);
+        assert_eq!(deltas[0].kind, DeltaKind::Removed);
+        assert_eq!(
+            deltas[0].scenario_title,
+            Some("Deprecated feature".to_string())
+        );
+    }
+
+    #[test]
+    fn parses_multiple_deltas() {
+        let content = r#"## Scenarios
+
+<!-- DELTA:NEW -->
+### Scenario: First
+* content
+<!-- /DELTA:NEW -->
+
+<!-- DELTA:CHANGED -->
+### Scenario: Second
+* updated
+<!-- /DELTA:CHANGED -->
+
+<!-- DELTA:REMOVED -->
+### Scenario: Third
+<!-- /DELTA:REMOVED -->
+"#;
+
+        let deltas = parse_deltas(content).unwrap();
+        assert_eq!(deltas.len(), 3);
+        assert_eq!(deltas[0].kind, DeltaKind::New);
+        assert_eq!(deltas[1].kind, DeltaKind::Changed);
+        assert_eq!(deltas[2].kind, DeltaKind::Removed);
+    }
+
+    #[test]
+    fn error_on_nested_delta_markers() {
+        let content = r#"<!-- DELTA:NEW -->
+### Scenario: Test
+<!-- DELTA:CHANGED -->
+nested
+<!-- /DELTA:CHANGED -->
+<!-- /DELTA:NEW -->
+"#;
+
+        let result = parse_deltas(content);
+        assert!(matches!(result, Err(RecordError::MalformedDelta { .. })));
+    }
+
+    #[test]
+    fn error_on_close_without_open() {
+        let content = r#"### Scenario: Test
+<!-- /DELTA:NEW -->
+"#;
+
+        let result = parse_deltas(content);
+        assert!(matches!(result, Err(RecordError::MalformedDelta { .. })));
+    }
+
+    #[test]
+    fn error_on_mismatched_close_marker() {
+        let content = r#"<!-- DELTA:NEW -->
+### Scenario: Test
+<!-- /DELTA:CHANGED -->
+"#;
+
+        let result = parse_deltas(content);
+        assert!(matches!(result, Err(RecordError::MalformedDelta { .. })));
+    }
+
+    #[test]
+    fn error_on_unclosed_delta_at_end_of_file() {
+        let content = r#"<!-- DELTA:REMOVED -->
+### Scenario: Test
+"#;
+
+        let result = parse_deltas(content);
+        assert!(matches!(result, Err(RecordError::MalformedDelta { .. })));
+    }
+
+    #[test]
+    fn strips_markers_preserves_content() {
+        let content = r#"# Feature: Test
+
+## Background
+
+Context here.
+
+## Scenarios
+
+<!-- DELTA:NEW -->
+### Scenario: New one
+
+* *GIVEN* setup
+* *WHEN* action
+* *THEN* result SHALL happen
+<!-- /DELTA:NEW -->
+"#;
+
+        let stripped = strip_delta_markers(content);
+
+        assert!(!stripped.contains("DELTA"));
+        assert!(stripped.contains("### Scenario: New one"));
+        assert!(stripped.contains("# Feature: Test"));
+    }
+
+    #[test]
+    fn strips_removed_content() {
+        let content = r#"# Feature: Test
+
+## Scenarios
+
+<!-- DELTA:REMOVED -->
+### Scenario: Gone
+
+* Old content
+<!-- /DELTA:REMOVED -->
+
+### Scenario: Stays
+
+* *GIVEN* valid
+"#;
+
+        let stripped = strip_delta_markers(content);
+
+        assert!(!stripped.contains("Gone"));
+        assert!(stripped.contains("### Scenario: Stays"));
+    }
+
+    #[test]
+    fn merge_appends_new_scenario() {
+        let existing = r#"# Feature: Test
+
+## Scenarios
+
+### Scenario: Existing
+
+* *GIVEN* something
+"#;
+
+        let delta = r#"## Scenarios
+
+<!-- DELTA:NEW -->
+### Scenario: New one
+
+* *GIVEN* new thing
+<!-- /DELTA:NEW -->
+"#;
+
+        let merged = merge_delta(existing, delta).unwrap();
+
+        assert!(merged.contains("### Scenario: Existing"));
+        assert!(merged.contains("### Scenario: New one"));
+    }
+
+    #[test]
+    fn merge_returns_existing_when_no_deltas() {
+        let existing = "# Feature: Test\n\nContent here.\n";
+        let delta = "No delta markers here.\n";
+
+        let merged = merge_delta(existing, delta).unwrap();
+        assert_eq!(merged, existing);
+    }
+
+    #[test]
+    fn merge_ignores_empty_new_slot() {
+        let existing = "# Feature: Test\n\nContent here.\n";
+        let delta = "# Feature: Test\n\n<!-- DELTA:NEW -->\n<!-- /DELTA:NEW -->\n";
+
+        let merged = merge_delta(existing, delta).unwrap();
+        assert_eq!(merged, existing);
+    }
+
+    #[test]
+    fn merge_rejects_other_empty_blocks() {
+        let existing = "# Feature: Test\n\nUsers log in.\n\n## Background\n\n* A fact\n\n## Scenarios\n\n### Scenario: Login\n\n* *GIVEN* a user\n";
+        let delta = "<!-- DELTA:CHANGED -->\n<!-- /DELTA:CHANGED -->\n<!-- DELTA:NEW after=\"Login\" -->\n<!-- /DELTA:NEW -->\n<!-- DELTA:DESCRIPTION -->\n<!-- /DELTA:DESCRIPTION -->\n<!-- DELTA:BACKGROUND:REMOVED -->\n<!-- /DELTA:BACKGROUND:REMOVED -->\n";
+
+        let result = merge_delta(existing, delta);
+        let Err(RecordError::UnresolvedDeltaTargets { errors }) = result else {
+            panic!("expected UnresolvedDeltaTargets, got {:?}", result);
+        };
+
+        let lines: Vec<usize> = errors.iter().map(|e| e.line).collect();
+        assert_eq!(lines, vec![1, 3, 5, 7]);
+        assert!(
+            errors
+                .iter()
+                .all(|e| e.problem == DeltaTargetProblem::MissingTitle)
+        );
+    }
+
+    #[test]
+    fn merge_replaces_changed_scenario() {
+        let existing = r#"# Feature: Test
+
+## Scenarios
+
+### Scenario: Login
+
+* *GIVEN* old setup
+* *WHEN* old action
+* *THEN* old result
+
+### Scenario: Other
+
+* *GIVEN* other
+"#;
+
+        let delta = r#"<!-- DELTA:CHANGED -->
+### Scenario: Login
+
+* *GIVEN* new setup
+* *WHEN* new action
+* *THEN* new result SHALL happen
+<!-- /DELTA:CHANGED -->
+"#;
+
+        let merged = merge_delta(existing, delta).unwrap();
+
+        assert!(merged.contains("new setup"));
+        assert!(!merged.contains("old setup"));
+        assert!(merged.contains("### Scenario: Other"));
+    }
+
+    #[test]
+    fn merge_removes_scenario() {
+        let existing = r#"# Feature: Test
+
+## Scenarios
+
+### Scenario: Keep
+
+* *GIVEN* keep this
+
+### Scenario: Remove
+
+* *GIVEN* remove this
+
+### Scenario: Also Keep
+
+* *GIVEN* also keep
+"#;
+
+        let delta = r#"<!-- DELTA:REMOVED -->
+### Scenario: Remove
+<!-- /DELTA:REMOVED -->
+"#;
+
+        let merged = merge_delta(existing, delta).unwrap();
+
+        assert!(merged.contains("### Scenario: Keep"));
+        assert!(merged.contains("### Scenario: Also Keep"));
+        assert!(!merged.contains("### Scenario: Remove"));
+        assert!(!merged.contains("remove this"));
+    }
+
+    #[test]
+    fn merge_errors_on_changed_scenario_that_does_not_exist() {
+        let existing = "# Feature: Test\n\n## Scenarios\n\n### Scenario: Login succeeds\n\n* *GIVEN* a user\n\n### Scenario: Logout\n\n* *GIVEN* a session\n";
+        let delta = "<!-- DELTA:CHANGED -->\n### Scenario: Login succeds\n\n* *GIVEN* new\n<!-- /DELTA:CHANGED -->\n";
+
+        let result = merge_delta(existing, delta);
+        let Err(RecordError::UnresolvedDeltaTargets { errors }) = result else {
+            panic!("expected UnresolvedDeltaTargets, got {:?}", result);
+        };
+
+        assert_eq!(
+            errors,
+            vec![DeltaTargetError {
+                line: 1,
+                kind: DeltaKind::Changed,
+                problem: DeltaTargetProblem::NotFound {
+                    title: "Login succeds".to_string(),
+                    suggestions: vec!["Login succeeds".to_string()],
+                },
+            }]
+        );
+    }
+
+    #[test]
+    fn merge_errors_on_removed_block_without_title() {
+        let existing = "# Feature: Test\n\n## Scenarios\n\n### Scenario: Keep\n\n* *GIVEN* keep\n";
+        let delta =
+            "## Scenarios\n\n<!-- DELTA:REMOVED -->\nNo heading here\n<!-- /DELTA:REMOVED -->\n";
+
+        let result = merge_delta(existing, delta);
+        let Err(RecordError::UnresolvedDeltaTargets { errors }) = result else {
+            panic!("expected UnresolvedDeltaTargets, got {:?}", result);
+        };
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].line, 3);
+        assert_eq!(errors[0].problem, DeltaTargetProblem::MissingTitle);
+        assert_eq!(
+            errors[0].to_string(),
+            "DELTA:REMOVED block has no '### Scenario:' title"
+        );
+    }
+
+    #[test]
+    fn missing_target_without_similar_titles_has_no_suggestions() {
+        let existing =
+            "# Feature: Test\n\n## Scenarios\n\n### Scenario: Export report\n\n* *GIVEN* data\n";
+        let deltas =
+            parse_deltas("<!-- DELTA:REMOVED -->\n### Scenario: Login\n<!-- /DELTA:REMOVED -->\n")
+                .unwrap();
+
+        let errors = check_delta_targets(existing, &deltas).unwrap();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors[0].to_string(),
+            "DELTA:REMOVED targets scenario 'Login' which does not exist in the permanent spec"
+        );
+    }
+
+    #[test]
+    fn suggestions_include_titles_containing_the_target() {
+        let candidates = vec![
+            "Login with SSO".to_string(),
+            "Export report".to_string(),
+            "Logn".to_string(),
+        ];
+        assert_eq!(
+            suggest_titles("Login", &candidates),
+            vec!["Logn".to_string(), "Login with SSO".to_string()]
+        );
+    }
+
+    #[test]
+    fn record_plan_names_delta_file_for_missing_target() {
+        let tmp = TempDir::new().unwrap();
+        let specs = tmp.path();
+
+        let feature_dir = specs.join("domain/feature");
+        fs::create_dir_all(&feature_dir).unwrap();
+        fs::write(
+            feature_dir.join("spec.md"),
+            "# Feature: Test\n\n## Scenarios\n\n### Scenario: Original\n\n* *GIVEN* original\n",
+        )
+        .unwrap();
+
+        let plan_dir = specs.join("_plans/test-plan/domain/feature");
+        fs::create_dir_all(&plan_dir).unwrap();
+        fs::write(
+            plan_dir.join("spec.md"),
+            "<!-- DELTA:CHANGED -->\n### Scenario: Orignal\n\n* *GIVEN* changed\n<!-- /DELTA:CHANGED -->\n",
+        )
+        .unwrap();
+
+        let err = record_unchecked(specs, "test-plan").unwrap_err();
+        let message = err.to_string();
+        assert!(message.contains("_plans/test-plan/domain/feature/spec.md"));
+        assert!(message.contains("line 1"));
+        assert!(message.contains("'Orignal'"));
+        assert!(message.contains("did you mean 'Original'?"));
+        assert!(specs.join("_plans/test-plan").exists());
+    }
+
+    const PREFIX_TITLES_SPEC: &str = r#"# Feature: Test
+
+## Scenarios
+
+### Scenario: Login
+
+* *GIVEN* login
+
+### Scenario: Login fails
+
+* *GIVEN* login fails
+
+### Scenario: Login with SSO
+
+* *GIVEN* login with sso
+"#;
+
+    #[test]
+    fn merge_changed_matches_exact_title_only() {
+        let delta = "<!-- DELTA:CHANGED -->\n### Scenario: Login\n\n* *GIVEN* updated login\n<!-- /DELTA:CHANGED -->\n";
+
+        let merged = merge_delta(PREFIX_TITLES_SPEC, delta).unwrap();
+
+        assert_eq!(
+            merged,
+            r#"# Feature: Test
+
+## Scenarios
+
+### Scenario: Login
+
+* *GIVEN* updated login
+
+### Scenario: Login fails
+
+* *GIVEN* login fails
+
+### Scenario: Login with SSO
+
+* *GIVEN* login with sso
+"#
+        );
+    }
+
+    #[test]
+    fn merge_removed_matches_exact_title_only() {
+        let delta = "<!-- DELTA:REMOVED -->\n### Scenario: Login fails\n<!-- /DELTA:REMOVED -->\n";
+
+        let merged = merge_delta(PREFIX_TITLES_SPEC, delta).unwrap();
+
+        assert!(merged.contains("### Scenario: Login\n"));
+        assert!(merged.contains("### Scenario: Login with SSO"));
+        assert!(!merged.contains("Login fails"));
+    }
+
+    #[test]
+    fn merge_matches_titles_containing_inline_code() {
+        let existing = "# Feature: Test\n\n## Scenarios\n\n### Scenario: Run `speq record`\n\n* *GIVEN* old\n\n### Scenario: Run `speq plan`\n\n* *GIVEN* plan\n";
+        let delta = "<!-- DELTA:CHANGED -->\n### Scenario: Run `speq record`\n\n* *GIVEN* new\n<!-- /DELTA:CHANGED -->\n";
+
+        let merged = merge_delta(existing, delta).unwrap();
+
+        assert!(merged.contains("* *GIVEN* new"));
+        assert!(!merged.contains("* *GIVEN* old"));
+        assert!(merged.contains("### Scenario: Run `speq plan`"));
+    }
+
+    #[test]
+    fn merge_refuses_ambiguous_target() {
+        let existing = "# Feature: Test\n\n## Scenarios\n\n### Scenario: Login\n\n* *GIVEN* one\n\n### Scenario: Login\n\n* *GIVEN* two\n";
+        let delta = "<!-- DELTA:CHANGED -->\n### Scenario: Login\n\n* *GIVEN* new\n<!-- /DELTA:CHANGED -->\n";
+
+        let result = merge_delta(existing, delta);
+        let Err(RecordError::UnresolvedDeltaTargets { errors }) = result else {
+            panic!("expected UnresolvedDeltaTargets, got {:?}", result);
+        };
+
+        assert_eq!(
+            errors[0].problem,
+            DeltaTargetProblem::Ambiguous {
+                title: "Login".to_string(),
+                count: 2,
             }
-        } else {
-            // Collapse multiple empty lines
-            let is_empty = lines[i].trim().is_empty();
-            if is_empty && last_was_empty {
-                i += 1;
-                continue;
+        );
+    }
+
+    #[test]
+    fn merge_refuses_two_blocks_targeting_same_scenario() {
+        let delta = "<!-- DELTA:CHANGED -->\n### Scenario: Login\n\n* *GIVEN* new\n<!-- /DELTA:CHANGED -->\n\n<!-- DELTA:REMOVED -->\n### Scenario: Login\n<!-- /DELTA:REMOVED -->\n";
+
+        let result = merge_delta(PREFIX_TITLES_SPEC, delta);
+        let Err(RecordError::UnresolvedDeltaTargets { errors }) = result else {
+            panic!("expected UnresolvedDeltaTargets, got {:?}", result);
+        };
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].line, 7);
+        assert_eq!(
+            errors[0].problem,
+            DeltaTargetProblem::DuplicateTarget {
+                title: "Login".to_string(),
+                first_line: 1,
             }
-            last_was_empty = is_empty;
-            result.push(lines[i]);
-            i += 1;
-        }
+        );
     }
 
-    result.join("\n")
-}
+    #[test]
+    fn merge_refuses_new_scenario_with_existing_title() {
+        let delta = "## Scenarios\n\n<!-- DELTA:NEW -->\n### Scenario: Login fails\n\n* *GIVEN* again\n<!-- /DELTA:NEW -->\n";
 
-pub fn strip_delta_markers(content: &str) -> String {
-    let mut result = Vec::new();
-    let mut in_removed = false;
+        let result = merge_delta(PREFIX_TITLES_SPEC, delta);
+        let Err(RecordError::UnresolvedDeltaTargets { errors }) = result else {
+            panic!("expected UnresolvedDeltaTargets, got {:?}", result);
+        };
 
-    for line in content.lines() {
-        let trimmed = line.trim();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].line, 3);
+        assert_eq!(
+            errors[0].to_string(),
+            "DELTA:NEW adds scenario 'Login fails' which already exists in the permanent spec (use DELTA:CHANGED to modify it)"
+        );
+    }
 
-        // Track REMOVED blocks to skip their content
-        if trimmed == "<!-- DELTA:REMOVED -->" {
-            in_removed = true;
-            continue;
-        }
-        if trimmed == "<!-- /DELTA:REMOVED -->" {
-            in_removed = false;
-            continue;
-        }
+    #[test]
+    fn merge_refuses_two_new_blocks_with_same_title() {
+        let delta = "<!-- DELTA:NEW -->\n### Scenario: Logout\n<!-- /DELTA:NEW -->\n<!-- DELTA:NEW -->\n### Scenario: Logout\n<!-- /DELTA:NEW -->\n";
 
-        // Skip all delta markers
-        if parse_delta_open(trimmed).is_some() || parse_delta_close(trimmed).is_some() {
-            continue;
-        }
+        let errors =
+            check_delta_targets(PREFIX_TITLES_SPEC, &parse_deltas(delta).unwrap()).unwrap();
 
-        // Skip content inside REMOVED blocks
-        if in_removed {
-            continue;
-        }
+        assert_eq!(
+            errors,
+            vec![DeltaTargetError {
+                line: 4,
+                kind: DeltaKind::New,
+                problem: DeltaTargetProblem::DuplicateTarget {
+                    title: "Logout".to_string(),
+                    first_line: 1,
+                },
+            }]
+        );
+    }
 
-        result.push(line);
+    const FEATURE_SPEC: &str = r#"# Feature: Login
+
+Users sign in.
+
+## Background
+
+* Accounts exist
+* Passwords are hashed
+
+## Scenarios
+
+### Scenario: Login
+
+* *GIVEN* a user
+"#;
+
+    #[test]
+    fn parses_marker_attributes() {
+        let delta = "<!-- DELTA:BACKGROUND:CHANGED was=\"Accounts exist\" -->\n* Accounts are verified\n<!-- /DELTA:BACKGROUND:CHANGED -->\n";
+
+        let deltas = parse_deltas(delta).unwrap();
+
+        assert_eq!(deltas[0].kind, DeltaKind::BackgroundChanged);
+        assert_eq!(deltas[0].attribute("was"), Some("Accounts exist"));
     }
 
-    // Clean up multiple consecutive empty lines
-    let joined = result.join("\n");
-    let mut clean = String::new();
-    let mut last_was_empty = false;
+    #[test]
+    fn rejects_unknown_marker_kind() {
+        let delta = "<!-- DELTA:BACKGROUND -->\n* Fact\n<!-- /DELTA:BACKGROUND -->\n";
 
-    for line in joined.lines() {
-        let is_empty = line.trim().is_empty();
-        if is_empty && last_was_empty {
-            continue;
-        }
-        if !clean.is_empty() {
-            clean.push('\n');
-        }
-        clean.push_str(line);
-        last_was_empty = is_empty;
+        let result = parse_deltas(delta);
+
+        assert!(matches!(
+            result,
+            Err(RecordError::MalformedDelta { line: 1, .. })
+        ));
     }
 
-    clean.push('\n');
-    clean
-}
+    #[test]
+    fn merge_replaces_description_and_renames_feature() {
+        let delta = "<!-- DELTA:RENAMED -->\n# Feature: Sign-in\n<!-- /DELTA:RENAMED -->\n\n<!-- DELTA:DESCRIPTION -->\nUsers sign in with a password or SSO.\n<!-- /DELTA:DESCRIPTION -->\n";
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::fs;
-    use tempfile::TempDir;
+        let merged = merge_delta(FEATURE_SPEC, delta).unwrap();
+
+        assert_eq!(
+            merged,
+            FEATURE_SPEC
+                .replace("# Feature: Login", "# Feature: Sign-in")
+                .replace("Users sign in.", "Users sign in with a password or SSO.")
+        );
+    }
 
     #[test]
-    fn parses_new_delta() {
-        let content = r#"## Scenarios
+    fn merge_adds_changes_and_removes_background_facts() {
+        let delta = r#"## Background
+
+<!-- DELTA:BACKGROUND:CHANGED was="Accounts exist" -->
+* Accounts exist and are verified
+<!-- /DELTA:BACKGROUND:CHANGED -->
+<!-- DELTA:BACKGROUND:REMOVED -->
+* Passwords are hashed
+<!-- /DELTA:BACKGROUND:REMOVED -->
+<!-- DELTA:BACKGROUND:NEW -->
+* Sessions expire after 1 hour
+<!-- /DELTA:BACKGROUND:NEW -->
+"#;
 
-<!-- DELTA:NEW -->
-### Scenario: User logs in
+        let merged = merge_delta(FEATURE_SPEC, delta).unwrap();
 
-* *GIVEN* a user exists
-* *WHEN* they log in
-* *THEN* they SHALL be authenticated
-<!-- /DELTA:NEW -->
+        assert_eq!(
+            merged,
+            FEATURE_SPEC.replace(
+                "* Accounts exist\n* Passwords are hashed\n",
+                "* Accounts exist and are verified\n* Sessions expire after 1 hour\n"
+            )
+        );
+    }
+
+    #[test]
+    fn merge_reports_background_targets() {
+        let delta = r#"<!-- DELTA:BACKGROUND:REMOVED -->
+* Password is hashed
+<!-- /DELTA:BACKGROUND:REMOVED -->
+<!-- DELTA:BACKGROUND:NEW -->
+* Accounts exist
+<!-- /DELTA:BACKGROUND:NEW -->
+<!-- DELTA:BACKGROUND:CHANGED -->
+* Accounts exist forever
+<!-- /DELTA:BACKGROUND:CHANGED -->
 "#;
 
-        let deltas = parse_deltas(content).unwrap();
-        assert_eq!(deltas.len(), 1);
-        assert_eq!(deltas[0].kind, DeltaKind::New);
-        assert_eq!(deltas[0].scenario_title, Some("User logs in".to_string()));
+        let errors = check_delta_targets(FEATURE_SPEC, &parse_deltas(delta).unwrap()).unwrap();
+        let messages: Vec<String> = errors.iter().map(ToString::to_string).collect();
+
+        assert_eq!(
+            messages,
+            vec![
+                "DELTA:BACKGROUND:REMOVED targets background fact 'Password is hashed' which does not exist in the permanent spec (did you mean 'Passwords are hashed'?)",
+                "DELTA:BACKGROUND:NEW adds background fact 'Accounts exist' which already exists in the permanent spec (use DELTA:BACKGROUND:CHANGED to modify it)",
+                "DELTA:BACKGROUND:CHANGED block has no was=\"...\" attribute naming the fact to replace",
+            ]
+        );
     }
 
     #[test]
-    fn parses_changed_delta() {
-        let content = r#"<!-- DELTA:CHANGED -->
-### Scenario: Old feature
+    fn merge_refuses_background_delta_without_background_section() {
+        let delta = "<!-- DELTA:BACKGROUND:NEW -->\n* Fact\n<!-- /DELTA:BACKGROUND:NEW -->\n";
 
-Updated content
-<!-- /DELTA:CHANGED -->"#;
+        let errors =
+            check_delta_targets(PREFIX_TITLES_SPEC, &parse_deltas(delta).unwrap()).unwrap();
 
-        let deltas = parse_deltas(content).unwrap();
-        assert_eq!(deltas.len(), 1);
-        assert_eq!(deltas[0].kind, DeltaKind::Changed);
+        assert_eq!(
+            errors[0].problem,
+            DeltaTargetProblem::MissingSection {
+                section: "## Background"
+            }
+        );
     }
 
     #[test]
-    fn parses_removed_delta() {
-        let content = r#"<!-- DELTA:REMOVED -->
-### Scenario: Deprecated feature
-<!-- /DELTA:REMOVED -->"#;
+    fn merge_refuses_repeated_description_blocks() {
+        let delta = "<!-- DELTA:DESCRIPTION -->\nOne.\n<!-- /DELTA:DESCRIPTION -->\n<!-- DELTA:DESCRIPTION -->\nTwo.\n<!-- /DELTA:DESCRIPTION -->\n";
+
+        let errors = check_delta_targets(FEATURE_SPEC, &parse_deltas(delta).unwrap()).unwrap();
 
-        let deltas = parse_deltas(content).unwrap();
-        assert_eq!(deltas.len(), 1);
-        assert_eq!(deltas[0].kind, DeltaKind::Removed);
         assert_eq!(
-            deltas[0].scenario_title,
-            Some("Deprecated feature".to_string())
+            errors[0].problem,
+            DeltaTargetProblem::Repeated { first_line: 1 }
         );
     }
 
     #[test]
-    fn parses_multiple_deltas() {
-        let content = r#"## Scenarios
+    fn strip_markers_drops_removed_background_facts() {
+        let delta = "## Background\n\n* Kept\n<!-- DELTA:BACKGROUND:REMOVED -->\n* Dropped\n<!-- /DELTA:BACKGROUND:REMOVED -->\n";
 
-<!-- DELTA:NEW -->
-### Scenario: First
-* content
-<!-- /DELTA:NEW -->
+        assert_eq!(strip_delta_markers(delta), "## Background\n\n* Kept\n");
+    }
 
-<!-- DELTA:CHANGED -->
-### Scenario: Second
-* updated
-<!-- /DELTA:CHANGED -->
+    #[test]
+    fn merge_inserts_new_scenario_after_anchor() {
+        let delta = "<!-- DELTA:NEW after=\"Login\" -->\n### Scenario: Login remembered\n\n* *GIVEN* a cookie\n<!-- /DELTA:NEW -->\n";
 
-<!-- DELTA:REMOVED -->
-### Scenario: Third
-<!-- /DELTA:REMOVED -->
-"#;
+        let merged = merge_delta(PREFIX_TITLES_SPEC, delta).unwrap();
 
-        let deltas = parse_deltas(content).unwrap();
-        assert_eq!(deltas.len(), 3);
-        assert_eq!(deltas[0].kind, DeltaKind::New);
-        assert_eq!(deltas[1].kind, DeltaKind::Changed);
-        assert_eq!(deltas[2].kind, DeltaKind::Removed);
+        assert_eq!(
+            merged,
+            PREFIX_TITLES_SPEC.replace(
+                "### Scenario: Login fails",
+                "### Scenario: Login remembered\n\n* *GIVEN* a cookie\n\n### Scenario: Login fails"
+            )
+        );
+    }
+
+    #[test]
+    fn merge_inserts_new_scenario_before_anchor() {
+        let delta = "<!-- DELTA:NEW before=\"Login\" -->\n### Scenario: Signup\n\n* *GIVEN* a visitor\n<!-- /DELTA:NEW -->\n";
+
+        let merged = merge_delta(PREFIX_TITLES_SPEC, delta).unwrap();
+
+        assert_eq!(
+            merged,
+            PREFIX_TITLES_SPEC.replace(
+                "### Scenario: Login\n",
+                "### Scenario: Signup\n\n* *GIVEN* a visitor\n\n### Scenario: Login\n"
+            )
+        );
+    }
+
+    #[test]
+    fn merge_inserts_after_last_scenario_at_end_of_file() {
+        let delta = "<!-- DELTA:NEW after=\"Login with SSO\" -->\n### Scenario: Logout\n\n* *GIVEN* a session\n<!-- /DELTA:NEW -->\n";
+
+        let merged = merge_delta(PREFIX_TITLES_SPEC, delta).unwrap();
+
+        assert_eq!(
+            merged,
+            format!("{PREFIX_TITLES_SPEC}\n### Scenario: Logout\n\n* *GIVEN* a session\n")
+        );
+    }
+
+    #[test]
+    fn merge_accepts_anchor_added_by_earlier_block() {
+        let delta = "<!-- DELTA:NEW -->\n### Scenario: Logout\n<!-- /DELTA:NEW -->\n<!-- DELTA:NEW after=\"Logout\" -->\n### Scenario: Logout everywhere\n<!-- /DELTA:NEW -->\n";
+
+        let errors =
+            check_delta_targets(PREFIX_TITLES_SPEC, &parse_deltas(delta).unwrap()).unwrap();
+
+        assert!(errors.is_empty(), "{errors:?}");
+    }
+
+    #[test]
+    fn merge_refuses_missing_anchor() {
+        let delta = "<!-- DELTA:NEW after=\"Login succeeds\" -->\n### Scenario: Logout\n<!-- /DELTA:NEW -->\n";
+
+        let errors =
+            check_delta_targets(PREFIX_TITLES_SPEC, &parse_deltas(delta).unwrap()).unwrap();
+
+        assert_eq!(
+            errors[0].to_string(),
+            "DELTA:NEW is placed after scenario 'Login succeeds' which does not exist in the permanent spec (did you mean 'Login'?)"
+        );
     }
 
     #[test]
-    fn error_on_nested_delta_markers() {
-        let content = r#"<!-- DELTA:NEW -->
-### Scenario: Test
-<!-- DELTA:CHANGED -->
-nested
-<!-- /DELTA:CHANGED -->
-<!-- /DELTA:NEW -->
-"#;
+    fn merge_refuses_both_placements() {
+        let delta = "<!-- DELTA:NEW after=\"Login\" before=\"Login fails\" -->\n### Scenario: Logout\n<!-- /DELTA:NEW -->\n";
 
-        let result = parse_deltas(content);
-        assert!(matches!(result, Err(RecordError::MalformedDelta { .. })));
+        let errors =
+            check_delta_targets(PREFIX_TITLES_SPEC, &parse_deltas(delta).unwrap()).unwrap();
+
+        assert_eq!(errors[0].problem, DeltaTargetProblem::ConflictingPlacement);
     }
 
     #[test]
-    fn error_on_close_without_open() {
-        let content = r#"### Scenario: Test
-<!-- /DELTA:NEW -->
-"#;
+    fn merge_refuses_anchor_removed_by_same_delta() {
+        let delta = "<!-- DELTA:REMOVED -->\n### Scenario: Login fails\n<!-- /DELTA:REMOVED -->\n<!-- DELTA:NEW after=\"Login fails\" -->\n### Scenario: Logout\n<!-- /DELTA:NEW -->\n";
 
-        let result = parse_deltas(content);
-        assert!(matches!(result, Err(RecordError::MalformedDelta { .. })));
+        let errors =
+            check_delta_targets(PREFIX_TITLES_SPEC, &parse_deltas(delta).unwrap()).unwrap();
+
+        assert_eq!(errors.len(), 1, "{errors:?}");
+        assert_eq!(
+            errors[0].to_string(),
+            "DELTA:NEW is placed after scenario 'Login fails' which the DELTA:REMOVED block at line 1 removes"
+        );
     }
 
     #[test]
-    fn error_on_mismatched_close_marker() {
-        let content = r#"<!-- DELTA:NEW -->
-### Scenario: Test
-<!-- /DELTA:CHANGED -->
-"#;
+    fn merge_refuses_placement_on_changed_block() {
+        let delta = "<!-- DELTA:CHANGED after=\"Login fails\" -->\n### Scenario: Login\n\n* *GIVEN* a user\n<!-- /DELTA:CHANGED -->\n";
 
-        let result = parse_deltas(content);
-        assert!(matches!(result, Err(RecordError::MalformedDelta { .. })));
+        let errors =
+            check_delta_targets(PREFIX_TITLES_SPEC, &parse_deltas(delta).unwrap()).unwrap();
+
+        assert_eq!(errors[0].problem, DeltaTargetProblem::UnsupportedPlacement);
     }
 
     #[test]
-    fn error_on_unclosed_delta_at_end_of_file() {
-        let content = r#"<!-- DELTA:REMOVED -->
-### Scenario: Test
-"#;
-
-        let result = parse_deltas(content);
-        assert!(matches!(result, Err(RecordError::MalformedDelta { .. })));
+    fn extract_scenario_title_returns_none_for_no_scenario() {
+        let content = "Just some text\nwithout scenario heading";
+        assert_eq!(extract_scenario_title(content), None);
     }
 
     #[test]
-    fn strips_markers_preserves_content() {
-        let content = r#"# Feature: Test
+    fn record_plan_creates_new_feature() {
+        let tmp = TempDir::new().unwrap();
+        let specs = tmp.path();
+
+        // Create plan with new feature
+        let plan_dir = specs.join("_plans/test-plan/domain/feature");
+        fs::create_dir_all(&plan_dir).unwrap();
+        fs::write(
+            plan_dir.join("spec.md"),
+            r#"# Feature: New Feature
+
+Description here.
 
 ## Background
 
-Context here.
+* Context.
 
 ## Scenarios
 
 <!-- DELTA:NEW -->
-### Scenario: New one
+### Scenario: Test
 
 * *GIVEN* setup
 * *WHEN* action
 * *THEN* result SHALL happen
 <!-- /DELTA:NEW -->
-"#;
+"#,
+        )
+        .unwrap();
 
-        let stripped = strip_delta_markers(content);
+        // Create _recorded directory
+        fs::create_dir_all(specs.join("_recorded")).unwrap();
 
-        assert!(!stripped.contains("DELTA"));
-        assert!(stripped.contains("### Scenario: New one"));
-        assert!(stripped.contains("# Feature: Test"));
+        let result = record_unchecked(specs, "test-plan").unwrap().features;
+
+        assert_eq!(result.len(), 1);
+        assert!(result[0].contains("domain/feature"));
+
+        // Verify spec created
+        let spec_content = fs::read_to_string(specs.join("domain/feature/spec.md")).unwrap();
+        assert!(spec_content.contains("### Scenario: Test"));
+        assert!(!spec_content.contains("DELTA"));
+
+        // Verify plan archived with date prefix
+        let recorded_dir = specs.join("_recorded");
+        let entries: Vec<_> = fs::read_dir(&recorded_dir)
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .collect();
+        assert_eq!(entries.len(), 1, "Expected exactly one recorded plan");
+
+        let archived_name = entries[0].file_name().to_string_lossy().to_string();
+        assert!(
+            archived_name.ends_with("-test-plan"),
+            "Archive should end with plan name"
+        );
+
+        // Verify date prefix format (YYYY-MM-DD-)
+        let date_prefix = &archived_name[..11];
+        assert!(
+            date_prefix.chars().nth(4) == Some('-')
+                && date_prefix.chars().nth(7) == Some('-')
+                && date_prefix.chars().nth(10) == Some('-'),
+            "Archive should have date prefix format YYYY-MM-DD-"
+        );
+
+        assert!(!specs.join("_plans/test-plan").exists());
     }
 
     #[test]
-    fn strips_removed_content() {
-        let content = r#"# Feature: Test
+    fn record_plan_merges_with_existing() {
+        let tmp = TempDir::new().unwrap();
+        let specs = tmp.path();
+
+        // Create existing feature
+        let feature_dir = specs.join("domain/feature");
+        fs::create_dir_all(&feature_dir).unwrap();
+        fs::write(
+            feature_dir.join("spec.md"),
+            r#"# Feature: Existing
+
+Description.
+
+## Background
+
+* Context.
 
 ## Scenarios
 
-<!-- DELTA:REMOVED -->
-### Scenario: Gone
+### Scenario: Original
 
-* Old content
-<!-- /DELTA:REMOVED -->
+* *GIVEN* original
+* *WHEN* action
+* *THEN* result SHALL happen
+"#,
+        )
+        .unwrap();
 
-### Scenario: Stays
+        // Create plan with delta
+        let plan_dir = specs.join("_plans/test-plan/domain/feature");
+        fs::create_dir_all(&plan_dir).unwrap();
+        fs::write(
+            plan_dir.join("spec.md"),
+            r#"## Scenarios
 
-* *GIVEN* valid
-"#;
+<!-- DELTA:NEW -->
+### Scenario: Added
 
-        let stripped = strip_delta_markers(content);
+* *GIVEN* added
+* *WHEN* action
+* *THEN* result SHALL happen
+<!-- /DELTA:NEW -->
+"#,
+        )
+        .unwrap();
 
-        assert!(!stripped.contains("Gone"));
-        assert!(stripped.contains("### Scenario: Stays"));
+        fs::create_dir_all(specs.join("_recorded")).unwrap();
+
+        let result = record_unchecked(specs, "test-plan").unwrap().features;
+        assert_eq!(result.len(), 1);
+
+        let spec_content = fs::read_to_string(specs.join("domain/feature/spec.md")).unwrap();
+        assert!(spec_content.contains("### Scenario: Original"));
+        assert!(spec_content.contains("### Scenario: Added"));
     }
 
     #[test]
-    fn merge_appends_new_scenario() {
-        let existing = r#"# Feature: Test
+    fn record_plan_refuses_empty_changed_block() {
+        let tmp = TempDir::new().unwrap();
+        let specs = tmp.path();
+        let existing = "# Feature: Login\n\nUsers log in.\n\n## Background\n\n* Users have accounts\n\n## Scenarios\n\n### Scenario: Login works\n\n* *GIVEN* a user\n* *WHEN* they log in\n* *THEN* the system SHALL admit them\n";
+        fs::create_dir_all(specs.join("auth/login")).unwrap();
+        fs::write(specs.join("auth/login/spec.md"), existing).unwrap();
+        let plan_dir = specs.join("_plans/change-login");
+        fs::create_dir_all(plan_dir.join("auth/login")).unwrap();
+        fs::write(
+            plan_dir.join("auth/login/spec.md"),
+            "<!-- DELTA:CHANGED -->\n<!-- /DELTA:CHANGED -->\n",
+        )
+        .unwrap();
+
+        let result = record_unchecked(specs, "change-login");
+        let Err(RecordError::InDeltaFile { source, .. }) = result else {
+            panic!("expected InDeltaFile, got {:?}", result);
+        };
+        assert!(
+            source
+                .to_string()
+                .contains("DELTA:CHANGED block has no '### Scenario:' title"),
+            "{source}"
+        );
+        assert_eq!(
+            fs::read_to_string(specs.join("auth/login/spec.md")).unwrap(),
+            existing
+        );
+        assert!(plan_dir.exists());
+    }
+
+    #[test]
+    fn record_plan_refuses_invalid_merge_without_writing() {
+        let tmp = TempDir::new().unwrap();
+        let specs = tmp.path();
+
+        let existing = r#"# Feature: Existing
+
+Description.
+
+## Background
+
+* Context.
 
 ## Scenarios
 
-### Scenario: Existing
+### Scenario: Original
 
-* *GIVEN* something
+* *GIVEN* original
+* *WHEN* action
+* *THEN* result SHALL happen
 "#;
+        let feature_dir = specs.join("domain/feature");
+        fs::create_dir_all(&feature_dir).unwrap();
+        fs::write(feature_dir.join("spec.md"), existing).unwrap();
 
-        let delta = r#"## Scenarios
+        // Second feature is valid on its own, but must not be written either
+        let plan_dir = specs.join("_plans/test-plan");
+        fs::create_dir_all(plan_dir.join("domain/feature")).unwrap();
+        fs::write(
+            plan_dir.join("domain/feature/spec.md"),
+            r#"<!-- DELTA:NEW -->
+### Scenario: Missing steps
+
+* *GIVEN* only a setup
+<!-- /DELTA:NEW -->
+"#,
+        )
+        .unwrap();
+        fs::create_dir_all(plan_dir.join("domain/other")).unwrap();
+        fs::write(
+            plan_dir.join("domain/other/spec.md"),
+            r#"# Feature: Other
+
+Description.
+
+## Background
+
+* Context.
+
+## Scenarios
 
 <!-- DELTA:NEW -->
-### Scenario: New one
+### Scenario: Fine
 
-* *GIVEN* new thing
+* *GIVEN* setup
+* *WHEN* action
+* *THEN* result SHALL happen
 <!-- /DELTA:NEW -->
-"#;
+"#,
+        )
+        .unwrap();
 
-        let merged = merge_delta(existing, delta).unwrap();
+        let result = record_unchecked(specs, "test-plan");
+        let Err(RecordError::InvalidMergedSpecs { failures }) = result else {
+            panic!("expected InvalidMergedSpecs, got {:?}", result);
+        };
 
-        assert!(merged.contains("### Scenario: Existing"));
-        assert!(merged.contains("### Scenario: New one"));
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].feature, "domain/feature");
+        assert!(
+            failures[0]
+                .delta_path
+                .ends_with("_plans/test-plan/domain/feature/spec.md")
+        );
+        assert!(
+            failures[0]
+                .errors
+                .contains(&ValidationError::ScenarioMissingWhen {
+                    scenario: "Missing steps".to_string(),
+                })
+        );
+
+        assert_eq!(
+            fs::read_to_string(feature_dir.join("spec.md")).unwrap(),
+            existing
+        );
+        assert!(!specs.join("domain/other/spec.md").exists());
+        assert!(plan_dir.exists());
+        assert!(!specs.join("_recorded").exists());
     }
 
-    #[test]
-    fn merge_returns_existing_when_no_deltas() {
-        let existing = "# Feature: Test\n\nContent here.\n";
-        let delta = "No delta markers here.\n";
+    const AUTH_SPEC: &str = r#"# Feature: Auth
 
-        let merged = merge_delta(existing, delta).unwrap();
-        assert_eq!(merged, existing);
-    }
+Users authenticate.
 
-    #[test]
-    fn merge_replaces_changed_scenario() {
-        let existing = r#"# Feature: Test
+## Background
+
+* Accounts exist.
 
 ## Scenarios
 
 ### Scenario: Login
 
-* *GIVEN* old setup
-* *WHEN* old action
-* *THEN* old result
+* *GIVEN* a user
+* *WHEN* they log in
+* *THEN* the system SHALL grant access
 
-### Scenario: Other
+### Scenario: Logout
+
+* *GIVEN* a session
+* *WHEN* they log out
+* *THEN* the system SHALL end the session
+"#;
+
+    fn setup_plan_with_base(specs: &Path, delta: &str) {
+        let feature_dir = specs.join("auth/login");
+        fs::create_dir_all(&feature_dir).unwrap();
+        fs::write(feature_dir.join("spec.md"), AUTH_SPEC).unwrap();
+
+        let plan_dir = specs.join("_plans/second-plan/auth/login");
+        fs::create_dir_all(&plan_dir).unwrap();
+        fs::write(plan_dir.join("spec.md"), delta).unwrap();
+
+        let snapshotted = snapshot_bases(specs, "second-plan", false).unwrap();
+        assert_eq!(snapshotted, vec!["auth/login".to_string()]);
+    }
+
+    #[test]
+    fn snapshot_bases_keeps_existing_snapshot_unless_refreshed() {
+        let tmp = TempDir::new().unwrap();
+        let specs = tmp.path();
+        setup_plan_with_base(specs, "");
+        let base_path = specs.join("_plans/second-plan/auth/login/base.md");
+        assert_eq!(fs::read_to_string(&base_path).unwrap(), AUTH_SPEC);
+
+        fs::write(specs.join("auth/login/spec.md"), "# Feature: Changed\n").unwrap();
+        assert!(
+            snapshot_bases(specs, "second-plan", false)
+                .unwrap()
+                .is_empty()
+        );
+        assert_eq!(fs::read_to_string(&base_path).unwrap(), AUTH_SPEC);
 
-* *GIVEN* other
-"#;
+        snapshot_bases(specs, "second-plan", true).unwrap();
+        assert_eq!(
+            fs::read_to_string(&base_path).unwrap(),
+            "# Feature: Changed\n"
+        );
+    }
 
-        let delta = r#"<!-- DELTA:CHANGED -->
-### Scenario: Login
+    #[test]
+    fn record_plan_three_way_merges_against_base() {
+        let tmp = TempDir::new().unwrap();
+        let specs = tmp.path();
+        setup_plan_with_base(
+            specs,
+            "<!-- DELTA:CHANGED -->\n### Scenario: Login\n\n* *GIVEN* a user\n* *WHEN* they log in\n* *THEN* the system SHALL grant access within 1s\n<!-- /DELTA:CHANGED -->\n",
+        );
 
-* *GIVEN* new setup
-* *WHEN* new action
-* *THEN* new result SHALL happen
-<!-- /DELTA:CHANGED -->
-"#;
+        // Another plan changed a different scenario after this one was created
+        let other_change = AUTH_SPEC.replace("SHALL end the session", "SHALL end all sessions");
+        fs::write(specs.join("auth/login/spec.md"), &other_change).unwrap();
 
-        let merged = merge_delta(existing, delta).unwrap();
+        record_unchecked(specs, "second-plan").unwrap();
 
-        assert!(merged.contains("new setup"));
-        assert!(!merged.contains("old setup"));
-        assert!(merged.contains("### Scenario: Other"));
+        let recorded = fs::read_to_string(specs.join("auth/login/spec.md")).unwrap();
+        assert!(recorded.contains("SHALL grant access within 1s"));
+        assert!(recorded.contains("SHALL end all sessions"));
+        assert!(!recorded.contains("DELTA"));
     }
 
     #[test]
-    fn merge_removes_scenario() {
-        let existing = r#"# Feature: Test
+    fn record_plan_reports_features_merged_without_base() {
+        let tmp = TempDir::new().unwrap();
+        let specs = tmp.path();
+        setup_plan_with_base(specs, "");
+        fs::remove_file(specs.join("_plans/second-plan/auth/login/base.md")).unwrap();
+        let plan_dir = specs.join("_plans/second-plan/billing/invoices");
+        fs::create_dir_all(&plan_dir).unwrap();
+        fs::write(plan_dir.join("spec.md"), AUTH_SPEC).unwrap();
 
-## Scenarios
+        let outcome = record_unchecked(specs, "second-plan").unwrap();
 
-### Scenario: Keep
+        // A new feature has nothing to merge against, so only auth/login is reported
+        assert_eq!(outcome.without_base, vec!["auth/login".to_string()]);
+    }
 
-* *GIVEN* keep this
+    #[test]
+    fn preview_feature_merges_like_record_without_writing() {
+        let tmp = TempDir::new().unwrap();
+        let specs = tmp.path();
+        setup_plan_with_base(
+            specs,
+            "<!-- DELTA:CHANGED -->\n### Scenario: Login\n\n* *GIVEN* a user\n* *WHEN* they log in\n* *THEN* the system SHALL grant access within 1s\n<!-- /DELTA:CHANGED -->\n",
+        );
+        let other_change = AUTH_SPEC.replace("SHALL end the session", "SHALL end all sessions");
+        fs::write(specs.join("auth/login/spec.md"), &other_change).unwrap();
 
-### Scenario: Remove
+        let preview = preview_feature(specs, "second-plan", "auth/login").unwrap();
 
-* *GIVEN* remove this
+        assert_eq!(preview.current.as_deref(), Some(other_change.as_str()));
+        assert!(preview.merged.contains("SHALL grant access within 1s"));
+        assert!(preview.merged.contains("SHALL end all sessions"));
+        assert!(
+            preview
+                .annotated()
+                .contains("<!-- PREVIEW:CHANGED -->\n### Scenario: Login\n")
+        );
+        assert_eq!(
+            fs::read_to_string(specs.join("auth/login/spec.md")).unwrap(),
+            other_change
+        );
 
-### Scenario: Also Keep
+        let missing = preview_feature(specs, "second-plan", "auth/signup");
+        assert!(
+            matches!(missing, Err(RecordError::NoDeltaSpec { feature, .. }) if feature == "auth/signup")
+        );
+    }
 
-* *GIVEN* also keep
-"#;
+    #[test]
+    fn record_plan_reports_conflicts_without_writing() {
+        let tmp = TempDir::new().unwrap();
+        let specs = tmp.path();
+        setup_plan_with_base(
+            specs,
+            "<!-- DELTA:CHANGED -->\n### Scenario: Login\n\n* *GIVEN* a user\n* *WHEN* they log in\n* *THEN* the system SHALL grant access within 1s\n<!-- /DELTA:CHANGED -->\n",
+        );
 
-        let delta = r#"<!-- DELTA:REMOVED -->
-### Scenario: Remove
-<!-- /DELTA:REMOVED -->
-"#;
+        let other_change = AUTH_SPEC.replace("SHALL grant access", "SHALL grant read access");
+        fs::write(specs.join("auth/login/spec.md"), &other_change).unwrap();
 
-        let merged = merge_delta(existing, delta).unwrap();
+        let result = record_unchecked(specs, "second-plan");
+        let Err(RecordError::MergeConflicts { path, conflicts }) = result else {
+            panic!("expected MergeConflicts, got {:?}", result);
+        };
 
-        assert!(merged.contains("### Scenario: Keep"));
-        assert!(merged.contains("### Scenario: Also Keep"));
-        assert!(!merged.contains("### Scenario: Remove"));
-        assert!(!merged.contains("remove this"));
+        assert!(path.ends_with("_plans/second-plan/auth/login/spec.md"));
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].section, "Scenario 'Login'");
+        assert!(conflicts[0].rendered.contains("<<<<<<< current"));
+        assert_eq!(
+            fs::read_to_string(specs.join("auth/login/spec.md")).unwrap(),
+            other_change
+        );
+        assert!(specs.join("_plans/second-plan").exists());
     }
 
     #[test]
-    fn extract_scenario_title_returns_none_for_no_scenario() {
-        let content = "Just some text\nwithout scenario heading";
-        assert_eq!(extract_scenario_title(content), None);
+    fn record_plan_moves_feature_and_merges_delta_at_new_path() {
+        let tmp = TempDir::new().unwrap();
+        let specs = tmp.path();
+        fs::create_dir_all(specs.join("auth/login")).unwrap();
+        fs::write(specs.join("auth/login/spec.md"), AUTH_SPEC).unwrap();
+        let session = AUTH_SPEC.replace("Users authenticate.", "Sessions follow `auth/login`.");
+        fs::create_dir_all(specs.join("cli/session")).unwrap();
+        fs::write(specs.join("cli/session/spec.md"), &session).unwrap();
+
+        let plan_dir = specs.join("_plans/move-login");
+        fs::create_dir_all(plan_dir.join("identity/login")).unwrap();
+        fs::write(
+            plan_dir.join("plan.md"),
+            "# Plan\n\n## Feature Operations\n\n* MOVE auth/login -> identity/login\n",
+        )
+        .unwrap();
+        fs::write(
+            plan_dir.join("identity/login/spec.md"),
+            "<!-- DELTA:RENAMED -->\n# Feature: Identity\n<!-- /DELTA:RENAMED -->\n",
+        )
+        .unwrap();
+
+        let outcome = record_unchecked(specs, "move-login").unwrap();
+
+        assert!(!specs.join("auth").exists());
+        assert_eq!(
+            fs::read_to_string(specs.join("identity/login/spec.md")).unwrap(),
+            AUTH_SPEC.replace("# Feature: Auth", "# Feature: Identity")
+        );
+        assert!(
+            fs::read_to_string(specs.join("cli/session/spec.md"))
+                .unwrap()
+                .contains("`identity/login`")
+        );
+        assert_eq!(outcome.features, vec!["identity/login".to_string()]);
+        assert_eq!(outcome.reference_changes.len(), 1);
+        let report = fs::read_to_string(outcome.archive_dir.join(OPERATIONS_REPORT_FILE)).unwrap();
+        assert!(report.contains("* MOVE auth/login -> identity/login"));
+        assert!(report.contains("cli/session/spec.md: 1 reference(s) to auth/login"));
+        let images = outcome.archive_dir.join(RECORD_IMAGES_DIR);
+        assert_eq!(
+            fs::read_to_string(images.join("cli/session").join(PRE_IMAGE_FILE)).unwrap(),
+            session
+        );
     }
 
     #[test]
-    fn record_plan_creates_new_feature() {
+    fn record_plan_refuses_invalid_rewritten_reference_without_writing() {
         let tmp = TempDir::new().unwrap();
         let specs = tmp.path();
+        fs::create_dir_all(specs.join("auth/login")).unwrap();
+        fs::write(specs.join("auth/login/spec.md"), AUTH_SPEC).unwrap();
+        let session = "# Feature: Session\n\nSessions follow `auth/login`.\n";
+        fs::create_dir_all(specs.join("cli/session")).unwrap();
+        fs::write(specs.join("cli/session/spec.md"), session).unwrap();
 
-        // Create plan with new feature
-        let plan_dir = specs.join("_plans/test-plan/domain/feature");
+        let plan_dir = specs.join("_plans/move-login");
         fs::create_dir_all(&plan_dir).unwrap();
         fs::write(
-            plan_dir.join("spec.md"),
-            r#"# Feature: New Feature
+            plan_dir.join("plan.md"),
+            "# Plan\n\n## Feature Operations\n\n* MOVE auth/login -> identity/login\n",
+        )
+        .unwrap();
 
-Description here.
+        let Err(RecordError::InvalidMergedSpecs { failures }) =
+            record_unchecked(specs, "move-login")
+        else {
+            panic!("expected InvalidMergedSpecs");
+        };
 
-## Background
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].feature, "cli/session");
+        assert!(specs.join("auth/login/spec.md").exists());
+        assert_eq!(
+            fs::read_to_string(specs.join("cli/session/spec.md")).unwrap(),
+            session
+        );
+        assert!(plan_dir.exists());
+    }
 
-* Context.
+    #[test]
+    fn record_plan_stamps_archive_with_given_date() {
+        let tmp = TempDir::new().unwrap();
+        let specs = tmp.path();
+        let plan_dir = specs.join("_plans/import-old/auth/login");
+        fs::create_dir_all(&plan_dir).unwrap();
+        fs::write(plan_dir.join("spec.md"), AUTH_SPEC).unwrap();
+        let options = RecordOptions {
+            date: NaiveDate::from_ymd_opt(2021, 3, 4),
+            force: true,
+            ..Default::default()
+        };
 
-## Scenarios
+        let archive_dir = record_plan_with(specs, "import-old", &options)
+            .unwrap()
+            .archive_dir;
 
-<!-- DELTA:NEW -->
-### Scenario: Test
+        assert_eq!(archive_dir, specs.join("_recorded/2021-03-04-import-old"));
+        let manifest = fs::read_to_string(archive_dir.join(ARCHIVE_MANIFEST_FILE)).unwrap();
+        assert!(manifest.contains("* Recorded: 2021-03-04\n"));
+    }
 
-* *GIVEN* setup
-* *WHEN* action
-* *THEN* result SHALL happen
-<!-- /DELTA:NEW -->
-"#,
+    #[test]
+    fn record_plan_promotes_decisions_when_asked() {
+        let tmp = TempDir::new().unwrap();
+        let specs = tmp.path();
+        let plan_dir = specs.join("_plans/add-login");
+        fs::create_dir_all(plan_dir.join("auth/login")).unwrap();
+        fs::write(plan_dir.join("auth/login/spec.md"), AUTH_SPEC).unwrap();
+        fs::write(
+            plan_dir.join(DECISION_LOG_FILE),
+            "# Decision Log: add-login\n\nDate: 2021-03-01\n\n## Design Decisions\n\n### [1] Use passwords\n\n- **Decision:** Passwords.\n- **Rationale:** Simple.\n- **Promotes to ADR:** yes\n",
         )
         .unwrap();
+        let options = RecordOptions {
+            date: NaiveDate::from_ymd_opt(2021, 3, 4),
+            promote_decisions: true,
+            force: true,
+            ..Default::default()
+        };
 
-        // Create _recorded directory
-        fs::create_dir_all(specs.join("_recorded")).unwrap();
+        let outcome = record_plan_with(specs, "add-login", &options).unwrap();
 
-        let result = record_plan(specs, "test-plan").unwrap();
+        assert_eq!(outcome.adrs, vec!["ADR-001: Use passwords"]);
+        let log = fs::read_to_string(specs.join(DECISION_LOG_FILE)).unwrap();
+        assert!(
+            log.contains(
+                "## ADR-001: Use passwords\n\n**Date:** 2021-03-04\n**Plan:** add-login\n"
+            )
+        );
+    }
 
-        assert_eq!(result.len(), 1);
-        assert!(result[0].contains("domain/feature"));
+    #[test]
+    fn source_date_epoch_is_a_utc_date() {
+        assert_eq!(
+            date_from_epoch("1700000000").unwrap(),
+            NaiveDate::from_ymd_opt(2023, 11, 14).unwrap()
+        );
+        assert!(matches!(
+            date_from_epoch("yesterday"),
+            Err(RecordError::InvalidSourceDateEpoch(_))
+        ));
+    }
 
-        // Verify spec created
-        let spec_content = fs::read_to_string(specs.join("domain/feature/spec.md")).unwrap();
-        assert!(spec_content.contains("### Scenario: Test"));
-        assert!(!spec_content.contains("DELTA"));
+    #[test]
+    fn explicit_record_date_wins() {
+        let date = NaiveDate::from_ymd_opt(2020, 1, 2);
+        assert_eq!(record_date(date).unwrap(), date.unwrap());
+    }
 
-        // Verify plan archived with date prefix
-        let recorded_dir = specs.join("_recorded");
-        let entries: Vec<_> = fs::read_dir(&recorded_dir)
+    #[test]
+    fn record_plan_suffixes_archive_recorded_same_day() {
+        let tmp = TempDir::new().unwrap();
+        let specs = tmp.path();
+        let plan_dir = specs.join("_plans/fix-validation/auth/login");
+        fs::create_dir_all(&plan_dir).unwrap();
+        fs::write(plan_dir.join("spec.md"), AUTH_SPEC).unwrap();
+        let first = record_unchecked(specs, "fix-validation")
             .unwrap()
-            .filter_map(|e| e.ok())
-            .collect();
-        assert_eq!(entries.len(), 1, "Expected exactly one recorded plan");
+            .archive_dir;
+        fs::create_dir_all(&plan_dir).unwrap();
+        fs::write(plan_dir.join("spec.md"), AUTH_SPEC).unwrap();
 
-        let archived_name = entries[0].file_name().to_string_lossy().to_string();
-        assert!(
-            archived_name.ends_with("-test-plan"),
-            "Archive should end with plan name"
+        let second = record_unchecked(specs, "fix-validation")
+            .unwrap()
+            .archive_dir;
+
+        assert_eq!(
+            second.file_name().unwrap().to_string_lossy(),
+            format!("{}-2", first.file_name().unwrap().to_string_lossy())
         );
+        assert!(first.join("auth/login/spec.md").exists());
+        assert!(second.join("auth/login/spec.md").exists());
+    }
 
-        // Verify date prefix format (YYYY-MM-DD-)
-        let date_prefix = &archived_name[..11];
-        assert!(
-            date_prefix.chars().nth(4) == Some('-')
-                && date_prefix.chars().nth(7) == Some('-')
-                && date_prefix.chars().nth(10) == Some('-'),
-            "Archive should have date prefix format YYYY-MM-DD-"
+    #[test]
+    fn record_plan_writes_archive_manifest() {
+        let tmp = TempDir::new().unwrap();
+        let specs = tmp.path();
+        fs::create_dir_all(specs.join("auth/legacy")).unwrap();
+        fs::write(specs.join("auth/legacy/spec.md"), AUTH_SPEC).unwrap();
+        let plan_dir = specs.join("_plans/add-login");
+        fs::create_dir_all(plan_dir.join("auth/login")).unwrap();
+        fs::write(plan_dir.join("auth/login/spec.md"), AUTH_SPEC).unwrap();
+        fs::write(
+            plan_dir.join("plan.md"),
+            "## Feature Operations\n\n* DELETE auth/legacy\n",
+        )
+        .unwrap();
+
+        let archive_dir = record_unchecked(specs, "add-login").unwrap().archive_dir;
+
+        let manifest = fs::read_to_string(archive_dir.join(ARCHIVE_MANIFEST_FILE)).unwrap();
+        let parsed = ArchiveManifest::parse(&manifest).unwrap();
+        assert_eq!(parsed.plan_name, "add-login");
+        assert_eq!(parsed.features, vec!["auth/login", "auth/legacy"]);
+        assert_eq!(parsed.render(), manifest);
+        assert_eq!(recorded_plan_name(&archive_dir), "add-login");
+    }
+
+    #[test]
+    fn recorded_plan_name_without_manifest_strips_date_prefix() {
+        let tmp = TempDir::new().unwrap();
+
+        assert_eq!(
+            recorded_plan_name(&tmp.path().join("2024-01-31-add-auth")),
+            "add-auth"
         );
+        assert_eq!(recorded_plan_name(&tmp.path().join("add-auth")), "add-auth");
+    }
 
-        assert!(!specs.join("_plans/test-plan").exists());
+    fn write_two_feature_plan(specs: &Path) -> PathBuf {
+        let plan_dir = specs.join("_plans/staged");
+        for feature in ["auth/login", "billing/invoice"] {
+            fs::create_dir_all(plan_dir.join(feature)).unwrap();
+            fs::write(plan_dir.join(feature).join("spec.md"), AUTH_SPEC).unwrap();
+        }
+        fs::write(plan_dir.join("plan.md"), "# Plan\n").unwrap();
+        plan_dir
     }
 
     #[test]
-    fn record_plan_merges_with_existing() {
+    fn record_plan_with_selected_feature_keeps_rest_of_plan() {
         let tmp = TempDir::new().unwrap();
         let specs = tmp.path();
+        let plan_dir = write_two_feature_plan(specs);
+        let options = RecordOptions {
+            features: vec!["auth/login".to_string()],
+            force: true,
+            ..Default::default()
+        };
 
-        // Create existing feature
-        let feature_dir = specs.join("domain/feature");
-        fs::create_dir_all(&feature_dir).unwrap();
-        fs::write(
-            feature_dir.join("spec.md"),
-            r#"# Feature: Existing
+        let outcome = record_plan_with(specs, "staged", &options).unwrap();
+
+        assert_eq!(outcome.features, vec!["auth/login".to_string()]);
+        assert_eq!(outcome.remaining, vec!["billing/invoice".to_string()]);
+        assert!(specs.join("auth/login/spec.md").exists());
+        assert!(!specs.join("billing/invoice/spec.md").exists());
+        assert!(!plan_dir.join("auth").exists());
+        assert!(plan_dir.join("billing/invoice/spec.md").exists());
+        assert!(outcome.archive_dir.join("auth/login/spec.md").exists());
+        assert!(outcome.archive_dir.join("plan.md").exists());
+        let report = fs::read_to_string(outcome.archive_dir.join(PARTIAL_RECORD_FILE)).unwrap();
+        assert!(report.contains("## Recorded\n\n* auth/login\n"));
+        assert!(report.contains("## Left in Plan\n\n* billing/invoice\n"));
+    }
 
-Description.
+    #[test]
+    fn record_plan_archives_plan_once_last_feature_is_recorded() {
+        let tmp = TempDir::new().unwrap();
+        let specs = tmp.path();
+        let plan_dir = write_two_feature_plan(specs);
+        let options = RecordOptions {
+            features: vec!["auth/login".to_string(), "billing/invoice".to_string()],
+            force: true,
+            ..Default::default()
+        };
 
-## Background
+        let outcome = record_plan_with(specs, "staged", &options).unwrap();
 
-* Context.
+        assert!(outcome.remaining.is_empty());
+        assert!(!plan_dir.exists());
+        assert!(!outcome.archive_dir.join(PARTIAL_RECORD_FILE).exists());
+    }
 
-## Scenarios
+    #[test]
+    fn record_plan_with_selected_operation_drops_its_bullet_from_plan() {
+        let tmp = TempDir::new().unwrap();
+        let specs = tmp.path();
+        for feature in ["auth/login", "auth/legacy"] {
+            fs::create_dir_all(specs.join(feature)).unwrap();
+            fs::write(specs.join(feature).join("spec.md"), AUTH_SPEC).unwrap();
+        }
+        let plan_dir = specs.join("_plans/reorganize");
+        fs::create_dir_all(&plan_dir).unwrap();
+        let plan_md =
+            "## Feature Operations\n\n* DELETE auth/legacy\n* MOVE auth/login -> identity/login\n";
+        fs::write(plan_dir.join("plan.md"), plan_md).unwrap();
+        let options = RecordOptions {
+            features: vec!["auth/legacy".to_string()],
+            force: true,
+            ..Default::default()
+        };
 
-### Scenario: Original
+        let outcome = record_plan_with(specs, "reorganize", &options).unwrap();
 
-* *GIVEN* original
-"#,
+        assert_eq!(
+            outcome.remaining,
+            vec!["MOVE auth/login -> identity/login".to_string()]
+        );
+        assert!(!specs.join("auth/legacy").exists());
+        assert!(specs.join("auth/login/spec.md").exists());
+        assert_eq!(
+            fs::read_to_string(plan_dir.join("plan.md")).unwrap(),
+            "## Feature Operations\n\n* MOVE auth/login -> identity/login\n"
+        );
+        assert_eq!(
+            fs::read_to_string(outcome.archive_dir.join("plan.md")).unwrap(),
+            plan_md
+        );
+    }
+
+    #[test]
+    fn record_plan_refuses_unknown_selected_feature() {
+        let tmp = TempDir::new().unwrap();
+        let specs = tmp.path();
+        write_two_feature_plan(specs);
+        let options = RecordOptions {
+            features: vec!["auth/logn".to_string()],
+            force: true,
+            ..Default::default()
+        };
+
+        let result = record_plan_with(specs, "staged", &options);
+
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "Plan 'staged' has no delta spec or feature operation for: auth/logn"
+        );
+        assert!(!specs.join("auth").exists());
+    }
+
+    #[test]
+    fn record_plan_refuses_invalid_operations_without_writing() {
+        let tmp = TempDir::new().unwrap();
+        let specs = tmp.path();
+        fs::create_dir_all(specs.join("auth/login")).unwrap();
+        fs::write(specs.join("auth/login/spec.md"), AUTH_SPEC).unwrap();
+
+        let plan_dir = specs.join("_plans/retire");
+        fs::create_dir_all(plan_dir.join("auth/login")).unwrap();
+        fs::write(
+            plan_dir.join("plan.md"),
+            "## Feature Operations\n\n* DELETE auth/login\n* DELETE auth/missing\n",
         )
         .unwrap();
+        fs::write(plan_dir.join("auth/login/spec.md"), AUTH_SPEC).unwrap();
 
-        // Create plan with delta
-        let plan_dir = specs.join("_plans/test-plan/domain/feature");
-        fs::create_dir_all(&plan_dir).unwrap();
-        fs::write(
-            plan_dir.join("spec.md"),
-            r#"## Scenarios
+        let result = record_unchecked(specs, "retire");
 
-<!-- DELTA:NEW -->
-### Scenario: Added
+        let Err(RecordError::InvalidFeatureOperations { errors }) = result else {
+            panic!("expected InvalidFeatureOperations, got {:?}", result);
+        };
+        assert_eq!(errors.len(), 2);
+        assert!(specs.join("auth/login/spec.md").exists());
+        assert!(plan_dir.exists());
+    }
 
-* *GIVEN* added
-<!-- /DELTA:NEW -->
-"#,
+    #[test]
+    fn record_plan_refuses_unverified_plan_without_writing() {
+        let tmp = TempDir::new().unwrap();
+        let specs = tmp.path();
+        let plan_dir = specs.join("_plans/add-login");
+        fs::create_dir_all(plan_dir.join("auth/login")).unwrap();
+        fs::write(plan_dir.join("auth/login/spec.md"), AUTH_SPEC).unwrap();
+        fs::write(
+            plan_dir.join("plan.md"),
+            "# Plan: add-login\n\nStatus: approved\n",
         )
         .unwrap();
 
-        fs::create_dir_all(specs.join("_recorded")).unwrap();
-
-        let result = record_plan(specs, "test-plan").unwrap();
-        assert_eq!(result.len(), 1);
+        let result = record_plan(specs, "add-login");
+        let Err(RecordError::NotReady(reason)) = result else {
+            panic!("expected NotReady, got {:?}", result);
+        };
+        assert!(matches!(reason, PlanStatusError::NotVerified { .. }));
+        assert!(!specs.join("auth/login/spec.md").exists());
 
-        let spec_content = fs::read_to_string(specs.join("domain/feature/spec.md")).unwrap();
-        assert!(spec_content.contains("### Scenario: Original"));
-        assert!(spec_content.contains("### Scenario: Added"));
+        fs::write(
+            plan_dir.join("plan.md"),
+            "# Plan: add-login\n\nStatus: verified\nApproved by: Dana on 2026-05-02\n",
+        )
+        .unwrap();
+        fs::write(
+            plan_dir.join("verification-report.md"),
+            "# Verification Report: add-login\n\nResult: pass\n",
+        )
+        .unwrap();
+        record_plan(specs, "add-login").unwrap();
+        assert!(specs.join("auth/login/spec.md").exists());
     }
 
     #[test]
@@ -0,0 +1,1045 @@
+use std::fs;
+use std::ops::Range;
+use std::path::Path;
+
+use thiserror::Error;
+
+use crate::decision_log::{self, DECISION_LOG_FILE};
+use crate::feature_ops::{self, FeatureOperation, PlannedOperation};
+use crate::promote;
+use crate::record::{
+    self, ARCHIVE_MANIFEST_FILE, ArchiveManifest, DeltaBlock, DeltaKind, OPERATIONS_REPORT_FILE,
+    PARTIAL_RECORD_FILE, POST_IMAGE_FILE, PRE_IMAGE_FILE, Placement, RECORD_IMAGES_DIR,
+};
+use crate::validate::parser;
+
+#[derive(Error, Debug)]
+pub enum RevertError {
+    #[error("Recorded plan not found: {0}")]
+    ArchiveNotFound(String),
+
+    #[error("{0} has no {RECORD_IMAGES_DIR}/ and cannot be reverted")]
+    NoRecordImages(String),
+
+    #[error("A plan named '{0}' already exists in specs/_plans/")]
+    PlanExists(String),
+
+    #[error("Later changes touch what this plan recorded; nothing was reverted")]
+    LaterChanges { conflicts: Vec<String> },
+
+    #[error("Failed to read file: {path}")]
+    FileReadError { path: String },
+
+    #[error("Failed to write file: {path}")]
+    FileWriteError { path: String },
+
+    #[error("Failed to move directory: {from} -> {to}: {source}")]
+    DirMoveError {
+        from: String,
+        to: String,
+        source: std::io::Error,
+    },
+
+    #[error("Failed to undo feature operations under {path}: {source}")]
+    FeatureOpsError {
+        path: String,
+        source: std::io::Error,
+    },
+
+    #[error("{path}: {source}")]
+    InvalidDelta {
+        path: String,
+        source: record::RecordError,
+    },
+}
+
+/// What `revert_plan` restored.
+#[derive(Debug)]
+pub struct RevertOutcome {
+    pub plan_name: String,
+    pub features: Vec<String>,
+    /// Moves and renames undone, in the order they were reversed
+    pub operations: Vec<FeatureOperation>,
+    /// Promoted ADRs removed from `specs/decision-log.md`
+    pub removed_adrs: Vec<String>,
+    /// Promoted ADRs left in `specs/decision-log.md` because later ADRs follow or cite them
+    pub kept_adrs: Vec<String>,
+}
+
+/// A permanent spec before and after the recording, read from the archive's record images.
+struct FeatureImage {
+    feature: String,
+    before: Option<String>,
+    after: Option<String>,
+}
+
+/// The content a revert writes for one feature, at its post-recording path.
+struct RestoredSpec {
+    feature: String,
+    content: Option<String>,
+    /// The pre-image is restored as is, so its references need no rewriting
+    verbatim: bool,
+}
+
+/// What a revert changed on disk so far, undone in reverse order if a later step fails.
+enum RevertStep {
+    /// A spec written or removed, with its content before
+    Spec {
+        feature: String,
+        previous: Option<String>,
+    },
+    Moved(PlannedOperation),
+}
+
+/// Undo a recorded plan: restore every permanent spec it changed and move the plan from
+/// `_recorded/<archive_name>` back to `_plans/`.
+///
+/// A partial recording whose plan is still active is merged back into it: its deltas return
+/// to the plan directory and plan.md gets back the operation bullets the recording removed.
+///
+/// When a spec is unchanged since recording its pre-image is restored as is. Otherwise each
+/// delta is inverted individually, and the revert is refused if a later change touched any
+/// scenario or section the plan recorded.
+pub fn revert_plan(specs_base: &Path, archive_name: &str) -> Result<RevertOutcome, RevertError> {
+    let archive_dir = specs_base.join("_recorded").join(archive_name);
+    if !archive_dir.is_dir() {
+        return Err(RevertError::ArchiveNotFound(archive_name.to_string()));
+    }
+
+    let images_dir = archive_dir.join(RECORD_IMAGES_DIR);
+    if !images_dir.is_dir() {
+        return Err(RevertError::NoRecordImages(archive_name.to_string()));
+    }
+
+    let plan_name = record::recorded_plan_name(&archive_dir);
+    let plan_dir = specs_base.join("_plans").join(&plan_name);
+    let partial = read_optional(&archive_dir.join(PARTIAL_RECORD_FILE))?;
+    let merge_back = plan_dir.exists();
+    if merge_back && partial.is_none() {
+        return Err(RevertError::PlanExists(plan_name));
+    }
+
+    let archived_plan_md = read_optional(&archive_dir.join("plan.md"))?;
+    let mut operations = match &archived_plan_md {
+        Some(content) => feature_ops::parse_operations(content).0,
+        None => Vec::new(),
+    };
+    // A partial archive keeps the whole plan.md; only its recorded operations are undone
+    let recorded = partial.as_deref().map(recorded_items).unwrap_or_default();
+    if partial.is_some() {
+        operations.retain(|planned| recorded.contains(&planned.operation.to_string()));
+    }
+
+    let mut conflicts = Vec::new();
+    let mut plan_md_update = None;
+    if merge_back {
+        for feature in &recorded {
+            if archive_dir.join(feature).is_dir() && plan_dir.join(feature).exists() {
+                conflicts.push(format!(
+                    "{feature}: the active plan '{plan_name}' has a delta for it again"
+                ));
+            }
+        }
+        if let (Some(archived), false) = (&archived_plan_md, operations.is_empty()) {
+            let lines: Vec<usize> = operations.iter().map(|p| p.line).collect();
+            let left = feature_ops::remove_operation_lines(archived, &lines);
+            if read_optional(&plan_dir.join("plan.md"))?.as_deref() == Some(left.as_str()) {
+                plan_md_update = Some(archived.clone());
+            } else {
+                conflicts.push(format!(
+                    "plan.md: the active plan '{plan_name}' changed since the partial record, so its operation bullets cannot be restored"
+                ));
+            }
+        }
+    }
+    let mut restored = Vec::new();
+
+    for image in load_images(&images_dir)? {
+        let current = read_optional(&specs_base.join(&image.feature).join("spec.md"))?;
+        let delta_path = archive_dir.join(&image.feature).join("spec.md");
+        let deltas = match read_optional(&delta_path)? {
+            Some(delta) => {
+                record::parse_deltas(&delta).map_err(|source| RevertError::InvalidDelta {
+                    path: delta_path.display().to_string(),
+                    source,
+                })?
+            }
+            None => Vec::new(),
+        };
+
+        match revert_feature(&image, current.as_deref(), &deltas) {
+            Ok(content) => restored.push(RestoredSpec {
+                verbatim: content == image.before,
+                feature: image.feature,
+                content,
+            }),
+            Err(found) => conflicts.extend(
+                found
+                    .into_iter()
+                    .map(|conflict| format!("{}: {}", image.feature, conflict)),
+            ),
+        }
+    }
+
+    // Moved features go back to their old path, which must still be free
+    let inverse = inverse_operations(&operations);
+    for planned in &inverse {
+        if let Some(destination) = planned.operation.destination()
+            && destination.spec_path(specs_base).exists()
+        {
+            conflicts.push(format!(
+                "{destination}: a permanent spec was created at the path {} had before recording",
+                planned.operation.source()
+            ));
+        }
+    }
+
+    if !conflicts.is_empty() {
+        return Err(RevertError::LaterChanges { conflicts });
+    }
+
+    // Point references to moved features back in memory, before anything is written
+    let rewritten = rewrite_references_back(specs_base, &mut restored, &inverse)?;
+
+    let mut steps = Vec::new();
+    if let Err(e) = apply_revert(specs_base, &restored, &inverse, &rewritten, &mut steps) {
+        undo_steps(specs_base, steps);
+        return Err(e);
+    }
+
+    let promoted = read_optional(&archive_dir.join(ARCHIVE_MANIFEST_FILE))?
+        .and_then(|content| ArchiveManifest::parse(&content))
+        .map(|manifest| manifest.adrs)
+        .unwrap_or_default();
+    let (removed_adrs, kept_adrs) = remove_promoted_adrs(specs_base, promoted)?;
+
+    if merge_back {
+        merge_into_plan(&archive_dir, &plan_dir, &recorded)?;
+        if let Some(content) = plan_md_update {
+            let plan_md = plan_dir.join("plan.md");
+            fs::write(&plan_md, content).map_err(|_| RevertError::FileWriteError {
+                path: plan_md.display().to_string(),
+            })?;
+        }
+        let _ = fs::remove_dir_all(&archive_dir);
+        return Ok(RevertOutcome {
+            plan_name,
+            features: restored.into_iter().map(|spec| spec.feature).collect(),
+            operations: inverse.into_iter().map(|p| p.operation).collect(),
+            removed_adrs,
+            kept_adrs,
+        });
+    }
+
+    // Return the plan to _plans without the files recording added to the archive
+    let _ = fs::remove_dir_all(&images_dir);
+    let _ = fs::remove_file(archive_dir.join(OPERATIONS_REPORT_FILE));
+    let _ = fs::remove_file(archive_dir.join(ARCHIVE_MANIFEST_FILE));
+    let _ = fs::remove_file(archive_dir.join(PARTIAL_RECORD_FILE));
+    fs::create_dir_all(specs_base.join("_plans")).map_err(|_| RevertError::FileWriteError {
+        path: specs_base.join("_plans").display().to_string(),
+    })?;
+    fs::rename(&archive_dir, &plan_dir).map_err(|source| RevertError::DirMoveError {
+        from: archive_dir.display().to_string(),
+        to: plan_dir.display().to_string(),
+        source,
+    })?;
+
+    Ok(RevertOutcome {
+        plan_name,
+        features: restored.into_iter().map(|spec| spec.feature).collect(),
+        operations: inverse.into_iter().map(|p| p.operation).collect(),
+        removed_adrs,
+        kept_adrs,
+    })
+}
+
+/// Drop the ADRs a recording promoted from the permanent log when nothing depends on them.
+/// Returns the removed and the kept headings.
+fn remove_promoted_adrs(
+    specs_base: &Path,
+    promoted: Vec<String>,
+) -> Result<(Vec<String>, Vec<String>), RevertError> {
+    let log_path = specs_base.join(DECISION_LOG_FILE);
+    if promoted.is_empty() {
+        return Ok((Vec::new(), Vec::new()));
+    }
+    let Some(content) = read_optional(&log_path)? else {
+        return Ok((Vec::new(), Vec::new()));
+    };
+
+    let numbers: Vec<u32> = promoted
+        .iter()
+        .filter_map(|heading| decision_log::parse_adr_heading(&format!("## {heading}")))
+        .collect();
+    match promote::remove_promoted_adrs(&content, &numbers) {
+        Some(updated) => {
+            fs::write(&log_path, updated).map_err(|_| RevertError::FileWriteError {
+                path: log_path.display().to_string(),
+            })?;
+            Ok((promoted, Vec::new()))
+        }
+        None => Ok((Vec::new(), promoted)),
+    }
+}
+
+/// The `* ` items under `## Recorded` in a partial record report
+fn recorded_items(report: &str) -> Vec<String> {
+    report
+        .lines()
+        .skip_while(|line| line.trim() != "## Recorded")
+        .skip(1)
+        .take_while(|line| !line.starts_with("## "))
+        .filter_map(|line| line.strip_prefix("* "))
+        .map(|item| item.trim().to_string())
+        .collect()
+}
+
+/// Move the deltas of a partial recording back into the plan it was recorded from
+fn merge_into_plan(
+    archive_dir: &Path,
+    plan_dir: &Path,
+    recorded: &[String],
+) -> Result<(), RevertError> {
+    for feature in recorded {
+        let from = archive_dir.join(feature);
+        if !from.is_dir() {
+            continue;
+        }
+        let to = plan_dir.join(feature);
+        let move_failed = |source| RevertError::DirMoveError {
+            from: from.display().to_string(),
+            to: to.display().to_string(),
+            source,
+        };
+        fs::create_dir_all(to.parent().unwrap()).map_err(move_failed)?;
+        fs::rename(&from, &to).map_err(move_failed)?;
+    }
+    Ok(())
+}
+
+fn read_optional(path: &Path) -> Result<Option<String>, RevertError> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    fs::read_to_string(path)
+        .map(Some)
+        .map_err(|_| RevertError::FileReadError {
+            path: path.display().to_string(),
+        })
+}
+
+fn load_images(images_dir: &Path) -> Result<Vec<FeatureImage>, RevertError> {
+    let list = |dir: &Path| -> Result<Vec<String>, RevertError> {
+        let entries = fs::read_dir(dir).map_err(|_| RevertError::FileReadError {
+            path: dir.display().to_string(),
+        })?;
+        let mut names: Vec<String> = entries
+            .filter_map(Result::ok)
+            .filter(|e| e.path().is_dir())
+            .filter_map(|e| e.file_name().into_string().ok())
+            .collect();
+        names.sort();
+        Ok(names)
+    };
+
+    let mut images = Vec::new();
+    for domain in list(images_dir)? {
+        for feature in list(&images_dir.join(&domain))? {
+            let dir = images_dir.join(&domain).join(&feature);
+            images.push(FeatureImage {
+                feature: format!("{domain}/{feature}"),
+                before: read_optional(&dir.join(PRE_IMAGE_FILE))?,
+                after: read_optional(&dir.join(POST_IMAGE_FILE))?,
+            });
+        }
+    }
+
+    Ok(images)
+}
+
+fn inverse_operations(operations: &[PlannedOperation]) -> Vec<PlannedOperation> {
+    operations
+        .iter()
+        .rev()
+        .filter_map(|planned| {
+            let operation = match &planned.operation {
+                FeatureOperation::Move { from, to } => FeatureOperation::Move {
+                    from: to.clone(),
+                    to: from.clone(),
+                },
+                FeatureOperation::Rename { from, to } => FeatureOperation::Rename {
+                    from: to.clone(),
+                    to: from.clone(),
+                },
+                // Deleted specs are restored from their pre-image
+                FeatureOperation::Delete(_) => return None,
+            };
+            Some(PlannedOperation {
+                line: planned.line,
+                operation,
+            })
+        })
+        .collect()
+}
+
+/// Rewrite references to moved features back to their old paths: in restored specs that
+/// are not their verbatim pre-image, and in every other permanent spec. Returns the other
+/// specs whose content changes.
+fn rewrite_references_back(
+    specs_base: &Path,
+    restored: &mut [RestoredSpec],
+    inverse: &[PlannedOperation],
+) -> Result<Vec<(String, String)>, RevertError> {
+    for spec in restored.iter_mut().filter(|spec| !spec.verbatim) {
+        if let Some(content) = spec.content.take() {
+            let feature = feature_ops::final_location(inverse, &spec.feature)
+                .unwrap_or_else(|| spec.feature.clone());
+            let mut specs = [(feature, content)];
+            feature_ops::rewrite_spec_references(&mut specs, inverse);
+            let [(_, content)] = specs;
+            spec.content = Some(content);
+        }
+    }
+
+    let mut rewritten = Vec::new();
+    for feature in crate::feature::discover_features(specs_base) {
+        let feature = feature.to_string();
+        if restored.iter().any(|spec| spec.feature == feature) {
+            continue;
+        }
+        let spec_path = specs_base.join(&feature).join("spec.md");
+        let Some(original) = read_optional(&spec_path)? else {
+            continue;
+        };
+        let mut specs = [(feature, original.clone())];
+        feature_ops::rewrite_spec_references(&mut specs, inverse);
+        let [(feature, content)] = specs;
+        if content != original {
+            rewritten.push((feature, content));
+        }
+    }
+
+    Ok(rewritten)
+}
+
+/// Write the restored specs, undo moves and renames, then write the specs whose references
+/// point back. Each finished step is added to `steps` so a failure can be undone.
+fn apply_revert(
+    specs_base: &Path,
+    restored: &[RestoredSpec],
+    inverse: &[PlannedOperation],
+    rewritten: &[(String, String)],
+    steps: &mut Vec<RevertStep>,
+) -> Result<(), RevertError> {
+    for spec in restored {
+        write_step(specs_base, &spec.feature, spec.content.as_deref(), steps)?;
+    }
+    for planned in inverse {
+        feature_ops::apply_operations(specs_base, std::slice::from_ref(planned)).map_err(
+            |source| RevertError::FeatureOpsError {
+                path: specs_base.display().to_string(),
+                source,
+            },
+        )?;
+        steps.push(RevertStep::Moved(planned.clone()));
+    }
+    for (feature, content) in rewritten {
+        write_step(specs_base, feature, Some(content), steps)?;
+    }
+    Ok(())
+}
+
+fn write_step(
+    specs_base: &Path,
+    feature: &str,
+    content: Option<&str>,
+    steps: &mut Vec<RevertStep>,
+) -> Result<(), RevertError> {
+    let previous = read_optional(&specs_base.join(feature).join("spec.md"))?;
+    write_restored(specs_base, feature, content)?;
+    steps.push(RevertStep::Spec {
+        feature: feature.to_string(),
+        previous,
+    });
+    Ok(())
+}
+
+/// Put back what a failed revert changed, latest step first. Best effort: the error that
+/// stopped the revert is what gets reported.
+fn undo_steps(specs_base: &Path, steps: Vec<RevertStep>) {
+    for step in steps.into_iter().rev() {
+        match step {
+            RevertStep::Spec { feature, previous } => {
+                let _ = write_restored(specs_base, &feature, previous.as_deref());
+            }
+            RevertStep::Moved(planned) => {
+                let _ = feature_ops::apply_operations(specs_base, &inverse_operations(&[planned]));
+            }
+        }
+    }
+}
+
+fn write_restored(
+    specs_base: &Path,
+    feature: &str,
+    content: Option<&str>,
+) -> Result<(), RevertError> {
+    let feature_dir = specs_base.join(feature);
+    let spec_path = feature_dir.join("spec.md");
+    let write_failed = || RevertError::FileWriteError {
+        path: spec_path.display().to_string(),
+    };
+
+    match content {
+        Some(content) => {
+            fs::create_dir_all(&feature_dir).map_err(|_| write_failed())?;
+            fs::write(&spec_path, content).map_err(|_| write_failed())
+        }
+        None => {
+            // The plan created this feature; remove it and any directories left empty
+            fs::remove_file(&spec_path).map_err(|_| write_failed())?;
+            let _ = fs::remove_dir(&feature_dir);
+            if let Some(domain_dir) = feature_dir.parent() {
+                let _ = fs::remove_dir(domain_dir);
+            }
+            Ok(())
+        }
+    }
+}
+
+/// The content to restore for one feature (`None` removes the spec), or the later
+/// changes that prevent it.
+fn revert_feature(
+    image: &FeatureImage,
+    current: Option<&str>,
+    deltas: &[DeltaBlock],
+) -> Result<Option<String>, Vec<String>> {
+    match (current, image.after.as_deref(), image.before.as_deref()) {
+        (None, None, before) => Ok(before.map(str::to_string)),
+        (Some(_), None, _) => Err(vec![
+            "a permanent spec was created again after the plan deleted it".to_string(),
+        ]),
+        (None, Some(_), _) => Err(vec![
+            "the permanent spec was removed or moved after recording".to_string(),
+        ]),
+        (Some(current), Some(after), before) if current == after => Ok(before.map(str::to_string)),
+        (Some(_), Some(_), None) => Err(vec![
+            "the plan created this feature and it changed after recording".to_string(),
+        ]),
+        (Some(current), Some(after), Some(before)) => {
+            invert_deltas(before, after, current, deltas).map(Some)
+        }
+    }
+}
+
+fn invert_deltas(
+    before: &str,
+    after: &str,
+    current: &str,
+    deltas: &[DeltaBlock],
+) -> Result<String, Vec<String>> {
+    let conflicts: Vec<String> = deltas
+        .iter()
+        .filter(|delta| delta_region(after, delta) != delta_region(current, delta))
+        .map(|delta| format!("{} changed after recording", describe(delta)))
+        .collect();
+    if !conflicts.is_empty() {
+        return Err(conflicts);
+    }
+
+    Ok(deltas
+        .iter()
+        .rev()
+        .fold(current.to_string(), |content, delta| {
+            invert_delta(&content, delta, before)
+        }))
+}
+
+fn describe(delta: &DeltaBlock) -> String {
+    match (&delta.kind, &delta.scenario_title) {
+        (_, Some(title)) => format!("scenario '{title}'"),
+        (DeltaKind::Description, _) => "feature description".to_string(),
+        (DeltaKind::Renamed, _) => "feature heading".to_string(),
+        _ => "Background".to_string(),
+    }
+}
+
+/// The text of the part of `content` a delta block changes, if present.
+fn delta_region(content: &str, delta: &DeltaBlock) -> Option<String> {
+    let span = region_span(content, delta)?;
+    Some(content[span].trim().to_string())
+}
+
+fn region_span(content: &str, delta: &DeltaBlock) -> Option<Range<usize>> {
+    let spec = parser::parse(content).ok()?.spec;
+    match delta.kind {
+        DeltaKind::New | DeltaKind::Changed | DeltaKind::Removed => {
+            let title = delta.scenario_title.as_deref()?;
+            record::find_scenario_span(content, title)
+        }
+        DeltaKind::Description => spec.description_span,
+        DeltaKind::Renamed => spec.heading_span,
+        _ => spec.background_span,
+    }
+}
+
+fn invert_delta(content: &str, delta: &DeltaBlock, before: &str) -> String {
+    let title = delta.scenario_title.as_deref();
+    let original = || delta_region(before, delta);
+
+    match (&delta.kind, title) {
+        (DeltaKind::New, Some(title)) => record::remove_scenario(content, title),
+        (DeltaKind::Changed, Some(title)) => match original() {
+            Some(text) => record::replace_scenario(content, title, &text),
+            None => content.to_string(),
+        },
+        (DeltaKind::Removed, Some(title)) => restore_scenario(content, title, before),
+        (DeltaKind::Description, _) => match original() {
+            Some(text) => record::replace_description(content, &text),
+            None => content.to_string(),
+        },
+        (DeltaKind::Renamed, _) => match original() {
+            Some(heading) => record::rename_feature(content, &heading),
+            None => content.to_string(),
+        },
+        (DeltaKind::BackgroundNew, _)
+        | (DeltaKind::BackgroundChanged, _)
+        | (DeltaKind::BackgroundRemoved, _) => {
+            match (region_span(content, delta), region_span(before, delta)) {
+                (Some(current), Some(original)) => format!(
+                    "{}{}{}",
+                    &content[..current.start],
+                    &before[original],
+                    &content[current.end..]
+                ),
+                _ => content.to_string(),
+            }
+        }
+        _ => content.to_string(),
+    }
+}
+
+/// Put a removed scenario back next to the scenario that preceded (or followed) it.
+fn restore_scenario(content: &str, title: &str, before: &str) -> String {
+    let Ok(parsed) = parser::parse(before) else {
+        return content.to_string();
+    };
+    let scenarios = parsed.spec.scenarios;
+    let Some(index) = scenarios.iter().position(|s| s.name == title) else {
+        return content.to_string();
+    };
+    let text = before[scenarios[index].span.clone()].trim_end();
+
+    let present = |name: &str| record::find_scenario_span(content, name).is_some();
+    let placement = scenarios[..index]
+        .iter()
+        .rev()
+        .find(|s| present(&s.name))
+        .map(|s| Placement::After(&s.name))
+        .or_else(|| {
+            scenarios[index + 1..]
+                .iter()
+                .find(|s| present(&s.name))
+                .map(|s| Placement::Before(&s.name))
+        });
+
+    record::insert_scenario(content, text, placement)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::record::{RecordError, RecordOptions, RecordOutcome, record_plan_with};
+    use tempfile::TempDir;
+
+    /// Record past the verification gate
+    fn record_unchecked(specs: &Path, plan_name: &str) -> Result<RecordOutcome, RecordError> {
+        let options = RecordOptions {
+            force: true,
+            ..Default::default()
+        };
+        record_plan_with(specs, plan_name, &options)
+    }
+
+    const AUTH_SPEC: &str = r#"# Feature: Auth
+
+Users authenticate.
+
+## Background
+
+* Accounts exist.
+
+## Scenarios
+
+### Scenario: Login
+
+* *GIVEN* a user
+* *WHEN* they log in
+* *THEN* the system SHALL grant access
+
+### Scenario: Logout
+
+* *GIVEN* a session
+* *WHEN* they log out
+* *THEN* the system SHALL end the session
+"#;
+
+    const DELTA: &str = r#"## Scenarios
+
+<!-- DELTA:CHANGED -->
+### Scenario: Login
+
+* *GIVEN* a user
+* *WHEN* they log in
+* *THEN* the system SHALL grant access within 1s
+<!-- /DELTA:CHANGED -->
+
+<!-- DELTA:REMOVED -->
+### Scenario: Logout
+<!-- /DELTA:REMOVED -->
+
+<!-- DELTA:NEW -->
+### Scenario: Lockout
+
+* *GIVEN* three failed logins
+* *WHEN* they log in again
+* *THEN* the system SHALL refuse access
+<!-- /DELTA:NEW -->
+"#;
+
+    fn record_auth_plan(specs: &Path) -> String {
+        fs::create_dir_all(specs.join("auth/login")).unwrap();
+        fs::write(specs.join("auth/login/spec.md"), AUTH_SPEC).unwrap();
+        let plan_dir = specs.join("_plans/change-login/auth/login");
+        fs::create_dir_all(&plan_dir).unwrap();
+        fs::write(plan_dir.join("spec.md"), DELTA).unwrap();
+
+        let outcome = record_unchecked(specs, "change-login").unwrap();
+        outcome
+            .archive_dir
+            .file_name()
+            .unwrap()
+            .to_string_lossy()
+            .to_string()
+    }
+
+    #[test]
+    fn restores_pre_image_and_moves_plan_back() {
+        let tmp = TempDir::new().unwrap();
+        let specs = tmp.path();
+        let archive = record_auth_plan(specs);
+
+        let outcome = revert_plan(specs, &archive).unwrap();
+
+        assert_eq!(outcome.plan_name, "change-login");
+        assert_eq!(
+            fs::read_to_string(specs.join("auth/login/spec.md")).unwrap(),
+            AUTH_SPEC
+        );
+        let plan_dir = specs.join("_plans/change-login");
+        assert_eq!(
+            fs::read_to_string(plan_dir.join("auth/login/spec.md")).unwrap(),
+            DELTA
+        );
+        assert!(!plan_dir.join(RECORD_IMAGES_DIR).exists());
+        assert!(!specs.join("_recorded").join(&archive).exists());
+    }
+
+    #[test]
+    fn inverts_deltas_when_other_parts_changed_later() {
+        let tmp = TempDir::new().unwrap();
+        let specs = tmp.path();
+        let archive = record_auth_plan(specs);
+        let spec_path = specs.join("auth/login/spec.md");
+        let later = fs::read_to_string(&spec_path)
+            .unwrap()
+            .replace("Users authenticate.", "Users authenticate with a password.");
+        fs::write(&spec_path, later).unwrap();
+
+        revert_plan(specs, &archive).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(&spec_path).unwrap(),
+            AUTH_SPEC.replace("Users authenticate.", "Users authenticate with a password.")
+        );
+    }
+
+    #[test]
+    fn refuses_when_later_plan_touched_recorded_scenario() {
+        let tmp = TempDir::new().unwrap();
+        let specs = tmp.path();
+        let archive = record_auth_plan(specs);
+        let spec_path = specs.join("auth/login/spec.md");
+        let later = fs::read_to_string(&spec_path)
+            .unwrap()
+            .replace("SHALL refuse access", "SHALL lock the account");
+        fs::write(&spec_path, &later).unwrap();
+
+        let result = revert_plan(specs, &archive);
+
+        let Err(RevertError::LaterChanges { conflicts }) = result else {
+            panic!("expected LaterChanges, got {:?}", result);
+        };
+        assert_eq!(
+            conflicts,
+            vec!["auth/login: scenario 'Lockout' changed after recording"]
+        );
+        assert_eq!(fs::read_to_string(&spec_path).unwrap(), later);
+        assert!(specs.join("_recorded").join(&archive).exists());
+    }
+
+    #[test]
+    fn reverts_feature_operations() {
+        let tmp = TempDir::new().unwrap();
+        let specs = tmp.path();
+        for feature in ["auth/login", "auth/legacy"] {
+            fs::create_dir_all(specs.join(feature)).unwrap();
+            fs::write(specs.join(feature).join("spec.md"), AUTH_SPEC).unwrap();
+        }
+        let plan_dir = specs.join("_plans/reorganize");
+        fs::create_dir_all(&plan_dir).unwrap();
+        fs::write(
+            plan_dir.join("plan.md"),
+            "## Feature Operations\n\n* MOVE auth/login -> identity/login\n* DELETE auth/legacy\n",
+        )
+        .unwrap();
+        let archive = record_unchecked(specs, "reorganize").unwrap().archive_dir;
+
+        let outcome = revert_plan(specs, &archive.file_name().unwrap().to_string_lossy()).unwrap();
+
+        assert_eq!(
+            outcome.operations,
+            vec![FeatureOperation::Move {
+                from: crate::feature::FeaturePath::new("identity", "login"),
+                to: crate::feature::FeaturePath::new("auth", "login"),
+            }]
+        );
+        assert!(specs.join("auth/login/spec.md").exists());
+        assert!(specs.join("auth/legacy/spec.md").exists());
+        assert!(!specs.join("identity").exists());
+    }
+
+    #[test]
+    fn points_moved_references_back() {
+        let tmp = TempDir::new().unwrap();
+        let specs = tmp.path();
+        fs::create_dir_all(specs.join("auth/login")).unwrap();
+        fs::write(specs.join("auth/login/spec.md"), AUTH_SPEC).unwrap();
+        let session = AUTH_SPEC.replace("Users authenticate.", "Sessions use `auth/login`.");
+        fs::create_dir_all(specs.join("cli/session")).unwrap();
+        fs::write(specs.join("cli/session/spec.md"), &session).unwrap();
+        let plan_dir = specs.join("_plans/reorganize");
+        fs::create_dir_all(&plan_dir).unwrap();
+        fs::write(
+            plan_dir.join("plan.md"),
+            "## Feature Operations\n\n* MOVE auth/login -> identity/login\n",
+        )
+        .unwrap();
+        let archive = record_unchecked(specs, "reorganize").unwrap().archive_dir;
+        // A spec written after recording that refers to the new path
+        fs::create_dir_all(specs.join("cli/audit")).unwrap();
+        fs::write(
+            specs.join("cli/audit/spec.md"),
+            AUTH_SPEC.replace("Users authenticate.", "Audit reads `identity/login`."),
+        )
+        .unwrap();
+
+        revert_plan(specs, &archive.file_name().unwrap().to_string_lossy()).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(specs.join("cli/session/spec.md")).unwrap(),
+            session
+        );
+        assert_eq!(
+            fs::read_to_string(specs.join("cli/audit/spec.md")).unwrap(),
+            AUTH_SPEC.replace("Users authenticate.", "Audit reads `auth/login`.")
+        );
+    }
+
+    #[test]
+    fn refuses_malformed_archived_delta() {
+        let tmp = TempDir::new().unwrap();
+        let specs = tmp.path();
+        let archive = record_auth_plan(specs);
+        let recorded = fs::read_to_string(specs.join("auth/login/spec.md")).unwrap();
+        fs::write(
+            specs
+                .join("_recorded")
+                .join(&archive)
+                .join("auth/login/spec.md"),
+            "<!-- DELTA:CHANGED -->\n### Scenario: Login\n",
+        )
+        .unwrap();
+
+        let result = revert_plan(specs, &archive);
+
+        assert!(
+            matches!(&result, Err(RevertError::InvalidDelta { path, .. }) if path.ends_with("auth/login/spec.md")),
+            "{result:?}"
+        );
+        assert_eq!(
+            fs::read_to_string(specs.join("auth/login/spec.md")).unwrap(),
+            recorded
+        );
+    }
+
+    #[test]
+    fn undoes_restored_specs_when_operations_fail() {
+        let tmp = TempDir::new().unwrap();
+        let specs = tmp.path();
+        for feature in ["auth/login", "cli/session"] {
+            fs::create_dir_all(specs.join(feature)).unwrap();
+            fs::write(specs.join(feature).join("spec.md"), AUTH_SPEC).unwrap();
+        }
+        let plan_dir = specs.join("_plans/reorganize");
+        fs::create_dir_all(plan_dir.join("cli/session")).unwrap();
+        fs::write(plan_dir.join("cli/session/spec.md"), DELTA).unwrap();
+        fs::write(
+            plan_dir.join("plan.md"),
+            "## Feature Operations\n\n* MOVE auth/login -> identity/login\n",
+        )
+        .unwrap();
+        let archive = record_unchecked(specs, "reorganize").unwrap().archive_dir;
+        let recorded = fs::read_to_string(specs.join("cli/session/spec.md")).unwrap();
+        // A file where the old domain directory has to be recreated blocks the move back
+        fs::write(specs.join("auth"), "not a directory").unwrap();
+
+        let result = revert_plan(specs, &archive.file_name().unwrap().to_string_lossy());
+
+        assert!(
+            matches!(result, Err(RevertError::FeatureOpsError { .. })),
+            "{result:?}"
+        );
+        assert_eq!(
+            fs::read_to_string(specs.join("cli/session/spec.md")).unwrap(),
+            recorded
+        );
+        assert!(specs.join("identity/login/spec.md").exists());
+        assert!(archive.exists());
+    }
+
+    #[test]
+    fn reverts_suffixed_archive_to_its_plan_name() {
+        let tmp = TempDir::new().unwrap();
+        let specs = tmp.path();
+        let first = record_auth_plan(specs);
+        let recorded_spec = fs::read_to_string(specs.join("auth/login/spec.md")).unwrap();
+        let follow_up = specs.join("_plans/change-login/auth/login");
+        fs::create_dir_all(&follow_up).unwrap();
+        fs::write(
+            follow_up.join("spec.md"),
+            "<!-- DELTA:DESCRIPTION -->\nUsers sign in.\n<!-- /DELTA:DESCRIPTION -->\n",
+        )
+        .unwrap();
+        let second = record_unchecked(specs, "change-login").unwrap().archive_dir;
+        let second = second.file_name().unwrap().to_string_lossy().to_string();
+        assert_eq!(second, format!("{first}-2"));
+
+        let outcome = revert_plan(specs, &second).unwrap();
+
+        assert_eq!(outcome.plan_name, "change-login");
+        assert!(follow_up.join("spec.md").exists());
+        assert_eq!(
+            fs::read_to_string(specs.join("auth/login/spec.md")).unwrap(),
+            recorded_spec
+        );
+    }
+
+    #[test]
+    fn merges_partial_recording_back_into_active_plan() {
+        let tmp = TempDir::new().unwrap();
+        let specs = tmp.path();
+        for feature in ["auth/login", "auth/legacy"] {
+            fs::create_dir_all(specs.join(feature)).unwrap();
+            fs::write(specs.join(feature).join("spec.md"), AUTH_SPEC).unwrap();
+        }
+        let plan_dir = specs.join("_plans/change-login");
+        let plan_md = "# Plan\n\n## Feature Operations\n\n* DELETE auth/legacy\n* MOVE auth/login -> identity/login\n";
+        fs::create_dir_all(plan_dir.join("billing/invoices")).unwrap();
+        fs::write(plan_dir.join("plan.md"), plan_md).unwrap();
+        fs::write(plan_dir.join("billing/invoices/spec.md"), AUTH_SPEC).unwrap();
+        fs::create_dir_all(plan_dir.join("identity/login")).unwrap();
+        fs::write(plan_dir.join("identity/login/spec.md"), DELTA).unwrap();
+        let options = RecordOptions {
+            features: vec!["identity/login".to_string()],
+            force: true,
+            ..Default::default()
+        };
+        let archive = record_plan_with(specs, "change-login", &options)
+            .unwrap()
+            .archive_dir;
+        assert!(specs.join("identity/login/spec.md").exists());
+
+        let outcome = revert_plan(specs, &archive.file_name().unwrap().to_string_lossy()).unwrap();
+
+        assert_eq!(outcome.features, vec!["identity/login".to_string()]);
+        assert_eq!(
+            fs::read_to_string(specs.join("auth/login/spec.md")).unwrap(),
+            AUTH_SPEC
+        );
+        // The unrecorded DELETE stays in the plan and is not undone
+        assert!(specs.join("auth/legacy/spec.md").exists());
+        assert!(!specs.join("identity").exists());
+        assert_eq!(
+            fs::read_to_string(plan_dir.join("plan.md")).unwrap(),
+            plan_md
+        );
+        assert_eq!(
+            fs::read_to_string(plan_dir.join("identity/login/spec.md")).unwrap(),
+            DELTA
+        );
+        assert!(plan_dir.join("billing/invoices/spec.md").exists());
+        assert!(!archive.exists());
+    }
+
+    #[test]
+    fn removes_adrs_the_recording_promoted() {
+        let tmp = TempDir::new().unwrap();
+        let specs = tmp.path();
+        fs::create_dir_all(specs.join("auth/login")).unwrap();
+        fs::write(specs.join("auth/login/spec.md"), AUTH_SPEC).unwrap();
+        let plan_dir = specs.join("_plans/change-login");
+        fs::create_dir_all(plan_dir.join("auth/login")).unwrap();
+        fs::write(plan_dir.join("auth/login/spec.md"), DELTA).unwrap();
+        fs::write(
+            plan_dir.join(DECISION_LOG_FILE),
+            "# Decision Log: change-login\n\nDate: 2021-03-01\n\n## Design Decisions\n\n### [1] Lock out after three failures\n\n- **Decision:** Three strikes.\n- **Rationale:** Simple.\n- **Promotes to ADR:** yes\n",
+        )
+        .unwrap();
+        let options = RecordOptions {
+            promote_decisions: true,
+            force: true,
+            ..Default::default()
+        };
+        let archive = record_plan_with(specs, "change-login", &options)
+            .unwrap()
+            .archive_dir;
+        let manifest = fs::read_to_string(archive.join(ARCHIVE_MANIFEST_FILE)).unwrap();
+        assert!(
+            manifest.contains("## Promoted ADRs\n\n* ADR-001: Lock out after three failures\n")
+        );
+
+        let outcome = revert_plan(specs, &archive.file_name().unwrap().to_string_lossy()).unwrap();
+
+        assert_eq!(
+            outcome.removed_adrs,
+            vec!["ADR-001: Lock out after three failures"]
+        );
+        assert!(outcome.kept_adrs.is_empty());
+        assert_eq!(
+            fs::read_to_string(specs.join(DECISION_LOG_FILE)).unwrap(),
+            "# Architecture Decision Records\n"
+        );
+    }
+
+    #[test]
+    fn refuses_archive_without_record_images() {
+        let tmp = TempDir::new().unwrap();
+        fs::create_dir_all(tmp.path().join("_recorded/2024-01-01-old-plan")).unwrap();
+
+        let result = revert_plan(tmp.path(), "2024-01-01-old-plan");
+
+        assert!(matches!(result, Err(RevertError::NoRecordImages(_))));
+    }
+}
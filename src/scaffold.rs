@@ -0,0 +1,359 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::NaiveDate;
+use thiserror::Error;
+
+use crate::record::BASE_SNAPSHOT_FILE;
+
+/// Verbs a plan name may start with, from the workflow's naming conventions
+pub const PLAN_VERBS: [&str; 5] = ["add", "change", "remove", "refactor", "fix"];
+
+/// An empty slot for new scenarios; recording leaves it out until it is filled in
+const EMPTY_NEW_DELTA: &str = "<!-- DELTA:NEW -->\n<!-- /DELTA:NEW -->\n";
+
+#[derive(Debug, Error, PartialEq)]
+pub enum PlanNameError {
+    #[error("Plan name '{0}' must be lowercase letters and digits joined by single hyphens")]
+    InvalidCharacters(String),
+
+    #[error(
+        "Plan name '{name}' must start with one of add, change, remove, refactor, fix (found '{verb}')"
+    )]
+    UnknownVerb { name: String, verb: String },
+
+    #[error("Plan name '{0}' needs a feature scope after the verb, e.g. add-user-auth")]
+    MissingScope(String),
+}
+
+#[derive(Debug, Error)]
+pub enum ScaffoldError {
+    #[error(transparent)]
+    InvalidName(#[from] PlanNameError),
+
+    #[error("Plan already exists: {0}")]
+    PlanExists(String),
+
+    #[error("Feature '{0}' must be written <domain>/<feature>")]
+    InvalidFeature(String),
+
+    #[error("Failed to read file: {path}")]
+    FileReadError { path: String },
+
+    #[error("Failed to write file: {path}")]
+    FileWriteError { path: String },
+}
+
+/// A delta spec `new_plan` created.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScaffoldedFeature {
+    /// `domain/feature`
+    pub feature: String,
+    /// No permanent spec exists yet, so the delta is a skeleton
+    pub is_new: bool,
+}
+
+#[derive(Debug)]
+pub struct NewPlan {
+    pub plan_dir: PathBuf,
+    pub features: Vec<ScaffoldedFeature>,
+}
+
+/// Check `<verb>-<feature-scope>[-<qualifier>]`: lowercase kebab-case starting with one of
+/// [`PLAN_VERBS`] and followed by at least one scope word.
+pub fn validate_plan_name(name: &str) -> Result<(), PlanNameError> {
+    let words: Vec<&str> = name.split('-').collect();
+    let well_formed = words.iter().all(|word| {
+        !word.is_empty()
+            && word
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+    });
+    if !well_formed {
+        return Err(PlanNameError::InvalidCharacters(name.to_string()));
+    }
+    if !PLAN_VERBS.contains(&words[0]) {
+        return Err(PlanNameError::UnknownVerb {
+            name: name.to_string(),
+            verb: words[0].to_string(),
+        });
+    }
+    if words.len() < 2 {
+        return Err(PlanNameError::MissingScope(name.to_string()));
+    }
+    Ok(())
+}
+
+/// Create `specs/_plans/<name>/` with `plan.md`, `decision-log.md` and one delta spec per
+/// feature. Deltas of existing features start as a copy of the permanent spec, with its
+/// `base.md` snapshot, and an empty `DELTA:NEW` block; new features get a skeleton.
+/// Nothing is written if the name or a feature is invalid or the plan exists, and a plan
+/// directory left half-built by a failed read or write is removed again.
+pub fn new_plan(
+    specs_base: &Path,
+    name: &str,
+    features: &[String],
+    date: NaiveDate,
+) -> Result<NewPlan, ScaffoldError> {
+    validate_plan_name(name)?;
+    if let Some(invalid) = features.iter().find(|feature| !is_feature_path(feature)) {
+        return Err(ScaffoldError::InvalidFeature(invalid.clone()));
+    }
+    let plan_dir = specs_base.join("_plans").join(name);
+    if plan_dir.exists() {
+        return Err(ScaffoldError::PlanExists(name.to_string()));
+    }
+
+    match write_plan(specs_base, &plan_dir, name, features, date) {
+        Ok(scaffolded) => Ok(NewPlan {
+            plan_dir,
+            features: scaffolded,
+        }),
+        Err(e) => {
+            let _ = fs::remove_dir_all(&plan_dir);
+            Err(e)
+        }
+    }
+}
+
+fn write_plan(
+    specs_base: &Path,
+    plan_dir: &Path,
+    name: &str,
+    features: &[String],
+    date: NaiveDate,
+) -> Result<Vec<ScaffoldedFeature>, ScaffoldError> {
+    let mut scaffolded = Vec::new();
+    for feature in features {
+        let permanent_path = specs_base.join(feature).join("spec.md");
+        let permanent = permanent_path
+            .exists()
+            .then(|| read_file(&permanent_path))
+            .transpose()?;
+        let delta_dir = plan_dir.join(feature);
+        create_dir(&delta_dir)?;
+
+        match &permanent {
+            Some(spec) => {
+                write_file(&delta_dir.join(BASE_SNAPSHOT_FILE), spec)?;
+                write_file(&delta_dir.join("spec.md"), &seeded_delta(spec))?;
+            }
+            None => write_file(&delta_dir.join("spec.md"), &new_feature_delta(feature))?,
+        }
+        scaffolded.push(ScaffoldedFeature {
+            feature: feature.clone(),
+            is_new: permanent.is_none(),
+        });
+    }
+
+    create_dir(plan_dir)?;
+    write_file(
+        &plan_dir.join("plan.md"),
+        &render_plan_md(name, &scaffolded),
+    )?;
+    write_file(
+        &plan_dir.join("decision-log.md"),
+        &render_decision_log(name, date),
+    )?;
+
+    Ok(scaffolded)
+}
+
+fn is_feature_path(feature: &str) -> bool {
+    let parts: Vec<&str> = feature.split('/').collect();
+    parts.len() == 2
+        && parts
+            .iter()
+            .all(|part| !part.is_empty() && !part.starts_with('.') && !part.starts_with('_'))
+}
+
+fn seeded_delta(permanent: &str) -> String {
+    format!("{}\n\n{}", permanent.trim_end(), EMPTY_NEW_DELTA)
+}
+
+/// `billing/invoice-export` -> `# Feature: Invoice Export` with empty sections
+fn new_feature_delta(feature: &str) -> String {
+    let slug = feature.rsplit('/').next().unwrap_or(feature);
+    let title: Vec<String> = slug
+        .split(['-', '_'])
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            chars.next().map_or_else(String::new, |first| {
+                first.to_uppercase().chain(chars).collect()
+            })
+        })
+        .collect();
+    format!(
+        "# Feature: {}\n\n<!-- Describe what the feature does and why. -->\n\n## Background\n\n## Scenarios\n\n{}",
+        title.join(" "),
+        EMPTY_NEW_DELTA
+    )
+}
+
+fn render_plan_md(name: &str, features: &[ScaffoldedFeature]) -> String {
+    let mut plan = format!(
+        "# Plan: {name}\n\nStatus: draft\n\n## Summary\n\n<!-- What this plan changes and why. -->\n\n## Features\n\n"
+    );
+    for feature in features {
+        let kind = if feature.is_new { "new" } else { "changed" };
+        plan.push_str(&format!("* {} ({})\n", feature.feature, kind));
+    }
+    if features.is_empty() {
+        plan.push_str("<!-- One `* <domain>/<feature>` bullet per feature the plan touches. -->\n");
+    }
+    plan.push_str(
+        "\n## Tasks\n\n<!-- One `- [ ]` line per task; tag complex work `[expert]`;\n     link scenarios as `[<domain>/<feature>: <Scenario>]`. -->\n",
+    );
+    plan
+}
+
+fn render_decision_log(name: &str, date: NaiveDate) -> String {
+    format!(
+        "# Decision Log: {name}\n\nDate: {}\n\n## Interview\n\n## Design Decisions\n\n## Review Findings\n\n<!-- Populated by speq-implement after code review. -->\n",
+        date.format("%Y-%m-%d")
+    )
+}
+
+fn create_dir(dir: &Path) -> Result<(), ScaffoldError> {
+    fs::create_dir_all(dir).map_err(|_| ScaffoldError::FileWriteError {
+        path: dir.display().to_string(),
+    })
+}
+
+fn read_file(path: &Path) -> Result<String, ScaffoldError> {
+    fs::read_to_string(path).map_err(|_| ScaffoldError::FileReadError {
+        path: path.display().to_string(),
+    })
+}
+
+fn write_file(path: &Path, content: &str) -> Result<(), ScaffoldError> {
+    fs::write(path, content).map_err(|_| ScaffoldError::FileWriteError {
+        path: path.display().to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validate::decision_log::validate_plan_log;
+    use tempfile::TempDir;
+
+    const LOGIN_SPEC: &str = "# Feature: Login\n\nUsers log in.\n\n## Background\n\n* Users have accounts\n\n## Scenarios\n\n### Scenario: Login works\n\n* *GIVEN* a user\n* *WHEN* they log in\n* *THEN* the system SHALL admit them\n";
+
+    fn date() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 5, 2).unwrap()
+    }
+
+    #[test]
+    fn accepts_names_following_the_convention() {
+        for name in [
+            "add-user-auth",
+            "fix-validation-edge-case",
+            "refactor-search",
+        ] {
+            assert_eq!(validate_plan_name(name), Ok(()), "{name}");
+        }
+    }
+
+    #[test]
+    fn rejects_names_breaking_the_convention() {
+        assert_eq!(
+            validate_plan_name("update-auth"),
+            Err(PlanNameError::UnknownVerb {
+                name: "update-auth".to_string(),
+                verb: "update".to_string()
+            })
+        );
+        assert_eq!(
+            validate_plan_name("add"),
+            Err(PlanNameError::MissingScope("add".to_string()))
+        );
+        for name in ["Add-auth", "add_auth", "add--auth", "add-auth-", ""] {
+            assert!(
+                matches!(
+                    validate_plan_name(name),
+                    Err(PlanNameError::InvalidCharacters(_))
+                ),
+                "{name}"
+            );
+        }
+    }
+
+    #[test]
+    fn scaffolds_plan_with_seeded_and_new_deltas() {
+        let tmp = TempDir::new().unwrap();
+        fs::create_dir_all(tmp.path().join("auth/login")).unwrap();
+        fs::write(tmp.path().join("auth/login/spec.md"), LOGIN_SPEC).unwrap();
+        let features = vec![
+            "auth/login".to_string(),
+            "billing/invoice-export".to_string(),
+        ];
+
+        let plan = new_plan(tmp.path(), "change-login", &features, date()).unwrap();
+
+        assert_eq!(
+            plan.features,
+            vec![
+                ScaffoldedFeature {
+                    feature: "auth/login".to_string(),
+                    is_new: false
+                },
+                ScaffoldedFeature {
+                    feature: "billing/invoice-export".to_string(),
+                    is_new: true
+                },
+            ]
+        );
+        let seeded = fs::read_to_string(plan.plan_dir.join("auth/login/spec.md")).unwrap();
+        assert_eq!(seeded, format!("{LOGIN_SPEC}\n{EMPTY_NEW_DELTA}"));
+        assert_eq!(
+            fs::read_to_string(plan.plan_dir.join("auth/login/base.md")).unwrap(),
+            LOGIN_SPEC
+        );
+        let skeleton =
+            fs::read_to_string(plan.plan_dir.join("billing/invoice-export/spec.md")).unwrap();
+        assert!(skeleton.starts_with("# Feature: Invoice Export\n"));
+        assert!(
+            !plan
+                .plan_dir
+                .join("billing/invoice-export/base.md")
+                .exists()
+        );
+
+        let plan_md = fs::read_to_string(plan.plan_dir.join("plan.md")).unwrap();
+        assert!(plan_md.contains("* auth/login (changed)\n* billing/invoice-export (new)\n"));
+        let log = fs::read_to_string(plan.plan_dir.join("decision-log.md")).unwrap();
+        assert!(log.contains("Date: 2026-05-02\n"));
+        let result = validate_plan_log(&log, "change-login");
+        assert!(result.is_success(), "errors: {:?}", result.errors);
+    }
+
+    #[test]
+    fn refuses_existing_plan_and_bad_features_without_writing() {
+        let tmp = TempDir::new().unwrap();
+        fs::create_dir_all(tmp.path().join("_plans/add-auth")).unwrap();
+
+        let exists = new_plan(tmp.path(), "add-auth", &[], date());
+        let bad_feature = new_plan(tmp.path(), "add-billing", &["billing".to_string()], date());
+
+        assert!(matches!(exists, Err(ScaffoldError::PlanExists(_))));
+        assert!(matches!(bad_feature, Err(ScaffoldError::InvalidFeature(f)) if f == "billing"));
+        assert!(!tmp.path().join("_plans/add-billing").exists());
+    }
+
+    #[test]
+    fn removes_half_built_plan_on_failure() {
+        let tmp = TempDir::new().unwrap();
+        fs::create_dir_all(tmp.path().join("auth/login")).unwrap();
+        fs::write(tmp.path().join("auth/login/spec.md"), LOGIN_SPEC).unwrap();
+        // A directory where the permanent spec should be makes reading it fail
+        fs::create_dir_all(tmp.path().join("billing/export/spec.md")).unwrap();
+        let features = vec!["auth/login".to_string(), "billing/export".to_string()];
+
+        let result = new_plan(tmp.path(), "change-login", &features, date());
+
+        assert!(matches!(result, Err(ScaffoldError::FileReadError { .. })));
+        assert!(!tmp.path().join("_plans/change-login").exists());
+    }
+}
@@ -30,6 +30,31 @@ pub struct SearchResult {
     pub score: f32,
 }
 
+/// A permanent ADR with its embedding
+#[derive(Serialize, Deserialize, Debug)]
+pub struct IndexedDecision {
+    pub id: String,
+    pub title: String,
+    pub status: String,
+    pub content: String,
+    pub embedding: Vec<f32>,
+}
+
+/// The decision index stored on disk next to the scenario index
+#[derive(Serialize, Deserialize, Debug)]
+pub struct DecisionIndex {
+    pub decisions: Vec<IndexedDecision>,
+}
+
+/// Decision search result with similarity score
+#[derive(Debug)]
+pub struct DecisionSearchResult {
+    pub id: String,
+    pub title: String,
+    pub status: String,
+    pub score: f32,
+}
+
 /// Get the cache directory path for speq
 pub fn get_cache_path() -> PathBuf {
     let local_cache = PathBuf::from(".cache").join("speq");
@@ -83,6 +108,15 @@ pub fn get_index_path() -> PathBuf {
     cache.join("indexes").join(format!("{}.idx", slug))
 }
 
+/// Get the decision index file path for the current project
+pub fn get_decision_index_path() -> PathBuf {
+    let cache = get_cache_path();
+    let slug = get_project_slug();
+    cache
+        .join("indexes")
+        .join(format!("{}.decisions.idx", slug))
+}
+
 /// Get the model directory path for speq's embedding model files
 pub fn get_model_dir() -> PathBuf {
     get_cache_path().join("models")
@@ -228,6 +262,105 @@ pub fn search_specs(query: &str, limit: usize) -> Result<Vec<SearchResult>, Stri
     Ok(results)
 }
 
+/// Build the decision index from the ADRs in `<base>/decision-log.md`
+pub fn index_decisions(base: &Path) -> Result<usize, String> {
+    let log_path = base.join(crate::decision_log::DECISION_LOG_FILE);
+    let content = std::fs::read_to_string(&log_path)
+        .map_err(|e| format!("Failed to read {}: {}", log_path.display(), e))?;
+    let log = crate::decision_log::PermanentLog::parse(&content);
+
+    let texts: Vec<String> = log
+        .adrs
+        .iter()
+        .map(|adr| {
+            let sections: Vec<&str> = adr.sections.iter().map(|s| s.body.as_str()).collect();
+            format!("{}\n{}", adr.title, sections.join("\n"))
+        })
+        .collect();
+
+    let embeddings = if texts.is_empty() {
+        Vec::new()
+    } else {
+        let embedder = crate::embedding::Embedder::load_model()?;
+        embedder.embed(&texts.iter().map(String::as_str).collect::<Vec<_>>())?
+    };
+
+    let decisions: Vec<IndexedDecision> = log
+        .adrs
+        .iter()
+        .zip(texts)
+        .zip(embeddings)
+        .map(|((adr, content), embedding)| IndexedDecision {
+            id: adr.id(),
+            title: adr.title.clone(),
+            status: adr.status.clone().unwrap_or_default(),
+            content,
+            embedding,
+        })
+        .collect();
+
+    let count = decisions.len();
+    let index_path = get_decision_index_path();
+    if let Some(parent) = index_path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create index directory: {}", e))?;
+    }
+
+    let encoded = postcard::to_allocvec(&DecisionIndex { decisions })
+        .map_err(|e| format!("Failed to serialize: {}", e))?;
+    std::fs::write(&index_path, encoded).map_err(|e| format!("Failed to write index: {}", e))?;
+
+    Ok(count)
+}
+
+/// Search the permanent ADRs, rebuilding the decision index when the log is newer
+pub fn search_decisions(query: &str, limit: usize) -> Result<Vec<DecisionSearchResult>, String> {
+    let base = Path::new("specs");
+    let index_path = get_decision_index_path();
+    let log_path = base.join(crate::decision_log::DECISION_LOG_FILE);
+    if !log_path.exists() {
+        return Err(format!("{} not found", log_path.display()));
+    }
+
+    let modified = |path: &Path| std::fs::metadata(path).and_then(|m| m.modified()).ok();
+    if modified(&index_path) < modified(&log_path) {
+        index_decisions(base)?;
+    }
+
+    let data = std::fs::read(&index_path).map_err(|e| format!("Failed to read index: {}", e))?;
+    let index: DecisionIndex =
+        postcard::from_bytes(&data).map_err(|e| format!("Failed to deserialize index: {}", e))?;
+
+    if index.decisions.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let embedder = crate::embedding::Embedder::load_model()?;
+    let query_embeddings = embedder.embed(&[query])?;
+    let query_embedding = query_embeddings
+        .first()
+        .ok_or_else(|| "Embedding produced no vector".to_string())?;
+
+    let mut scored: Vec<(f32, &IndexedDecision)> = index
+        .decisions
+        .iter()
+        .map(|d| (cosine_similarity(query_embedding, &d.embedding), d))
+        .collect();
+    scored.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+
+    Ok(scored
+        .into_iter()
+        .take(limit)
+        .filter(|(score, _)| *score > 0.0)
+        .map(|(score, d)| DecisionSearchResult {
+            id: d.id.clone(),
+            title: d.title.clone(),
+            status: d.status.clone(),
+            score,
+        })
+        .collect())
+}
+
 /// Calculate cosine similarity between two vectors
 fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
     if a.len() != b.len() {
@@ -1,5 +1,14 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::NaiveDate;
 use thiserror::Error;
 
+use crate::decision_log::{
+    Adr, DECISION_LOG_FILE, PermanentLog, parse_field, parse_plan_decisions, strip_decision_number,
+};
+use crate::record;
+
 #[derive(Debug)]
 pub struct DecisionLogValidationResult {
     pub errors: Vec<DecisionLogError>,
@@ -41,11 +50,37 @@ pub enum DecisionLogError {
     #[error(
         "H1 heading does not match plan name: expected '# Decision Log: {expected}', found '{found}'"
     )]
-    PlanLogH1Mismatch { expected: String, found: String },
+    PlanLogH1Mismatch {
+        line: usize,
+        expected: String,
+        found: String,
+    },
 
     #[error("Missing 'Date:' line")]
     PlanLogMissingDate,
 
+    #[error("Invalid date '{value}' (expected YYYY-MM-DD)")]
+    PlanLogInvalidDate { line: usize, value: String },
+
+    #[error("Design decision heading '### {title}' must be '### [N] <title>'")]
+    PlanLogDecisionMissingNumber { line: usize, title: String },
+
+    #[error("'**{field}:**' is not under a '### [N] <title>' design decision")]
+    PlanLogFieldOutsideDecision { line: usize, field: String },
+
+    #[error("Design decision '{title}' is missing '- **{field}:**'")]
+    PlanLogDecisionMissingField {
+        line: usize,
+        title: String,
+        field: String,
+    },
+
+    #[error("Interview question has no '**A:**' answer")]
+    PlanLogUnansweredQuestion { line: usize },
+
+    #[error("Interview answer has no preceding '**Q:**' question")]
+    PlanLogAnswerWithoutQuestion { line: usize },
+
     #[error(
         "No valid sections found (expected ## Interview, ## Design Decisions, or ## Review Findings)"
     )]
@@ -65,22 +100,121 @@ pub enum DecisionLogError {
 
     #[error("ADR-{number:03} has invalid status '{status}'")]
     PermanentLogInvalidStatus { number: u32, status: String },
+
+    #[error("ADR-{number:03} is superseded by itself")]
+    PermanentLogSelfSupersession { number: u32 },
+
+    #[error("ADR-{number:03} is superseded by ADR-{by:03}, which does not exist")]
+    PermanentLogDanglingSupersession { number: u32, by: u32 },
+
+    #[error(
+        "ADR-{number:03} is superseded by earlier ADR-{by:03}; supersession must point forward"
+    )]
+    PermanentLogBackwardSupersession { number: u32, by: u32 },
+
+    #[error("Supersession cycle: {}", format_cycle(.cycle))]
+    PermanentLogSupersessionCycle { cycle: Vec<u32> },
+
+    #[error("ADR-{number:03} matches no decision marked 'Promotes to ADR: yes' in plan '{plan}'")]
+    PermanentLogUnpromotedAdr { number: u32, plan: String },
+
+    #[error(
+        "Decision '{title}' in recorded plan '{plan}' is marked 'Promotes to ADR: yes' but has no ADR"
+    )]
+    RecordedDecisionMissingAdr { plan: String, title: String },
+}
+
+impl DecisionLogError {
+    /// 1-based line in the decision log the error points at, if any
+    pub fn line(&self) -> Option<usize> {
+        match self {
+            DecisionLogError::PlanLogH1Mismatch { line, .. }
+            | DecisionLogError::PlanLogInvalidDate { line, .. }
+            | DecisionLogError::PlanLogDecisionMissingNumber { line, .. }
+            | DecisionLogError::PlanLogFieldOutsideDecision { line, .. }
+            | DecisionLogError::PlanLogDecisionMissingField { line, .. }
+            | DecisionLogError::PlanLogUnansweredQuestion { line }
+            | DecisionLogError::PlanLogAnswerWithoutQuestion { line } => Some(*line),
+            _ => None,
+        }
+    }
+}
+
+fn format_cycle(cycle: &[u32]) -> String {
+    cycle
+        .iter()
+        .chain(cycle.first())
+        .map(|number| format!("ADR-{number:03}"))
+        .collect::<Vec<_>>()
+        .join(" -> ")
 }
 
 #[derive(Debug, PartialEq)]
 pub enum DecisionLogWarning {
-    PlanLogInvalidPromotesValue { value: String },
+    PlanLogInvalidPromotesValue {
+        line: usize,
+        value: String,
+    },
+    /// A second design decision with the same title
+    PlanLogDuplicateDecision {
+        line: usize,
+        first_line: usize,
+        title: String,
+    },
+    /// The superseding ADR never refers to the ADR it replaces
+    PermanentLogSupersessionNotMentioned {
+        number: u32,
+        by: u32,
+    },
+    /// The ADR's plan is neither archived nor active, e.g. because `_recorded` is not
+    /// checked in, so it cannot be cross-checked
+    PermanentLogUnknownPlan {
+        number: u32,
+        plan: String,
+    },
 }
 
 impl std::fmt::Display for DecisionLogWarning {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            DecisionLogWarning::PlanLogInvalidPromotesValue { value } => {
+            DecisionLogWarning::PlanLogInvalidPromotesValue { value, .. } => {
                 write!(
                     f,
                     "Invalid 'Promotes to ADR' value '{value}' (expected 'yes' or 'no')"
                 )
             }
+            DecisionLogWarning::PlanLogDuplicateDecision {
+                first_line, title, ..
+            } => {
+                write!(
+                    f,
+                    "Design decision '{title}' duplicates the one on line {first_line}"
+                )
+            }
+            DecisionLogWarning::PermanentLogSupersessionNotMentioned { number, by } => {
+                write!(
+                    f,
+                    "ADR-{by:03} supersedes ADR-{number:03} but does not mention ADR-{number:03}"
+                )
+            }
+            DecisionLogWarning::PermanentLogUnknownPlan { number, plan } => {
+                write!(
+                    f,
+                    "ADR-{number:03} names plan '{plan}', which is not in specs/_recorded/ or specs/_plans/; it was not cross-checked"
+                )
+            }
+        }
+    }
+}
+
+impl DecisionLogWarning {
+    /// 1-based line in the decision log the warning points at, if any
+    pub fn line(&self) -> Option<usize> {
+        match self {
+            DecisionLogWarning::PlanLogInvalidPromotesValue { line, .. }
+            | DecisionLogWarning::PlanLogDuplicateDecision { line, .. } => Some(*line),
+            DecisionLogWarning::PermanentLogSupersessionNotMentioned { .. }
+            | DecisionLogWarning::PermanentLogUnknownPlan { .. } => None,
         }
     }
 }
@@ -90,6 +224,16 @@ const VALID_PLAN_LOG_SECTIONS: &[&str] =
 
 const PROMOTES_TO_ADR_PREFIX: &str = "Promotes to ADR:";
 
+/// Fields every `### [N] <title>` entry under `## Design Decisions` must have
+const DECISION_FIELDS: [&str; 4] = ["Decision", "Alternatives", "Rationale", "Promotes to ADR"];
+
+/// A design decision entry and the fields found under it so far
+struct DecisionEntry {
+    line: usize,
+    title: String,
+    fields: Vec<String>,
+}
+
 pub fn validate_plan_log(content: &str, plan_name: &str) -> DecisionLogValidationResult {
     let mut result = DecisionLogValidationResult::new();
     let expected_h1 = format!("# Decision Log: {plan_name}");
@@ -97,38 +241,67 @@ pub fn validate_plan_log(content: &str, plan_name: &str) -> DecisionLogValidatio
     let mut h1_found = false;
     let mut date_found = false;
     let mut section_found = false;
+    let mut section = "";
+    let mut entries: Vec<DecisionEntry> = Vec::new();
+    let mut in_entry = false;
+    let mut open_question: Option<usize> = None;
 
-    for raw_line in content.lines() {
+    for (index, raw_line) in content.lines().enumerate() {
+        let line_number = index + 1;
         let line = raw_line.trim_end();
+        let trimmed = line.trim();
 
         if !h1_found && is_h1_line(line) {
             h1_found = true;
-            if line.trim() != expected_h1 {
+            if trimmed != expected_h1 {
                 result.add_error(DecisionLogError::PlanLogH1Mismatch {
+                    line: line_number,
                     expected: expected_h1.clone(),
-                    found: line.trim().to_string(),
+                    found: trimmed.to_string(),
                 });
             }
             continue;
         }
 
-        if !date_found && line.trim_start().starts_with("Date:") {
+        if !date_found && let Some(value) = trimmed.strip_prefix("Date:") {
             date_found = true;
+            if NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d").is_err() {
+                result.add_error(DecisionLogError::PlanLogInvalidDate {
+                    line: line_number,
+                    value: value.trim().to_string(),
+                });
+            }
         }
 
-        if is_plan_log_section(line) {
-            section_found = true;
+        if trimmed.starts_with("## ") {
+            close_question(open_question.take(), &mut result);
+            section_found |= is_plan_log_section(line);
+            section = trimmed;
+            in_entry = false;
+        } else if section == "## Design Decisions" {
+            check_decision_line(
+                trimmed,
+                line_number,
+                &mut entries,
+                &mut in_entry,
+                &mut result,
+            );
+        } else if section == "## Interview" {
+            check_interview_line(trimmed, line_number, &mut open_question, &mut result);
         }
 
         if let Some(value) = extract_promotes_value(line) {
             let normalized = value.trim().to_lowercase();
             if normalized != "yes" && normalized != "no" {
                 result.add_warning(DecisionLogWarning::PlanLogInvalidPromotesValue {
+                    line: line_number,
                     value: value.trim().to_string(),
                 });
             }
         }
     }
+    close_question(open_question, &mut result);
+    check_decision_entries(&entries, &mut result);
 
     if !h1_found {
         result.add_error(DecisionLogError::PlanLogMissingH1 {
@@ -147,6 +320,94 @@ pub fn validate_plan_log(content: &str, plan_name: &str) -> DecisionLogValidatio
     result
 }
 
+/// Track `### [N] <title>` entries and their `- **Field:**` / `* **Field:**` bullets.
+fn check_decision_line(
+    line: &str,
+    line_number: usize,
+    entries: &mut Vec<DecisionEntry>,
+    in_entry: &mut bool,
+    result: &mut DecisionLogValidationResult,
+) {
+    if let Some(title) = line.strip_prefix("### ") {
+        if strip_decision_number(title) == title.trim() {
+            result.add_error(DecisionLogError::PlanLogDecisionMissingNumber {
+                line: line_number,
+                title: title.trim().to_string(),
+            });
+        }
+        entries.push(DecisionEntry {
+            line: line_number,
+            title: strip_decision_number(title).to_string(),
+            fields: Vec::new(),
+        });
+        *in_entry = true;
+        return;
+    }
+
+    let Some(field) = parse_field(line)
+        .map(|(name, _)| name)
+        .filter(|name| DECISION_FIELDS.contains(name))
+    else {
+        return;
+    };
+    match entries.last_mut() {
+        Some(entry) if *in_entry => entry.fields.push(field.to_string()),
+        _ => result.add_error(DecisionLogError::PlanLogFieldOutsideDecision {
+            line: line_number,
+            field: field.to_string(),
+        }),
+    }
+}
+
+/// Pair each `**Q:**` with the `**A:**` that follows it.
+fn check_interview_line(
+    line: &str,
+    line_number: usize,
+    open_question: &mut Option<usize>,
+    result: &mut DecisionLogValidationResult,
+) {
+    let line = line
+        .strip_prefix("- ")
+        .or_else(|| line.strip_prefix("* "))
+        .unwrap_or(line);
+    if line.starts_with("**Q:**") {
+        close_question(open_question.replace(line_number), result);
+    } else if line.starts_with("**A:**") && open_question.take().is_none() {
+        result.add_error(DecisionLogError::PlanLogAnswerWithoutQuestion { line: line_number });
+    }
+}
+
+fn close_question(open_question: Option<usize>, result: &mut DecisionLogValidationResult) {
+    if let Some(line) = open_question {
+        result.add_error(DecisionLogError::PlanLogUnansweredQuestion { line });
+    }
+}
+
+fn check_decision_entries(entries: &[DecisionEntry], result: &mut DecisionLogValidationResult) {
+    for (index, entry) in entries.iter().enumerate() {
+        for field in DECISION_FIELDS {
+            if !entry.fields.iter().any(|found| found == field) {
+                result.add_error(DecisionLogError::PlanLogDecisionMissingField {
+                    line: entry.line,
+                    title: entry.title.clone(),
+                    field: field.to_string(),
+                });
+            }
+        }
+
+        if let Some(first) = entries[..index]
+            .iter()
+            .find(|earlier| earlier.title.eq_ignore_ascii_case(&entry.title))
+        {
+            result.add_warning(DecisionLogWarning::PlanLogDuplicateDecision {
+                line: entry.line,
+                first_line: first.line,
+                title: entry.title.clone(),
+            });
+        }
+    }
+}
+
 pub fn validate_permanent_log(content: &str) -> DecisionLogValidationResult {
     let mut result = DecisionLogValidationResult::new();
     let expected_h1 = "# Architecture Decision Records";
@@ -166,59 +427,152 @@ pub fn validate_permanent_log(content: &str) -> DecisionLogValidationResult {
         result.add_error(DecisionLogError::PermanentLogMissingH1);
     }
 
-    let blocks = collect_adr_blocks(content);
+    let log = PermanentLog::parse(content);
 
-    validate_adr_numbering(&blocks, &mut result);
+    validate_adr_numbering(&log.adrs, &mut result);
 
-    for block in &blocks {
-        validate_adr_block(block, &mut result);
+    for adr in &log.adrs {
+        validate_adr(adr, &mut result);
     }
 
+    validate_supersession(&log, &mut result);
+
     result
 }
 
-struct AdrBlock<'a> {
-    number: u32,
-    lines: Vec<&'a str>,
+/// Check every `Superseded by ADR-NNN` link and the graph they form.
+fn validate_supersession(log: &PermanentLog, result: &mut DecisionLogValidationResult) {
+    for adr in &log.adrs {
+        let number = adr.number;
+        let Some(by) = adr.superseded_by() else {
+            continue;
+        };
+
+        if by == number {
+            result.add_error(DecisionLogError::PermanentLogSelfSupersession { number });
+            continue;
+        }
+        let Some(successor) = log.get(by) else {
+            result.add_error(DecisionLogError::PermanentLogDanglingSupersession { number, by });
+            continue;
+        };
+        if by < number {
+            result.add_error(DecisionLogError::PermanentLogBackwardSupersession { number, by });
+        }
+        if !successor.mentions(&adr.id()) {
+            result.add_warning(DecisionLogWarning::PermanentLogSupersessionNotMentioned {
+                number,
+                by,
+            });
+        }
+    }
+
+    for cycle in log.supersession_cycles() {
+        result.add_error(DecisionLogError::PermanentLogSupersessionCycle { cycle });
+    }
 }
 
-fn collect_adr_blocks(content: &str) -> Vec<AdrBlock<'_>> {
-    let mut blocks: Vec<AdrBlock<'_>> = Vec::new();
-    let mut current: Option<AdrBlock<'_>> = None;
+/// Resolve each ADR's `**Plan:**` against `specs/_recorded/` and `specs/_plans/`, and each
+/// recorded plan's `Promotes to ADR: yes` decisions against the ADRs.
+pub fn cross_check_plans(specs_base: &Path, content: &str) -> DecisionLogValidationResult {
+    let log = PermanentLog::parse(content);
+    let recorded = recorded_plan_dirs(specs_base);
+    let mut result = DecisionLogValidationResult::new();
 
-    for raw_line in content.lines() {
-        let trimmed = raw_line.trim_end();
-        if let Some(number) = parse_adr_heading(trimmed) {
-            if let Some(block) = current.take() {
-                blocks.push(block);
-            }
-            current = Some(AdrBlock {
-                number,
-                lines: vec![trimmed],
+    for adr in &log.adrs {
+        // A missing Plan field is already reported by validate_permanent_log
+        let Some(plan) = adr.plan.as_deref() else {
+            continue;
+        };
+        let mut dirs: Vec<&Path> = recorded
+            .iter()
+            .filter(|(name, _)| name == plan)
+            .map(|(_, dir)| dir.as_path())
+            .collect();
+        let active = specs_base.join("_plans").join(plan);
+        if active.is_dir() {
+            dirs.push(&active);
+        }
+
+        if dirs.is_empty() {
+            result.add_warning(DecisionLogWarning::PermanentLogUnknownPlan {
+                number: adr.number,
+                plan: plan.to_string(),
+            });
+        } else if !dirs.iter().any(|dir| {
+            promoted_titles(dir)
+                .iter()
+                .any(|title| same_title(title, &adr.title))
+        }) {
+            result.add_error(DecisionLogError::PermanentLogUnpromotedAdr {
+                number: adr.number,
+                plan: plan.to_string(),
             });
-        } else if let Some(block) = current.as_mut() {
-            block.lines.push(trimmed);
         }
     }
 
-    if let Some(block) = current.take() {
-        blocks.push(block);
+    for (plan, dir) in &recorded {
+        for title in promoted_titles(dir) {
+            let has_adr = log.adrs.iter().any(|adr| {
+                same_title(&adr.title, &title) && adr.plan.as_deref() == Some(plan.as_str())
+            });
+            let error = DecisionLogError::RecordedDecisionMissingAdr {
+                plan: plan.clone(),
+                title,
+            };
+            if !has_adr && !result.errors.contains(&error) {
+                result.add_error(error);
+            }
+        }
     }
 
-    blocks
+    result
 }
 
-fn validate_adr_numbering(blocks: &[AdrBlock<'_>], result: &mut DecisionLogValidationResult) {
-    if blocks.is_empty() {
+/// ADRs may be retitled in case or whitespace after promotion.
+fn same_title(a: &str, b: &str) -> bool {
+    a.trim().to_lowercase() == b.trim().to_lowercase()
+}
+
+/// Every archive under `specs/_recorded/` with the plan it holds, in name order.
+fn recorded_plan_dirs(specs_base: &Path) -> Vec<(String, PathBuf)> {
+    let Ok(entries) = fs::read_dir(specs_base.join("_recorded")) else {
+        return Vec::new();
+    };
+    let mut dirs: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_dir())
+        .collect();
+    dirs.sort();
+    dirs.into_iter()
+        .map(|dir| (record::recorded_plan_name(&dir), dir))
+        .collect()
+}
+
+/// Titles of the decisions a plan's decision log marks `Promotes to ADR: yes`.
+fn promoted_titles(plan_dir: &Path) -> Vec<String> {
+    fs::read_to_string(plan_dir.join(DECISION_LOG_FILE))
+        .map(|content| {
+            parse_plan_decisions(&content)
+                .into_iter()
+                .filter(|decision| decision.promotes)
+                .map(|decision| decision.title)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn validate_adr_numbering(adrs: &[Adr], result: &mut DecisionLogValidationResult) {
+    if adrs.is_empty() {
         return;
     }
 
-    let first = blocks[0].number;
+    let first = adrs[0].number;
     if first != 1 {
         result.add_error(DecisionLogError::PermanentLogNotStartAt001 { first });
     }
 
-    for window in blocks.windows(2) {
+    for window in adrs.windows(2) {
         let prev = window[0].number;
         let next = window[1].number;
         if next != prev + 1 {
@@ -227,71 +581,29 @@ fn validate_adr_numbering(blocks: &[AdrBlock<'_>], result: &mut DecisionLogValid
     }
 }
 
-fn validate_adr_block(block: &AdrBlock<'_>, result: &mut DecisionLogValidationResult) {
-    let mut has_date = false;
-    let mut has_plan = false;
-    let mut has_status = false;
-    let mut status_value: Option<String> = None;
-    let mut has_context_heading = false;
-    let mut has_decision_heading = false;
-
-    for line in &block.lines {
-        let trimmed = line.trim();
-        if extract_bold_field(trimmed, "Date").is_some() {
-            has_date = true;
-        }
-        if extract_bold_field(trimmed, "Plan").is_some() {
-            has_plan = true;
-        }
-        if let Some(value) = extract_bold_field(trimmed, "Status") {
-            has_status = true;
-            status_value = Some(value.trim().to_string());
-        }
-        if trimmed == "### Context" {
-            has_context_heading = true;
-        }
-        if trimmed == "### Decision" {
-            has_decision_heading = true;
+fn validate_adr(adr: &Adr, result: &mut DecisionLogValidationResult) {
+    let required = [
+        ("**Date:**", adr.date.is_some()),
+        ("**Plan:**", adr.plan.is_some()),
+        ("**Status:**", adr.status.is_some()),
+        ("### Context", adr.section("Context").is_some()),
+        ("### Decision", adr.section("Decision").is_some()),
+    ];
+    for (field, present) in required {
+        if !present {
+            result.add_error(DecisionLogError::PermanentLogMissingField {
+                number: adr.number,
+                field: field.to_string(),
+            });
         }
     }
 
-    if !has_date {
-        result.add_error(DecisionLogError::PermanentLogMissingField {
-            number: block.number,
-            field: "**Date:**".to_string(),
-        });
-    }
-    if !has_plan {
-        result.add_error(DecisionLogError::PermanentLogMissingField {
-            number: block.number,
-            field: "**Plan:**".to_string(),
-        });
-    }
-    if !has_status {
-        result.add_error(DecisionLogError::PermanentLogMissingField {
-            number: block.number,
-            field: "**Status:**".to_string(),
-        });
-    }
-    if !has_context_heading {
-        result.add_error(DecisionLogError::PermanentLogMissingField {
-            number: block.number,
-            field: "### Context".to_string(),
-        });
-    }
-    if !has_decision_heading {
-        result.add_error(DecisionLogError::PermanentLogMissingField {
-            number: block.number,
-            field: "### Decision".to_string(),
-        });
-    }
-
-    if let Some(status) = status_value
-        && !is_valid_status(&status)
+    if let Some(status) = &adr.status
+        && !is_valid_status(status)
     {
         result.add_error(DecisionLogError::PermanentLogInvalidStatus {
-            number: block.number,
-            status,
+            number: adr.number,
+            status: status.clone(),
         });
     }
 }
@@ -314,23 +626,6 @@ fn extract_promotes_value(line: &str) -> Option<&str> {
     Some(unbolded.trim())
 }
 
-fn parse_adr_heading(line: &str) -> Option<u32> {
-    let trimmed = line.trim_start();
-    let rest = trimmed.strip_prefix("## ADR-")?;
-    let colon_idx = rest.find(':')?;
-    let digits = &rest[..colon_idx];
-    if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
-        return None;
-    }
-    digits.parse::<u32>().ok()
-}
-
-fn extract_bold_field<'a>(line: &'a str, name: &str) -> Option<&'a str> {
-    let prefix = format!("**{name}:**");
-    let idx = line.find(&prefix)?;
-    Some(&line[idx + prefix.len()..])
-}
-
 fn is_valid_status(status: &str) -> bool {
     let trimmed = status.trim();
     if trimmed == "Accepted" || trimmed == "Deprecated" {
@@ -345,8 +640,19 @@ fn is_valid_status(status: &str) -> bool {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use tempfile::TempDir;
+
+    const VALID_PLAN_LOG: &str = "# Decision Log: my-plan\n\nDate: 2026-04-27\n\n## Interview\n\n**Q:** Parser?\n**A:** Lines.\n\n## Design Decisions\n\n### [1] Use line scanning\n\n- **Decision:** Use line scanning.\n- **Alternatives:** AST.\n- **Rationale:** Simpler.\n- **Promotes to ADR:** yes\n";
+
+    fn decision(title: &str, promotes: &str) -> String {
+        format!(
+            "### {title}\n\n- **Decision:** d\n- **Alternatives:** a\n- **Rationale:** r\n- **Promotes to ADR:** {promotes}\n\n"
+        )
+    }
 
-    const VALID_PLAN_LOG: &str = "# Decision Log: my-plan\n\nDate: 2026-04-27\n\n## Design Decisions\n\n- **Decision:** Use line scanning.\n- **Alternatives:** AST.\n- **Rationale:** Simpler.\n- **Promotes to ADR:** yes\n";
+    fn plan_log_with(sections: &str) -> String {
+        format!("# Decision Log: my-plan\n\nDate: 2026-04-27\n\n{sections}")
+    }
 
     const VALID_PERMANENT_LOG: &str = "# Architecture Decision Records\n\n## ADR-001: Use line-oriented state machine\n\n**Date:** 2026-04-27\n**Plan:** add-decision-log-validation\n**Status:** Accepted\n\n### Context\n\nFlat markdown.\n\n### Decision\n\nLine scanning.\n\n### Options Considered\n\n- AST.\n\n### Consequences\n\n- Simpler.\n";
 
@@ -372,7 +678,7 @@ mod tests {
         let result = validate_plan_log(content, "my-plan");
         assert!(result.errors.iter().any(|e| matches!(
             e,
-            DecisionLogError::PlanLogH1Mismatch { expected, found }
+            DecisionLogError::PlanLogH1Mismatch { expected, found, .. }
                 if expected == "# Decision Log: my-plan" && found == "# Decision Log: wrong-name"
         )));
     }
@@ -412,24 +718,143 @@ mod tests {
 
     #[test]
     fn plan_log_bad_promote_warns_not_errors() {
-        let content = "# Decision Log: my-plan\n\nDate: 2026-04-27\n\n## Design Decisions\n\n- **Promotes to ADR:** maybe\n";
-        let result = validate_plan_log(content, "my-plan");
+        let content = plan_log_with(&format!(
+            "## Design Decisions\n\n{}",
+            decision("[1] A", "maybe")
+        ));
+        let result = validate_plan_log(&content, "my-plan");
         assert!(result.is_success(), "errors: {:?}", result.errors);
         assert_eq!(result.warnings.len(), 1);
         assert!(matches!(
             &result.warnings[0],
-            DecisionLogWarning::PlanLogInvalidPromotesValue { value } if value == "maybe"
+            DecisionLogWarning::PlanLogInvalidPromotesValue { value, .. } if value == "maybe"
         ));
     }
 
     #[test]
     fn plan_log_promote_yes_or_no_case_insensitive() {
-        let content = "# Decision Log: my-plan\n\nDate: 2026-04-27\n\n## Design Decisions\n\n- **Promotes to ADR:** YES\n- **Promotes to ADR:** No\n";
-        let result = validate_plan_log(content, "my-plan");
+        let content = plan_log_with(&format!(
+            "## Design Decisions\n\n{}{}",
+            decision("[1] A", "YES"),
+            decision("[2] B", "No")
+        ));
+        let result = validate_plan_log(&content, "my-plan");
         assert!(result.is_success());
         assert!(result.warnings.is_empty());
     }
 
+    #[test]
+    fn plan_log_invalid_date_fails_with_line() {
+        let content = "# Decision Log: my-plan\n\nDate: 27.04.2026\n\n## Review Findings\n";
+        let result = validate_plan_log(content, "my-plan");
+        assert_eq!(
+            result.errors,
+            vec![DecisionLogError::PlanLogInvalidDate {
+                line: 3,
+                value: "27.04.2026".to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn plan_log_decision_missing_fields_fails() {
+        let content = plan_log_with(
+            "## Design Decisions\n\n### [1] Use line scanning\n\n- **Decision:** Lines.\n- **Promotes to ADR:** no\n",
+        );
+        let result = validate_plan_log(&content, "my-plan");
+        assert_eq!(
+            result.errors,
+            vec![
+                DecisionLogError::PlanLogDecisionMissingField {
+                    line: 7,
+                    title: "Use line scanning".to_string(),
+                    field: "Alternatives".to_string(),
+                },
+                DecisionLogError::PlanLogDecisionMissingField {
+                    line: 7,
+                    title: "Use line scanning".to_string(),
+                    field: "Rationale".to_string(),
+                },
+            ]
+        );
+        assert_eq!(result.errors[0].line(), Some(7));
+    }
+
+    #[test]
+    fn plan_log_decision_without_number_fails() {
+        let content = plan_log_with(&format!(
+            "## Design Decisions\n\n{}",
+            decision("Use line scanning", "no")
+        ));
+        let result = validate_plan_log(&content, "my-plan");
+        assert_eq!(
+            result.errors,
+            vec![DecisionLogError::PlanLogDecisionMissingNumber {
+                line: 7,
+                title: "Use line scanning".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn plan_log_decision_accepts_star_bullets() {
+        let content = plan_log_with(&format!(
+            "## Interview\n\n* **Q:** Why?\n* **A:** Because.\n\n## Design Decisions\n\n{}",
+            decision("[1] Use line scanning", "yes").replace("- **", "* **")
+        ));
+        let result = validate_plan_log(&content, "my-plan");
+        assert!(result.is_success(), "{:?}", result.errors);
+        assert!(parse_plan_decisions(&content)[0].promotes);
+    }
+
+    #[test]
+    fn plan_log_field_outside_decision_fails() {
+        let content = plan_log_with("## Design Decisions\n\n- **Decision:** Lines.\n");
+        let result = validate_plan_log(&content, "my-plan");
+        assert_eq!(
+            result.errors,
+            vec![DecisionLogError::PlanLogFieldOutsideDecision {
+                line: 7,
+                field: "Decision".to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn plan_log_interview_pairs_questions_with_answers() {
+        let content = plan_log_with(
+            "## Interview\n\n**Q:** One?\n**Q:** Two?\n**A:** Yes.\n**A:** Again.\n\n**Q:** Last?\n\n## Review Findings\n",
+        );
+        let result = validate_plan_log(&content, "my-plan");
+        assert_eq!(
+            result.errors,
+            vec![
+                DecisionLogError::PlanLogUnansweredQuestion { line: 7 },
+                DecisionLogError::PlanLogAnswerWithoutQuestion { line: 10 },
+                DecisionLogError::PlanLogUnansweredQuestion { line: 12 },
+            ]
+        );
+    }
+
+    #[test]
+    fn plan_log_duplicate_decision_warns() {
+        let content = plan_log_with(&format!(
+            "## Design Decisions\n\n{}{}",
+            decision("[1] Use line scanning", "no"),
+            decision("[2] use line scanning", "no")
+        ));
+        let result = validate_plan_log(&content, "my-plan");
+        assert!(result.is_success(), "errors: {:?}", result.errors);
+        assert_eq!(
+            result.warnings,
+            vec![DecisionLogWarning::PlanLogDuplicateDecision {
+                line: 14,
+                first_line: 7,
+                title: "use line scanning".to_string(),
+            }]
+        );
+    }
+
     #[test]
     fn valid_permanent_log_passes() {
         let result = validate_permanent_log(VALID_PERMANENT_LOG);
@@ -490,6 +915,13 @@ mod tests {
         )));
     }
 
+    #[test]
+    fn permanent_log_status_after_sections_passes() {
+        let content = "# Architecture Decision Records\n\n## ADR-001: A\n\n**Date:** 2026\n**Plan:** p\n\n### Context\n\nx\n\n### Decision\n\ny\n\n**Status:** Accepted\n";
+        let result = validate_permanent_log(content);
+        assert!(result.is_success(), "{:?}", result.errors);
+    }
+
     #[test]
     fn permanent_log_missing_context_heading_fails() {
         let content = "# Architecture Decision Records\n\n## ADR-001: A\n\n**Date:** 2026\n**Plan:** p\n**Status:** Accepted\n\n### Decision\n\ny\n";
@@ -530,4 +962,187 @@ mod tests {
         let result = validate_permanent_log(content);
         assert!(result.is_success(), "errors: {:?}", result.errors);
     }
+
+    fn adr_entry(number: u32, status: &str, decision: &str) -> String {
+        format!(
+            "## ADR-{number:03}: Decision {number}\n\n**Date:** 2026\n**Plan:** p\n**Status:** {status}\n\n### Context\n\nx\n\n### Decision\n\n{decision}\n\n"
+        )
+    }
+
+    fn permanent_log(entries: &[String]) -> String {
+        format!("# Architecture Decision Records\n\n{}", entries.concat())
+    }
+
+    #[test]
+    fn permanent_log_supersession_mentioning_predecessor_has_no_warnings() {
+        let content = permanent_log(&[
+            adr_entry(1, "Superseded by ADR-002", "a"),
+            adr_entry(2, "Accepted", "Replaces ADR-001."),
+        ]);
+        let result = validate_permanent_log(&content);
+        assert!(result.is_success(), "errors: {:?}", result.errors);
+        assert!(
+            result.warnings.is_empty(),
+            "warnings: {:?}",
+            result.warnings
+        );
+    }
+
+    #[test]
+    fn permanent_log_dangling_supersession_fails() {
+        let content = permanent_log(&[adr_entry(1, "Superseded by ADR-004", "a")]);
+        let result = validate_permanent_log(&content);
+        assert!(result.errors.iter().any(|e| matches!(
+            e,
+            DecisionLogError::PermanentLogDanglingSupersession { number: 1, by: 4 }
+        )));
+    }
+
+    #[test]
+    fn permanent_log_self_supersession_fails() {
+        let content = permanent_log(&[adr_entry(1, "Superseded by ADR-001", "a")]);
+        let result = validate_permanent_log(&content);
+        assert!(result.errors.iter().any(|e| matches!(
+            e,
+            DecisionLogError::PermanentLogSelfSupersession { number: 1 }
+        )));
+        assert!(
+            !result
+                .errors
+                .iter()
+                .any(|e| matches!(e, DecisionLogError::PermanentLogSupersessionCycle { .. }))
+        );
+    }
+
+    #[test]
+    fn permanent_log_backward_supersession_fails() {
+        let content = permanent_log(&[
+            adr_entry(1, "Accepted", "Replaces ADR-002."),
+            adr_entry(2, "Superseded by ADR-001", "b"),
+        ]);
+        let result = validate_permanent_log(&content);
+        assert!(result.errors.iter().any(|e| matches!(
+            e,
+            DecisionLogError::PermanentLogBackwardSupersession { number: 2, by: 1 }
+        )));
+    }
+
+    #[test]
+    fn permanent_log_supersession_cycle_fails_once() {
+        let content = permanent_log(&[
+            adr_entry(1, "Superseded by ADR-002", "Replaces ADR-002."),
+            adr_entry(2, "Superseded by ADR-001", "Replaces ADR-001."),
+        ]);
+        let result = validate_permanent_log(&content);
+        let cycles: Vec<String> = result
+            .errors
+            .iter()
+            .filter(|e| matches!(e, DecisionLogError::PermanentLogSupersessionCycle { .. }))
+            .map(|e| e.to_string())
+            .collect();
+        assert_eq!(
+            cycles,
+            vec!["Supersession cycle: ADR-001 -> ADR-002 -> ADR-001"]
+        );
+    }
+
+    #[test]
+    fn permanent_log_superseding_adr_not_mentioning_predecessor_warns() {
+        let content = permanent_log(&[
+            adr_entry(1, "Superseded by ADR-002", "a"),
+            adr_entry(2, "Accepted", "b"),
+        ]);
+        let result = validate_permanent_log(&content);
+        assert!(result.is_success(), "errors: {:?}", result.errors);
+        assert_eq!(
+            result.warnings,
+            vec![DecisionLogWarning::PermanentLogSupersessionNotMentioned { number: 1, by: 2 }]
+        );
+    }
+
+    const PLAN_LOG: &str = "# Decision Log: add-auth\n\nDate: 2026-01-10\n\n## Design Decisions\n\n### [1] Use sessions\n\n- **Decision:** Server-side sessions.\n- **Rationale:** Revocation.\n- **Promotes to ADR:** yes\n\n### [2] Cookie name\n\n- **Decision:** sid.\n- **Promotes to ADR:** yes\n";
+
+    fn cross_check_log(plan: &str) -> String {
+        format!(
+            "# Architecture Decision Records\n\n## ADR-001: Use sessions\n\n**Date:** 2026\n**Plan:** {plan}\n**Status:** Accepted\n\n### Context\n\nx\n\n### Decision\n\ny\n"
+        )
+    }
+
+    #[test]
+    fn cross_check_reports_unknown_plan() {
+        let tmp = TempDir::new().unwrap();
+
+        let result = cross_check_plans(tmp.path(), &cross_check_log("add-auth"));
+
+        assert!(result.is_success(), "{:?}", result.errors);
+        assert_eq!(
+            result.warnings,
+            vec![DecisionLogWarning::PermanentLogUnknownPlan {
+                number: 1,
+                plan: "add-auth".to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn cross_check_matches_titles_ignoring_case_and_whitespace() {
+        let tmp = TempDir::new().unwrap();
+        let plan_dir = tmp.path().join("_plans/add-auth");
+        fs::create_dir_all(&plan_dir).unwrap();
+        fs::write(
+            plan_dir.join(DECISION_LOG_FILE),
+            PLAN_LOG.replace("Use sessions", "use SESSIONS"),
+        )
+        .unwrap();
+        let log = cross_check_log("add-auth").replace("Use sessions", "Use Sessions");
+
+        let result = cross_check_plans(tmp.path(), &log);
+
+        assert!(result.is_success(), "{:?}", result.errors);
+    }
+
+    #[test]
+    fn cross_check_resolves_suffixed_archive_and_reports_unpromoted_decisions() {
+        let tmp = TempDir::new().unwrap();
+        let archive = tmp.path().join("_recorded/2026-01-10-add-auth-2");
+        fs::create_dir_all(&archive).unwrap();
+        fs::write(
+            archive.join(record::ARCHIVE_MANIFEST_FILE),
+            "# Recorded Plan: add-auth\n\n* Recorded: 2026-01-10\n\n## Features\n\n",
+        )
+        .unwrap();
+        fs::write(archive.join(DECISION_LOG_FILE), PLAN_LOG).unwrap();
+
+        let result = cross_check_plans(tmp.path(), &cross_check_log("add-auth"));
+
+        assert_eq!(
+            result.errors,
+            vec![DecisionLogError::RecordedDecisionMissingAdr {
+                plan: "add-auth".to_string(),
+                title: "Cookie name".to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn cross_check_reports_adr_not_marked_in_active_plan() {
+        let tmp = TempDir::new().unwrap();
+        let plan_dir = tmp.path().join("_plans/add-auth");
+        fs::create_dir_all(&plan_dir).unwrap();
+        fs::write(
+            plan_dir.join(DECISION_LOG_FILE),
+            PLAN_LOG.replace("yes", "no"),
+        )
+        .unwrap();
+
+        let result = cross_check_plans(tmp.path(), &cross_check_log("add-auth"));
+
+        assert_eq!(
+            result.errors,
+            vec![DecisionLogError::PermanentLogUnpromotedAdr {
+                number: 1,
+                plan: "add-auth".to_string()
+            }]
+        );
+    }
 }
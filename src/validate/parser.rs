@@ -1,3 +1,5 @@
+use std::ops::Range;
+
 use pulldown_cmark::{Event, HeadingLevel, Parser, Tag, TagEnd};
 
 use crate::validate::report::{ValidationError, ValidationWarning};
@@ -15,12 +17,27 @@ pub struct FeatureSpec {
     pub has_background: bool,
     pub has_scenarios_section: bool,
     pub scenarios: Vec<Scenario>,
+    /// Byte range of the `# Feature:` heading line
+    pub heading_span: Option<Range<usize>>,
+    /// Byte range between the feature heading and the next heading
+    pub description_span: Option<Range<usize>>,
+    /// Byte range from the `## Background` heading up to the next heading
+    pub background_span: Option<Range<usize>>,
+    pub background_facts: Vec<BackgroundFact>,
+}
+
+#[derive(Debug)]
+pub struct BackgroundFact {
+    pub text: String,
+    pub span: Range<usize>,
 }
 
 #[derive(Debug)]
 pub struct Scenario {
     pub name: String,
     pub steps: Vec<Step>,
+    /// Byte range from the scenario heading up to the next heading (or end of document)
+    pub span: Range<usize>,
 }
 
 #[derive(Debug)]
@@ -50,6 +67,12 @@ enum ParseState {
     InEmphasis,
 }
 
+#[derive(Debug, Clone, Copy)]
+enum OpenSection {
+    Description(usize),
+    Background(usize),
+}
+
 #[derive(Default)]
 struct ParseContext {
     state: ParseState,
@@ -57,28 +80,49 @@ struct ParseContext {
     current_step_kind: Option<StepKind>,
     current_step_text: String,
     heading_text: String,
+    heading_start: usize,
+    heading_end: usize,
+    open_section: Option<OpenSection>,
+    list_depth: usize,
     description_buffer: String,
     in_list_item: bool,
     warnings: Vec<ValidationWarning>,
 }
 
 pub fn parse(content: &str) -> Result<ParseResult, ValidationError> {
-    let parser = Parser::new(content);
+    let parser = Parser::new(content).into_offset_iter();
     let mut spec = FeatureSpec::default();
     let mut ctx = ParseContext::default();
 
-    for event in parser {
+    for (event, range) in parser {
         match event {
             Event::Start(Tag::Heading { level, .. }) => {
-                handle_heading_start(&mut spec, &mut ctx, level);
+                handle_heading_start(&mut spec, &mut ctx, level, range);
             }
             Event::Text(text) => {
                 handle_text(&mut ctx, &text);
             }
+            Event::Code(code) => {
+                handle_heading_code(&mut ctx, &code);
+            }
             Event::End(TagEnd::Heading(_)) => {
                 handle_heading_end(&mut spec, &mut ctx);
             }
+            Event::Start(Tag::List(_)) => {
+                ctx.list_depth += 1;
+            }
+            Event::End(TagEnd::List(_)) => {
+                ctx.list_depth = ctx.list_depth.saturating_sub(1);
+            }
             Event::Start(Tag::Item) => {
+                if ctx.list_depth == 1
+                    && matches!(ctx.open_section, Some(OpenSection::Background(_)))
+                {
+                    spec.background_facts.push(BackgroundFact {
+                        text: bullet_text(&content[range.clone()]),
+                        span: range,
+                    });
+                }
                 handle_item_start(&mut ctx);
             }
             Event::End(TagEnd::Item) => {
@@ -101,9 +145,11 @@ pub fn parse(content: &str) -> Result<ParseResult, ValidationError> {
         }
     }
 
-    if let Some(scenario) = ctx.current_scenario {
+    if let Some(mut scenario) = ctx.current_scenario.take() {
+        scenario.span.end = content.len();
         spec.scenarios.push(scenario);
     }
+    close_section(&mut spec, &mut ctx, content.len());
 
     Ok(ParseResult {
         spec,
@@ -111,12 +157,59 @@ pub fn parse(content: &str) -> Result<ParseResult, ValidationError> {
     })
 }
 
-fn handle_heading_start(spec: &mut FeatureSpec, ctx: &mut ParseContext, level: HeadingLevel) {
-    if let Some(scenario) = ctx.current_scenario.take() {
+/// Top-level list items of a markdown fragment, read the same way as Background facts.
+pub fn bullet_items(content: &str) -> Vec<BackgroundFact> {
+    let mut items = Vec::new();
+    let mut depth = 0usize;
+
+    for (event, range) in Parser::new(content).into_offset_iter() {
+        match event {
+            Event::Start(Tag::List(_)) => depth += 1,
+            Event::End(TagEnd::List(_)) => depth = depth.saturating_sub(1),
+            Event::Start(Tag::Item) if depth == 1 => items.push(BackgroundFact {
+                text: bullet_text(&content[range.clone()]),
+                span: range,
+            }),
+            _ => {}
+        }
+    }
+
+    items
+}
+
+fn bullet_text(item: &str) -> String {
+    let trimmed = item.trim();
+    ["* ", "- ", "+ "]
+        .iter()
+        .find_map(|marker| trimmed.strip_prefix(marker))
+        .unwrap_or(trimmed)
+        .trim()
+        .to_string()
+}
+
+fn close_section(spec: &mut FeatureSpec, ctx: &mut ParseContext, offset: usize) {
+    match ctx.open_section.take() {
+        Some(OpenSection::Description(start)) => spec.description_span = Some(start..offset),
+        Some(OpenSection::Background(start)) => spec.background_span = Some(start..offset),
+        None => {}
+    }
+}
+
+fn handle_heading_start(
+    spec: &mut FeatureSpec,
+    ctx: &mut ParseContext,
+    level: HeadingLevel,
+    range: Range<usize>,
+) {
+    if let Some(mut scenario) = ctx.current_scenario.take() {
+        scenario.span.end = range.start;
         spec.scenarios.push(scenario);
     }
+    close_section(spec, ctx, range.start);
 
     ctx.heading_text.clear();
+    ctx.heading_start = range.start;
+    ctx.heading_end = range.end;
     ctx.state = match level {
         HeadingLevel::H1 => ParseState::InFeatureHeading,
         HeadingLevel::H2 => ParseState::InH2Heading,
@@ -143,6 +236,17 @@ fn handle_text(ctx: &mut ParseContext, text: &str) {
     }
 }
 
+fn handle_heading_code(ctx: &mut ParseContext, code: &str) {
+    if matches!(
+        ctx.state,
+        ParseState::InFeatureHeading | ParseState::InH2Heading | ParseState::InScenarioHeading
+    ) {
+        ctx.heading_text.push('`');
+        ctx.heading_text.push_str(code);
+        ctx.heading_text.push('`');
+    }
+}
+
 fn handle_emphasis_text(ctx: &mut ParseContext, text: &str) {
     let trimmed = text.trim();
 
@@ -189,11 +293,16 @@ fn handle_heading_end(spec: &mut FeatureSpec, ctx: &mut ParseContext) {
                 .strip_prefix("Feature:")
                 .or_else(|| trimmed.strip_prefix("Feature"))
                 .map(|s| s.trim().to_string());
+            spec.heading_span = Some(ctx.heading_start..ctx.heading_end);
+            ctx.open_section = Some(OpenSection::Description(ctx.heading_end));
             ctx.state = ParseState::AfterFeatureHeading;
         }
         ParseState::InH2Heading => {
             match trimmed {
-                "Background" => spec.has_background = true,
+                "Background" => {
+                    spec.has_background = true;
+                    ctx.open_section = Some(OpenSection::Background(ctx.heading_start));
+                }
                 "Scenarios" => spec.has_scenarios_section = true,
                 _ => {}
             }
@@ -207,6 +316,7 @@ fn handle_heading_end(spec: &mut FeatureSpec, ctx: &mut ParseContext) {
             ctx.current_scenario = Some(Scenario {
                 name: name.to_string(),
                 steps: Vec::new(),
+                span: ctx.heading_start..ctx.heading_start,
             });
             ctx.state = ParseState::InScenario;
         }
@@ -250,6 +360,49 @@ fn handle_paragraph_end(spec: &mut FeatureSpec, ctx: &mut ParseContext) {
 mod tests {
     use super::*;
 
+    #[test]
+    fn records_feature_section_spans_and_background_facts() {
+        let md = "# Feature: X\n\nDoes things.\n\n## Background\n\n* First fact\n* Second `code` fact\n\n## Scenarios\n";
+        let spec = parse(md).unwrap().spec;
+
+        assert_eq!(&md[spec.heading_span.unwrap()], "# Feature: X\n");
+        assert_eq!(&md[spec.description_span.unwrap()], "\nDoes things.\n\n");
+        assert_eq!(
+            &md[spec.background_span.unwrap()],
+            "## Background\n\n* First fact\n* Second `code` fact\n\n"
+        );
+        let facts: Vec<&str> = spec
+            .background_facts
+            .iter()
+            .map(|f| f.text.as_str())
+            .collect();
+        assert_eq!(facts, vec!["First fact", "Second `code` fact"]);
+        assert_eq!(&md[spec.background_facts[0].span.clone()], "* First fact\n");
+    }
+
+    #[test]
+    fn records_scenario_spans_up_to_next_heading() {
+        let md = "# Feature: X\n\n## Scenarios\n\n### Scenario: A\n\n* *GIVEN* a\n\n### Scenario: B\n\n* *GIVEN* b\n";
+        let result = parse(md).unwrap();
+        let scenarios = &result.spec.scenarios;
+
+        assert_eq!(
+            &md[scenarios[0].span.clone()],
+            "### Scenario: A\n\n* *GIVEN* a\n\n"
+        );
+        assert_eq!(
+            &md[scenarios[1].span.clone()],
+            "### Scenario: B\n\n* *GIVEN* b\n"
+        );
+    }
+
+    #[test]
+    fn keeps_inline_code_in_scenario_names() {
+        let md = "### Scenario: Run `speq record`\n\n* *GIVEN* a\n";
+        let result = parse(md).unwrap();
+        assert_eq!(result.spec.scenarios[0].name, "Run `speq record`");
+    }
+
     #[test]
     fn parses_feature_heading() {
         let md = "# Feature: User Login\n\nDescription here";
@@ -223,7 +223,9 @@ mod tests {
                         text: "the system SHALL respond".to_string(),
                     },
                 ],
+                span: 0..0,
             }],
+            ..Default::default()
         }
     }
 
@@ -1,7 +1,9 @@
 use assert_cmd::Command;
 use predicates::prelude::*;
 use serial_test::serial;
+use speq_skill::plan_status::{PlanStatus, set_status};
 use std::fs;
+use std::path::Path;
 use std::sync::OnceLock;
 use tempfile::TempDir;
 
@@ -105,6 +107,24 @@ fn ensure_model_cached() {
     });
 }
 
+/// Mark a plan approved and verified with a passing verification report so `speq record`
+/// accepts it.
+fn verify_plan(root: &Path, plan_name: &str) {
+    let plan_dir = root.join("specs/_plans").join(plan_name);
+    let plan_md = plan_dir.join("plan.md");
+    let content = fs::read_to_string(&plan_md).unwrap_or_else(|_| format!("# Plan: {plan_name}\n"));
+    fs::write(
+        &plan_md,
+        set_status(&content, PlanStatus::Verified, Some("Tester on 2026-05-02")),
+    )
+    .unwrap();
+    fs::write(
+        plan_dir.join("verification-report.md"),
+        format!("# Verification Report: {plan_name}\n\nResult: pass\n"),
+    )
+    .unwrap();
+}
+
 /// Resolve the system cache path as a `String` for passing via `SPEQ_CACHE_DIR`.
 fn system_cache_dir() -> String {
     speq_skill::search::get_cache_path()
@@ -463,6 +483,29 @@ mod search {
             .success();
     }
 
+    #[test]
+    #[serial]
+    fn decision_search_finds_adr() {
+        ensure_model_cached();
+        let tmp = setup_test_specs();
+        let cache_dir = system_cache_dir();
+        fs::write(
+            tmp.path().join("specs/decision-log.md"),
+            "# Architecture Decision Records\n\n## ADR-001: Store embeddings with postcard\n\n**Date:** 2026-01-01\n**Plan:** add-search\n**Status:** Accepted\n\n### Context\n\nThe search index must load quickly.\n\n### Decision\n\nSerialize the index with postcard.\n",
+        )
+        .unwrap();
+
+        cmd()
+            .current_dir(tmp.path())
+            .env("SPEQ_CACHE_DIR", &cache_dir)
+            .args(["decision-log", "search", "index serialization"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains(
+                "ADR-001: Store embeddings with postcard [Accepted]",
+            ));
+    }
+
     #[test]
     #[serial]
     fn search_reports_actionable_error_when_model_missing() {
@@ -561,6 +604,7 @@ A test feature.
         // Create _recorded directory
         fs::create_dir_all(specs.join("_recorded")).unwrap();
 
+        verify_plan(tmp.path(), "test-plan");
         cmd()
             .current_dir(tmp.path())
             .args(["record", "test-plan"])
@@ -604,6 +648,330 @@ A test feature.
         assert!(!specs.join("_plans/test-plan").exists());
     }
 
+    #[test]
+    fn record_refuses_plan_with_invalid_merged_spec() {
+        let tmp = setup_test_specs();
+        let specs = tmp.path().join("specs");
+        let original = fs::read_to_string(specs.join("cli/validate/spec.md")).unwrap();
+
+        let plan_dir = specs.join("_plans/bad-plan/cli/validate");
+        fs::create_dir_all(&plan_dir).unwrap();
+        fs::write(
+            plan_dir.join("spec.md"),
+            r#"<!-- DELTA:NEW -->
+### Scenario: Incomplete
+
+* *GIVEN* a setup
+<!-- /DELTA:NEW -->
+"#,
+        )
+        .unwrap();
+
+        verify_plan(tmp.path(), "bad-plan");
+        cmd()
+            .current_dir(tmp.path())
+            .args(["record", "bad-plan"])
+            .assert()
+            .code(1)
+            .stderr(predicate::str::contains("not recorded"))
+            .stderr(predicate::str::contains(
+                "_plans/bad-plan/cli/validate/spec.md",
+            ))
+            .stderr(predicate::str::contains(
+                "Scenario 'Incomplete' is missing a WHEN step",
+            ));
+
+        assert_eq!(
+            fs::read_to_string(specs.join("cli/validate/spec.md")).unwrap(),
+            original
+        );
+        assert!(specs.join("_plans/bad-plan").exists());
+    }
+
+    #[test]
+    fn record_applies_feature_operations() {
+        let tmp = setup_test_specs();
+        let specs = tmp.path().join("specs");
+
+        let plan_dir = specs.join("_plans/rename-validate");
+        fs::create_dir_all(&plan_dir).unwrap();
+        fs::write(
+            plan_dir.join("plan.md"),
+            "# Plan\n\n## Feature Operations\n\n* RENAME cli/validate -> cli/check\n* DELETE validation/document-structure\n",
+        )
+        .unwrap();
+
+        verify_plan(tmp.path(), "rename-validate");
+        cmd()
+            .current_dir(tmp.path())
+            .args(["record", "rename-validate"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("RENAME cli/validate -> cli/check"))
+            .stdout(predicate::str::contains(
+                "DELETE validation/document-structure",
+            ));
+
+        assert!(specs.join("cli/check/spec.md").exists());
+        assert!(!specs.join("cli/validate").exists());
+        assert!(!specs.join("validation").exists());
+    }
+
+    #[test]
+    fn record_refuses_operation_on_missing_feature() {
+        let tmp = setup_test_specs();
+        let specs = tmp.path().join("specs");
+
+        let plan_dir = specs.join("_plans/retire");
+        fs::create_dir_all(&plan_dir).unwrap();
+        fs::write(
+            plan_dir.join("plan.md"),
+            "# Plan\n\nStatus: verified\n\n## Feature Operations\n\n* DELETE cli/missing\n",
+        )
+        .unwrap();
+
+        verify_plan(tmp.path(), "retire");
+        cmd()
+            .current_dir(tmp.path())
+            .args(["record", "retire"])
+            .assert()
+            .code(1)
+            .stderr(predicate::str::contains(
+                "plan.md:8: DELETE cli/missing: no permanent spec at cli/missing/spec.md",
+            ));
+
+        assert!(plan_dir.exists());
+    }
+
+    #[test]
+    fn record_uses_date_flag_then_source_date_epoch() {
+        let tmp = setup_test_specs();
+        let specs = tmp.path().join("specs");
+        for plan in ["dated", "reproducible"] {
+            let plan_dir = specs.join("_plans").join(plan).join("cli").join(plan);
+            fs::create_dir_all(&plan_dir).unwrap();
+            fs::copy(specs.join("cli/validate/spec.md"), plan_dir.join("spec.md")).unwrap();
+        }
+
+        verify_plan(tmp.path(), "dated");
+        cmd()
+            .current_dir(tmp.path())
+            .env("SOURCE_DATE_EPOCH", "1700000000")
+            .args(["record", "dated", "--date", "2021-03-04"])
+            .assert()
+            .success();
+        verify_plan(tmp.path(), "reproducible");
+        cmd()
+            .current_dir(tmp.path())
+            .env("SOURCE_DATE_EPOCH", "1700000000")
+            .args(["record", "reproducible"])
+            .assert()
+            .success();
+
+        assert!(specs.join("_recorded/2021-03-04-dated").is_dir());
+        assert!(specs.join("_recorded/2023-11-14-reproducible").is_dir());
+    }
+
+    #[test]
+    fn record_selected_feature_leaves_rest_in_plan() {
+        let tmp = setup_test_specs();
+        let specs = tmp.path().join("specs");
+        let plan_dir = specs.join("_plans/staged");
+        for feature in ["cli/first", "cli/second"] {
+            fs::create_dir_all(plan_dir.join(feature)).unwrap();
+            fs::copy(
+                specs.join("cli/validate/spec.md"),
+                plan_dir.join(feature).join("spec.md"),
+            )
+            .unwrap();
+        }
+
+        verify_plan(tmp.path(), "staged");
+        cmd()
+            .current_dir(tmp.path())
+            .args(["record", "staged", "--feature", "cli/first"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains(
+                "Recorded part of plan 'staged' to specs/_recorded/",
+            ))
+            .stdout(predicate::str::contains("Left in specs/_plans/staged/:"))
+            .stdout(predicate::str::contains("  - cli/second"));
+
+        assert!(specs.join("cli/first/spec.md").exists());
+        assert!(!specs.join("cli/second").exists());
+        assert!(plan_dir.join("cli/second/spec.md").exists());
+    }
+
+    #[test]
+    fn record_revert_restores_specs_and_plan() {
+        let tmp = setup_test_specs();
+        let specs = tmp.path().join("specs");
+        let original = fs::read_to_string(specs.join("cli/validate/spec.md")).unwrap();
+
+        let plan_dir = specs.join("_plans/extend-validate/cli/validate");
+        fs::create_dir_all(&plan_dir).unwrap();
+        fs::write(
+            plan_dir.join("spec.md"),
+            r#"## Scenarios
+
+<!-- DELTA:NEW -->
+### Scenario: Strict mode
+
+* *GIVEN* strict mode
+* *WHEN* validation runs
+* *THEN* the system SHALL fail on warnings
+<!-- /DELTA:NEW -->
+"#,
+        )
+        .unwrap();
+
+        verify_plan(tmp.path(), "extend-validate");
+        cmd()
+            .current_dir(tmp.path())
+            .args(["record", "extend-validate"])
+            .assert()
+            .success();
+        let archive = fs::read_dir(specs.join("_recorded"))
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap()
+            .file_name()
+            .into_string()
+            .unwrap();
+
+        cmd()
+            .current_dir(tmp.path())
+            .args(["record", "revert", &archive])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains(
+                "restored plan 'extend-validate' to specs/_plans/",
+            ))
+            .stdout(predicate::str::contains("✓ cli/validate"));
+
+        assert_eq!(
+            fs::read_to_string(specs.join("cli/validate/spec.md")).unwrap(),
+            original
+        );
+        assert!(plan_dir.join("spec.md").exists());
+    }
+
+    #[test]
+    fn record_revert_unknown_archive_fails() {
+        let tmp = setup_test_specs();
+
+        cmd()
+            .current_dir(tmp.path())
+            .args(["record", "revert", "2024-01-01-missing"])
+            .assert()
+            .code(1)
+            .stderr(predicate::str::contains(
+                "Recorded plan not found: 2024-01-01-missing",
+            ));
+    }
+
+    #[test]
+    fn record_fails_when_changed_scenario_does_not_exist() {
+        let tmp = setup_test_specs();
+        let specs = tmp.path().join("specs");
+        let original = fs::read_to_string(specs.join("cli/validate/spec.md")).unwrap();
+
+        let plan_dir = specs.join("_plans/typo-plan/cli/validate");
+        fs::create_dir_all(&plan_dir).unwrap();
+        fs::write(
+            plan_dir.join("spec.md"),
+            r#"## Scenarios
+
+<!-- DELTA:CHANGED -->
+### Scenario: Basic tset
+
+* *GIVEN* a setup
+* *WHEN* an action occurs
+* *THEN* the system SHALL respond
+<!-- /DELTA:CHANGED -->
+"#,
+        )
+        .unwrap();
+
+        verify_plan(tmp.path(), "typo-plan");
+        cmd()
+            .current_dir(tmp.path())
+            .args(["record", "typo-plan"])
+            .assert()
+            .code(1)
+            .stderr(predicate::str::contains(
+                "_plans/typo-plan/cli/validate/spec.md: line 3",
+            ))
+            .stderr(predicate::str::contains("'Basic tset'"))
+            .stderr(predicate::str::contains("did you mean 'Basic test'?"));
+
+        assert_eq!(
+            fs::read_to_string(specs.join("cli/validate/spec.md")).unwrap(),
+            original
+        );
+        assert!(specs.join("_plans/typo-plan").exists());
+    }
+
+    #[test]
+    fn record_reports_conflict_with_change_recorded_after_snapshot() {
+        let tmp = setup_test_specs();
+        let specs = tmp.path().join("specs");
+
+        let plan_dir = specs.join("_plans/late-plan/cli/validate");
+        fs::create_dir_all(&plan_dir).unwrap();
+        fs::write(specs.join("_plans/late-plan/plan.md"), "# Plan\n").unwrap();
+        fs::write(
+            plan_dir.join("spec.md"),
+            r#"<!-- DELTA:CHANGED -->
+### Scenario: Basic test
+
+* *GIVEN* a setup
+* *WHEN* an action occurs
+* *THEN* the system SHALL respond quickly
+<!-- /DELTA:CHANGED -->
+"#,
+        )
+        .unwrap();
+
+        cmd()
+            .current_dir(tmp.path())
+            .args(["plan", "snapshot", "late-plan"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("cli/validate"));
+        assert!(plan_dir.join("base.md").exists());
+
+        // Another plan changes the same scenario before this one is recorded
+        let spec_path = specs.join("cli/validate/spec.md");
+        let current = fs::read_to_string(&spec_path)
+            .unwrap()
+            .replace("SHALL respond", "SHALL respond politely");
+        fs::write(&spec_path, &current).unwrap();
+
+        cmd()
+            .current_dir(tmp.path())
+            .args(["plan", "validate", "late-plan"])
+            .assert()
+            .stdout(predicate::str::contains(
+                "changed since the plan's base snapshot",
+            ));
+
+        verify_plan(tmp.path(), "late-plan");
+        cmd()
+            .current_dir(tmp.path())
+            .args(["record", "late-plan"])
+            .assert()
+            .code(1)
+            .stderr(predicate::str::contains("Scenario 'Basic test'"))
+            .stderr(predicate::str::contains("<<<<<<< current"))
+            .stderr(predicate::str::contains("SHALL respond politely"))
+            .stderr(predicate::str::contains(">>>>>>> plan"));
+
+        assert_eq!(fs::read_to_string(&spec_path).unwrap(), current);
+    }
+
     #[test]
     #[serial]
     fn record_rebuilds_search_index() {
@@ -674,6 +1042,7 @@ A new feature added via plan.
         fs::create_dir_all(specs.join("_recorded")).unwrap();
 
         // Record the plan - should rebuild index and show indexed count
+        verify_plan(tmp.path(), "test-plan");
         cmd()
             .current_dir(tmp.path())
             .env("SPEQ_CACHE_DIR", &cache_dir)
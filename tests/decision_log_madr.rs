@@ -0,0 +1,138 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use tempfile::TempDir;
+
+fn cmd() -> Command {
+    Command::new(assert_cmd::cargo::cargo_bin!("speq"))
+}
+
+const PERMANENT_LOG: &str = "# Architecture Decision Records
+
+## ADR-001: Use line scanning
+
+**Date:** 2026-01-10
+**Plan:** add-validation
+**Status:** Superseded by ADR-002
+
+### Context
+
+Flat markdown.
+
+### Decision
+
+Scan line by line.
+
+## ADR-002: Use pulldown-cmark
+
+**Date:** 2026-03-02
+**Plan:** change-parser
+**Status:** Accepted
+
+### Context
+
+Line scanning (ADR-001) missed nested lists.
+
+### Decision
+
+Parse with pulldown-cmark.
+";
+
+#[test]
+fn export_writes_one_file_per_adr() {
+    let tmp = TempDir::new().unwrap();
+    fs::create_dir_all(tmp.path().join("specs")).unwrap();
+    fs::write(tmp.path().join("specs/decision-log.md"), PERMANENT_LOG).unwrap();
+
+    cmd()
+        .current_dir(tmp.path())
+        .args(["decision-log", "export", "--format", "madr", "docs/adr"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Exported 2 ADR(s) to docs/adr"));
+
+    let superseded =
+        fs::read_to_string(tmp.path().join("docs/adr/0001-use-line-scanning.md")).unwrap();
+    assert!(superseded.contains(
+        "## Status\n\nSuperseded by [2. Use pulldown-cmark](0002-use-pulldown-cmark.md)\n"
+    ));
+    assert!(
+        tmp.path()
+            .join("docs/adr/0002-use-pulldown-cmark.md")
+            .exists()
+    );
+}
+
+#[test]
+fn export_then_import_restores_log() {
+    let tmp = TempDir::new().unwrap();
+    fs::create_dir_all(tmp.path().join("specs")).unwrap();
+    fs::write(tmp.path().join("specs/decision-log.md"), PERMANENT_LOG).unwrap();
+
+    cmd()
+        .current_dir(tmp.path())
+        .args(["decision-log", "export", "docs/adr"])
+        .assert()
+        .success();
+    fs::remove_file(tmp.path().join("specs/decision-log.md")).unwrap();
+
+    cmd()
+        .current_dir(tmp.path())
+        .args(["decision-log", "import", "docs/adr"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("✓ ADR-002: Use pulldown-cmark"));
+
+    let content = fs::read_to_string(tmp.path().join("specs/decision-log.md")).unwrap();
+    assert_eq!(content, PERMANENT_LOG);
+}
+
+#[test]
+fn import_adr_tools_directory() {
+    let tmp = TempDir::new().unwrap();
+    let adr_dir = tmp.path().join("doc/adr");
+    fs::create_dir_all(&adr_dir).unwrap();
+    fs::write(
+        adr_dir.join("0001-record-architecture-decisions.md"),
+        "# 1. Record architecture decisions\n\nDate: 2024-01-15\n\n## Status\n\nAccepted\n\n## Context\n\nWe need to record decisions.\n\n## Decision\n\nWe will use ADRs.\n\n## Consequences\n\nSee Michael Nygard's article.\n",
+    )
+    .unwrap();
+    fs::write(adr_dir.join("README.md"), "# Decisions\n").unwrap();
+
+    cmd()
+        .current_dir(tmp.path())
+        .args(["decision-log", "import", "doc/adr", "--plan", "legacy"])
+        .assert()
+        .success();
+
+    let content = fs::read_to_string(tmp.path().join("specs/decision-log.md")).unwrap();
+    assert!(content.contains(
+        "## ADR-001: Record architecture decisions\n\n**Date:** 2024-01-15\n**Plan:** legacy\n**Status:** Accepted\n"
+    ));
+    assert!(content.contains("### Consequences\n\nSee Michael Nygard's article.\n"));
+}
+
+#[test]
+fn import_over_existing_log_needs_force() {
+    let tmp = TempDir::new().unwrap();
+    fs::create_dir_all(tmp.path().join("specs")).unwrap();
+    fs::write(tmp.path().join("specs/decision-log.md"), PERMANENT_LOG).unwrap();
+    cmd()
+        .current_dir(tmp.path())
+        .args(["decision-log", "export", "docs/adr"])
+        .assert()
+        .success();
+
+    cmd()
+        .current_dir(tmp.path())
+        .args(["decision-log", "import", "docs/adr"])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("use --force"));
+
+    cmd()
+        .current_dir(tmp.path())
+        .args(["decision-log", "import", "docs/adr", "--force"])
+        .assert()
+        .success();
+}
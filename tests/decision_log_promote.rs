@@ -0,0 +1,77 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use tempfile::TempDir;
+
+fn cmd() -> Command {
+    Command::new(assert_cmd::cargo::cargo_bin!("speq"))
+}
+
+const PLAN_LOG: &str = "# Decision Log: add-auth\n\nDate: 2026-04-27\n\n## Design Decisions\n\n### [1] Use JWT for sessions\n\n- **Decision:** Issue signed JWTs.\n- **Alternatives:** Server-side sessions.\n- **Rationale:** Stateless services scale out.\n- **Promotes to ADR:** yes\n\n### [2] Name the cookie\n\n- **Decision:** Cookie name `sid`.\n- **Promotes to ADR:** no\n";
+
+fn setup_plan(tmp: &TempDir) {
+    let plan_dir = tmp.path().join("specs/_plans/add-auth");
+    fs::create_dir_all(&plan_dir).unwrap();
+    fs::write(plan_dir.join("decision-log.md"), PLAN_LOG).unwrap();
+}
+
+#[test]
+fn promote_appends_next_adr_that_validates() {
+    let tmp = TempDir::new().unwrap();
+    setup_plan(&tmp);
+
+    cmd()
+        .current_dir(tmp.path())
+        .args([
+            "decision-log",
+            "promote",
+            "add-auth",
+            "--date",
+            "2026-05-02",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("✓ ADR-001: Use JWT for sessions"));
+
+    let log = fs::read_to_string(tmp.path().join("specs/decision-log.md")).unwrap();
+    assert!(log.contains("**Date:** 2026-05-02\n**Plan:** add-auth\n**Status:** Accepted"));
+    assert!(!log.contains("Name the cookie"));
+
+    cmd()
+        .current_dir(tmp.path())
+        .args(["decision-log", "validate"])
+        .assert()
+        .success();
+}
+
+#[test]
+fn promote_twice_adds_nothing() {
+    let tmp = TempDir::new().unwrap();
+    setup_plan(&tmp);
+
+    for _ in 0..2 {
+        cmd()
+            .current_dir(tmp.path())
+            .args(["decision-log", "promote", "add-auth"])
+            .assert()
+            .success();
+    }
+
+    let log = fs::read_to_string(tmp.path().join("specs/decision-log.md")).unwrap();
+    assert_eq!(log.matches("## ADR-").count(), 1);
+}
+
+#[test]
+fn promote_without_plan_log_fails() {
+    let tmp = TempDir::new().unwrap();
+    fs::create_dir_all(tmp.path().join("specs/_plans/no-log")).unwrap();
+
+    cmd()
+        .current_dir(tmp.path())
+        .args(["decision-log", "promote", "no-log"])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains(
+            "Plan 'no-log' has no decision-log.md",
+        ));
+}
@@ -0,0 +1,143 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use tempfile::TempDir;
+
+fn cmd() -> Command {
+    Command::new(assert_cmd::cargo::cargo_bin!("speq"))
+}
+
+const PERMANENT_LOG: &str = "# Architecture Decision Records
+
+## ADR-001: Use line scanning
+
+**Date:** 2026-01-10
+**Plan:** add-validation
+**Status:** Superseded by ADR-002
+
+### Context
+
+Flat markdown.
+
+### Decision
+
+Scan line by line.
+
+## ADR-002: Use pulldown-cmark
+
+**Date:** 2026-03-02
+**Plan:** change-parser
+**Status:** Accepted
+
+### Context
+
+Line scanning (ADR-001) missed nested lists.
+
+### Decision
+
+Parse with pulldown-cmark.
+";
+
+fn setup_log() -> TempDir {
+    let tmp = TempDir::new().unwrap();
+    fs::create_dir_all(tmp.path().join("specs")).unwrap();
+    fs::write(tmp.path().join("specs/decision-log.md"), PERMANENT_LOG).unwrap();
+    tmp
+}
+
+#[test]
+fn list_prints_every_adr() {
+    let tmp = setup_log();
+
+    cmd()
+        .current_dir(tmp.path())
+        .args(["decision-log", "list"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "ADR-001  2026-01-10  Superseded by ADR-002  Use line scanning",
+        ))
+        .stdout(predicate::str::contains(
+            "ADR-002  2026-03-02  Accepted  Use pulldown-cmark",
+        ));
+}
+
+#[test]
+fn list_filters_by_status() {
+    let tmp = setup_log();
+
+    cmd()
+        .current_dir(tmp.path())
+        .args(["decision-log", "list", "--status", "accepted"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("ADR-002"))
+        .stdout(predicate::str::contains("ADR-001").not());
+}
+
+#[test]
+fn show_prints_adr() {
+    let tmp = setup_log();
+
+    cmd()
+        .current_dir(tmp.path())
+        .args(["decision-log", "show", "2"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(
+            "## ADR-002: Use pulldown-cmark\n\n**Date:** 2026-03-02\n",
+        ))
+        .stdout(predicate::str::contains(
+            "### Decision\n\nParse with pulldown-cmark.\n",
+        ));
+}
+
+#[test]
+fn show_superseded_adr_prints_chain_to_current_decision() {
+    let tmp = setup_log();
+
+    cmd()
+        .current_dir(tmp.path())
+        .args(["decision-log", "show", "ADR-001"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Supersession: ADR-001 → ADR-002 (Accepted)",
+        ));
+}
+
+#[test]
+fn show_current_adr_prints_no_chain() {
+    let tmp = setup_log();
+
+    cmd()
+        .current_dir(tmp.path())
+        .args(["decision-log", "show", "ADR-002"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Supersession").not());
+}
+
+#[test]
+fn show_unknown_adr_fails() {
+    let tmp = setup_log();
+
+    cmd()
+        .current_dir(tmp.path())
+        .args(["decision-log", "show", "ADR-009"])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("ADR-009 not found"));
+}
+
+#[test]
+fn list_without_log_fails() {
+    let tmp = TempDir::new().unwrap();
+
+    cmd()
+        .current_dir(tmp.path())
+        .args(["decision-log", "list"])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("decision-log.md not found"));
+}
@@ -0,0 +1,127 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use tempfile::TempDir;
+
+fn cmd() -> Command {
+    Command::new(assert_cmd::cargo::cargo_bin!("speq"))
+}
+
+/// Two branches each added an ADR-002.
+const MERGED_LOG: &str = "# Architecture Decision Records
+
+## ADR-001: Use line scanning
+
+**Date:** 2026-01-10
+**Plan:** add-validation
+**Status:** Superseded by ADR-002
+
+### Context
+
+Flat markdown.
+
+### Decision
+
+Scan line by line.
+
+## ADR-002: Cache embeddings
+
+**Date:** 2026-02-01
+**Plan:** add-search
+**Status:** Accepted
+
+### Context
+
+Indexing is slow.
+
+### Decision
+
+Cache embeddings on disk.
+
+## ADR-002: Use pulldown-cmark
+
+**Date:** 2026-03-02
+**Plan:** change-parser
+**Status:** Accepted
+
+### Context
+
+Line scanning (ADR-001) missed nested lists.
+
+### Decision
+
+Parse with pulldown-cmark.
+";
+
+fn setup_log(content: &str) -> TempDir {
+    let tmp = TempDir::new().unwrap();
+    fs::create_dir_all(tmp.path().join("specs")).unwrap();
+    fs::write(tmp.path().join("specs/decision-log.md"), content).unwrap();
+    tmp
+}
+
+#[test]
+fn renumber_dry_run_previews_without_writing() {
+    let tmp = setup_log(MERGED_LOG);
+
+    cmd()
+        .current_dir(tmp.path())
+        .args(["decision-log", "renumber", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "-## ADR-002: Use pulldown-cmark\n+## ADR-003: Use pulldown-cmark",
+        ))
+        .stdout(predicate::str::contains(
+            "WARN: ADR-002 numbered more than one ADR",
+        ))
+        .stdout(predicate::str::contains("Dry run"));
+
+    let content = fs::read_to_string(tmp.path().join("specs/decision-log.md")).unwrap();
+    assert_eq!(content, MERGED_LOG);
+}
+
+#[test]
+fn renumber_rewrites_log_so_it_validates() {
+    let tmp = setup_log(MERGED_LOG);
+
+    cmd()
+        .current_dir(tmp.path())
+        .args(["decision-log", "renumber"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Rewrote 1 line(s)"));
+
+    let content = fs::read_to_string(tmp.path().join("specs/decision-log.md")).unwrap();
+    assert!(content.contains("## ADR-003: Use pulldown-cmark"));
+
+    cmd()
+        .current_dir(tmp.path())
+        .args(["decision-log", "validate"])
+        .assert()
+        .success();
+}
+
+#[test]
+fn renumber_sequential_log_changes_nothing() {
+    let tmp = setup_log(&MERGED_LOG.replacen("## ADR-002: Use", "## ADR-003: Use", 1));
+
+    cmd()
+        .current_dir(tmp.path())
+        .args(["decision-log", "renumber"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("already sequential"));
+}
+
+#[test]
+fn renumber_without_log_fails() {
+    let tmp = TempDir::new().unwrap();
+
+    cmd()
+        .current_dir(tmp.path())
+        .args(["decision-log", "renumber"])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("decision-log.md not found"));
+}
@@ -120,3 +120,49 @@ fn optional_sections_absent_passes() {
         .assert()
         .success();
 }
+
+#[test]
+fn dangling_supersession_fails() {
+    let tmp = TempDir::new().unwrap();
+    setup_fixture(&tmp, "dangling-supersession");
+
+    cmd()
+        .current_dir(tmp.path())
+        .args(["decision-log", "validate"])
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains(
+            "ADR-001 is superseded by ADR-003, which does not exist",
+        ));
+}
+
+#[test]
+fn cross_check_reports_orphans_both_ways() {
+    let tmp = TempDir::new().unwrap();
+    setup_fixture(&tmp, "cross-check");
+
+    cmd()
+        .current_dir(tmp.path())
+        .args(["decision-log", "validate", "--cross-check"])
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains(
+            "WARN: ADR-002 names plan 'change-parser', which is not in specs/_recorded/ or specs/_plans/",
+        ))
+        .stdout(predicate::str::contains(
+            "Decision 'Rotate session ids on login' in recorded plan 'add-auth' is marked 'Promotes to ADR: yes' but has no ADR",
+        ))
+        .stdout(predicate::str::contains("ADR-001").not());
+}
+
+#[test]
+fn validate_without_cross_check_ignores_plans() {
+    let tmp = TempDir::new().unwrap();
+    setup_fixture(&tmp, "cross-check");
+
+    cmd()
+        .current_dir(tmp.path())
+        .args(["decision-log", "validate"])
+        .assert()
+        .success();
+}
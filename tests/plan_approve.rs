@@ -0,0 +1,266 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use std::path::PathBuf;
+use tempfile::TempDir;
+
+const DELTA: &str = "# Feature: Login
+
+Users log in.
+
+## Background
+
+* Users have accounts
+
+## Scenarios
+
+<!-- DELTA:NEW -->
+### Scenario: Login works
+
+* *GIVEN* a user
+* *WHEN* they log in
+* *THEN* the system SHALL admit them
+<!-- /DELTA:NEW -->
+";
+
+fn cmd() -> Command {
+    Command::new(assert_cmd::cargo::cargo_bin!("speq"))
+}
+
+/// A draft plan adding `auth/login`; returns the plan directory
+fn setup_plan(tmp: &TempDir) -> PathBuf {
+    let plan_dir = tmp.path().join("specs/_plans/add-login");
+    fs::create_dir_all(plan_dir.join("auth/login")).unwrap();
+    fs::write(
+        plan_dir.join("plan.md"),
+        "# Plan: add-login\n\n## Tasks\n\n- [x] Add login\n",
+    )
+    .unwrap();
+    fs::write(plan_dir.join("auth/login/spec.md"), DELTA).unwrap();
+    plan_dir
+}
+
+#[test]
+fn approve_marks_draft_plan_approved() {
+    let tmp = TempDir::new().unwrap();
+    let plan_dir = setup_plan(&tmp);
+
+    cmd()
+        .current_dir(tmp.path())
+        .env("SOURCE_DATE_EPOCH", "1700000000")
+        .args(["plan", "approve", "add-login", "--by", "Dana"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Approved plan 'add-login' (by Dana).",
+        ));
+
+    assert_eq!(
+        fs::read_to_string(plan_dir.join("plan.md")).unwrap(),
+        "# Plan: add-login\n\nStatus: approved\nApproved by: Dana on 2023-11-14\n\n## Tasks\n\n- [x] Add login\n"
+    );
+
+    cmd()
+        .current_dir(tmp.path())
+        .args(["plan", "show", "add-login"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Status: approved (approved by Dana on 2023-11-14)",
+        ));
+
+    cmd()
+        .current_dir(tmp.path())
+        .args(["plan", "approve", "add-login", "--by", "Dana"])
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains(
+            "Plan 'add-login' is approved; only a draft plan can be approved",
+        ));
+}
+
+#[test]
+fn approve_refuses_invalid_plan() {
+    let tmp = TempDir::new().unwrap();
+    let plan_dir = setup_plan(&tmp);
+    fs::write(plan_dir.join("plan.md"), "# Plan\n\nStatus: done\n").unwrap();
+
+    cmd()
+        .current_dir(tmp.path())
+        .args(["plan", "approve", "add-login", "--by", "Dana"])
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains("fails validation"));
+
+    cmd()
+        .current_dir(tmp.path())
+        .args(["plan", "validate", "add-login"])
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains("plan.md:3: Invalid status 'done'"));
+}
+
+#[test]
+fn record_refuses_unverified_plan_unless_forced() {
+    let tmp = TempDir::new().unwrap();
+    let plan_dir = setup_plan(&tmp);
+
+    cmd()
+        .current_dir(tmp.path())
+        .args(["record", "add-login"])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains(
+            "Plan 'add-login' is draft; record needs a verified plan; use --force to record anyway",
+        ));
+    assert!(!tmp.path().join("specs/auth/login/spec.md").exists());
+
+    cmd()
+        .current_dir(tmp.path())
+        .args(["record", "add-login", "--force"])
+        .assert()
+        .success();
+    assert!(tmp.path().join("specs/auth/login/spec.md").exists());
+    assert!(!plan_dir.exists());
+}
+
+#[test]
+fn record_needs_passing_verification_report() {
+    let tmp = TempDir::new().unwrap();
+    let plan_dir = setup_plan(&tmp);
+    fs::write(
+        plan_dir.join("plan.md"),
+        "# Plan: add-login\n\nStatus: verified\nApproved by: Dana on 2026-05-02\n\n## Tasks\n\n- [x] Add login\n",
+    )
+    .unwrap();
+
+    cmd()
+        .current_dir(tmp.path())
+        .args(["record", "add-login"])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains(
+            "Plan 'add-login' has no verification-report.md",
+        ));
+
+    fs::write(
+        plan_dir.join("verification-report.md"),
+        "# Verification Report: add-login\n\nResult: fail\n",
+    )
+    .unwrap();
+    cmd()
+        .current_dir(tmp.path())
+        .args(["record", "add-login"])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains(
+            "verification-report.md: verification failed (Result: fail)",
+        ));
+
+    fs::write(
+        plan_dir.join("verification-report.md"),
+        "# Verification Report: add-login\n\nResult: pass\n",
+    )
+    .unwrap();
+    cmd()
+        .current_dir(tmp.path())
+        .args(["record", "add-login"])
+        .assert()
+        .success();
+}
+
+#[test]
+fn record_needs_approval() {
+    let tmp = TempDir::new().unwrap();
+    let plan_dir = setup_plan(&tmp);
+    fs::write(
+        plan_dir.join("plan.md"),
+        "# Plan: add-login\n\nStatus: verified\n\n## Tasks\n\n- [x] Add login\n",
+    )
+    .unwrap();
+    fs::write(
+        plan_dir.join("verification-report.md"),
+        "# Verification Report: add-login\n\nResult: pass\n",
+    )
+    .unwrap();
+
+    cmd()
+        .current_dir(tmp.path())
+        .args(["record", "add-login"])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains(
+            "Plan 'add-login' has no 'Approved by:' line; approve it with `speq plan approve`",
+        ));
+    assert!(!tmp.path().join("specs/auth/login/spec.md").exists());
+}
+
+#[test]
+fn status_moves_approved_plan_to_verified() {
+    let tmp = TempDir::new().unwrap();
+    let plan_dir = setup_plan(&tmp);
+    cmd()
+        .current_dir(tmp.path())
+        .args(["plan", "approve", "add-login", "--by", "Dana"])
+        .assert()
+        .success();
+
+    cmd()
+        .current_dir(tmp.path())
+        .args(["plan", "status", "add-login", "verified"])
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains(
+            "Plan 'add-login' is approved and cannot become verified",
+        ));
+    cmd()
+        .current_dir(tmp.path())
+        .args(["plan", "status", "add-login", "implemented"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Plan 'add-login' is now implemented.",
+        ));
+    cmd()
+        .current_dir(tmp.path())
+        .args(["plan", "status", "add-login", "verified"])
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains(
+            "Plan 'add-login' has no verification-report.md",
+        ));
+
+    fs::write(
+        plan_dir.join("verification-report.md"),
+        "# Verification Report: add-login\n\nResult: pass\n",
+    )
+    .unwrap();
+    cmd()
+        .current_dir(tmp.path())
+        .args(["plan", "status", "add-login", "verified"])
+        .assert()
+        .success();
+    cmd()
+        .current_dir(tmp.path())
+        .args(["record", "add-login"])
+        .assert()
+        .success();
+}
+
+#[test]
+fn status_rejects_draft_and_approved_targets() {
+    let tmp = TempDir::new().unwrap();
+    setup_plan(&tmp);
+
+    cmd()
+        .current_dir(tmp.path())
+        .args(["plan", "status", "add-login", "implemented"])
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains("Plan 'add-login' is draft"));
+    cmd()
+        .current_dir(tmp.path())
+        .args(["plan", "status", "add-login", "approved"])
+        .assert()
+        .failure();
+}
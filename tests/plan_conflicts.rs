@@ -0,0 +1,77 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use tempfile::TempDir;
+
+fn cmd() -> Command {
+    Command::new(assert_cmd::cargo::cargo_bin!("speq"))
+}
+
+fn write_plan(tmp: &TempDir, plan_name: &str, delta: &str) {
+    let plan_dir = tmp.path().join("specs/_plans").join(plan_name);
+    fs::create_dir_all(plan_dir.join("auth/login")).unwrap();
+    fs::write(plan_dir.join("plan.md"), format!("# Plan: {plan_name}\n")).unwrap();
+    fs::write(plan_dir.join("auth/login/spec.md"), delta).unwrap();
+}
+
+fn setup_overlapping_plans() -> TempDir {
+    let tmp = TempDir::new().unwrap();
+    write_plan(
+        &tmp,
+        "change-login",
+        "# Feature: Login\n\nUsers log in.\n\n## Background\n\n* Users have accounts\n\n## Scenarios\n\n<!-- DELTA:NEW -->\n### Scenario: Login\n\n* *GIVEN* a user\n* *WHEN* they log in\n* *THEN* the system SHALL admit them\n<!-- /DELTA:NEW -->\n",
+    );
+    write_plan(
+        &tmp,
+        "fix-login",
+        "# Feature: Login\n\nUsers log in.\n\n## Background\n\n* Users have accounts\n\n## Scenarios\n\n<!-- DELTA:NEW -->\n### Scenario: Login\n\n* *GIVEN* a user\n* *WHEN* they log in twice\n* *THEN* the system SHALL admit them once\n<!-- /DELTA:NEW -->\n",
+    );
+    tmp
+}
+
+#[test]
+fn reports_plans_touching_the_same_scenario() {
+    let tmp = setup_overlapping_plans();
+
+    cmd()
+        .current_dir(tmp.path())
+        .args(["plan", "conflicts"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Found 1 overlap(s) between active plans:\n  auth/login: Scenario 'Login'\n    change-login: NEW\n    fix-login: NEW\nRecording both plans of a pair may conflict or overwrite, depending on base snapshots.\n",
+        ));
+}
+
+#[test]
+fn reports_no_overlap() {
+    let tmp = TempDir::new().unwrap();
+    write_plan(
+        &tmp,
+        "change-login",
+        "<!-- DELTA:NEW -->\n### Scenario: Login\n<!-- /DELTA:NEW -->\n",
+    );
+
+    cmd()
+        .current_dir(tmp.path())
+        .args(["plan", "conflicts"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "No active plans touch the same scenarios.",
+        ));
+}
+
+#[test]
+fn validate_warns_about_overlapping_plan() {
+    let tmp = setup_overlapping_plans();
+
+    cmd()
+        .current_dir(tmp.path())
+        .args(["plan", "validate", "fix-login"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "WARN: auth/login: scenario 'Login' (NEW here) is also NEW by plan 'change-login'; recording both may conflict or overwrite, depending on base snapshots",
+        ));
+}
@@ -0,0 +1,122 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use tempfile::TempDir;
+
+const LOGIN_SPEC: &str = "# Feature: Login
+
+Users log in.
+
+## Background
+
+* Users have accounts
+
+## Scenarios
+
+### Scenario: Login works
+
+* *GIVEN* a user
+* *WHEN* they log in
+* *THEN* the system SHALL admit them
+";
+
+fn cmd() -> Command {
+    Command::new(assert_cmd::cargo::cargo_bin!("speq"))
+}
+
+fn setup_specs() -> TempDir {
+    let tmp = TempDir::new().unwrap();
+    let feature_dir = tmp.path().join("specs/auth/login");
+    fs::create_dir_all(&feature_dir).unwrap();
+    fs::write(feature_dir.join("spec.md"), LOGIN_SPEC).unwrap();
+    tmp
+}
+
+#[test]
+fn scaffolds_plan_files() {
+    let tmp = setup_specs();
+
+    cmd()
+        .current_dir(tmp.path())
+        .args(["plan", "new", "change-login"])
+        .args([
+            "--feature",
+            "auth/login",
+            "--feature",
+            "billing/invoice-export",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Created plan 'change-login'"))
+        .stdout(predicate::str::contains(
+            "auth/login/spec.md (seeded from permanent spec)",
+        ))
+        .stdout(predicate::str::contains(
+            "billing/invoice-export/spec.md (new feature)",
+        ));
+
+    let plan_dir = tmp.path().join("specs/_plans/change-login");
+    for file in [
+        "plan.md",
+        "decision-log.md",
+        "auth/login/spec.md",
+        "auth/login/base.md",
+        "billing/invoice-export/spec.md",
+    ] {
+        assert!(plan_dir.join(file).exists(), "missing {file}");
+    }
+}
+
+#[test]
+fn seeded_plan_validates_without_empty_block_warning() {
+    let tmp = setup_specs();
+    cmd()
+        .current_dir(tmp.path())
+        .args(["plan", "new", "change-login", "--feature", "auth/login"])
+        .assert()
+        .success();
+
+    cmd()
+        .current_dir(tmp.path())
+        .args(["plan", "validate", "change-login"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Empty DELTA").not());
+}
+
+#[test]
+fn rejects_name_without_known_verb() {
+    let tmp = setup_specs();
+
+    cmd()
+        .current_dir(tmp.path())
+        .args(["plan", "new", "update-login"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            "must start with one of add, change, remove, refactor, fix",
+        ));
+
+    assert!(!tmp.path().join("specs/_plans/update-login").exists());
+}
+
+#[test]
+fn refuses_existing_plan() {
+    let tmp = setup_specs();
+    fs::create_dir_all(tmp.path().join("specs/_plans/change-login")).unwrap();
+
+    cmd()
+        .current_dir(tmp.path())
+        .args(["plan", "new", "change-login", "--feature", "auth/login"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            "Plan already exists: change-login",
+        ));
+
+    assert!(
+        !tmp.path()
+            .join("specs/_plans/change-login/plan.md")
+            .exists()
+    );
+}
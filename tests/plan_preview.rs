@@ -0,0 +1,109 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use tempfile::TempDir;
+
+const LOGIN_SPEC: &str = "# Feature: Login
+
+Users log in.
+
+## Background
+
+* Users have accounts
+
+## Scenarios
+
+### Scenario: Login works
+
+* *GIVEN* a user
+* *WHEN* they log in
+* *THEN* the system SHALL admit them
+
+### Scenario: Guest login
+
+* *GIVEN* a guest
+* *WHEN* they log in
+* *THEN* the system SHALL admit them read-only
+";
+
+const DELTA: &str = "# Feature: Login
+
+## Scenarios
+
+<!-- DELTA:NEW -->
+### Scenario: Lockout
+
+* *GIVEN* five failed attempts
+* *WHEN* the user logs in
+* *THEN* the system SHALL refuse
+<!-- /DELTA:NEW -->
+
+<!-- DELTA:REMOVED -->
+### Scenario: Guest login
+<!-- /DELTA:REMOVED -->
+";
+
+fn cmd() -> Command {
+    Command::new(assert_cmd::cargo::cargo_bin!("speq"))
+}
+
+fn setup_plan() -> TempDir {
+    let tmp = TempDir::new().unwrap();
+    let specs = tmp.path().join("specs");
+    fs::create_dir_all(specs.join("auth/login")).unwrap();
+    fs::write(specs.join("auth/login/spec.md"), LOGIN_SPEC).unwrap();
+    let delta_dir = specs.join("_plans/change-login/auth/login");
+    fs::create_dir_all(&delta_dir).unwrap();
+    fs::write(delta_dir.join("spec.md"), DELTA).unwrap();
+    fs::write(specs.join("_plans/change-login/plan.md"), "# Plan\n").unwrap();
+    tmp
+}
+
+#[test]
+fn prints_merged_spec_without_touching_files() {
+    let tmp = setup_plan();
+
+    cmd()
+        .current_dir(tmp.path())
+        .args(["plan", "preview", "change-login", "auth/login"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("# Feature: Login\n"))
+        .stdout(predicate::str::contains("### Scenario: Lockout"))
+        .stdout(predicate::str::contains("Guest login").not())
+        .stdout(predicate::str::contains("DELTA").not());
+
+    assert_eq!(
+        fs::read_to_string(tmp.path().join("specs/auth/login/spec.md")).unwrap(),
+        LOGIN_SPEC
+    );
+}
+
+#[test]
+fn annotate_marks_changed_sections() {
+    let tmp = setup_plan();
+
+    cmd()
+        .current_dir(tmp.path())
+        .args(["plan", "preview", "change-login", "auth/login", "--annotate"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "<!-- PREVIEW:REMOVED Scenario: Guest login -->\n\n<!-- PREVIEW:NEW -->\n### Scenario: Lockout\n",
+        ))
+        .stdout(predicate::str::contains("<!-- PREVIEW:CHANGED -->").not());
+}
+
+#[test]
+fn fails_for_feature_without_delta() {
+    let tmp = setup_plan();
+
+    cmd()
+        .current_dir(tmp.path())
+        .args(["plan", "preview", "change-login", "auth/signup"])
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains(
+            "Plan 'change-login' has no delta spec for auth/signup",
+        ));
+}
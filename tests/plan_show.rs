@@ -0,0 +1,101 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use tempfile::TempDir;
+
+fn cmd() -> Command {
+    Command::new(assert_cmd::cargo::cargo_bin!("speq"))
+}
+
+/// A plan changing `auth/login` and adding `billing/invoices`
+fn setup_plan() -> TempDir {
+    let tmp = TempDir::new().unwrap();
+    let specs = tmp.path().join("specs");
+    fs::create_dir_all(specs.join("auth/login")).unwrap();
+    fs::write(
+        specs.join("auth/login/spec.md"),
+        "# Feature: Login\n\n## Scenarios\n\n### Scenario: Login works\n",
+    )
+    .unwrap();
+
+    let plan_dir = specs.join("_plans/change-login");
+    fs::create_dir_all(plan_dir.join("auth/login")).unwrap();
+    fs::create_dir_all(plan_dir.join("billing/invoices")).unwrap();
+    fs::write(
+        plan_dir.join("plan.md"),
+        "# Plan: change-login\n\n## Tasks\n\n- [x] 1.1 Count failures\n- [ ] 1.2 Export invoices\n",
+    )
+    .unwrap();
+    fs::write(
+        plan_dir.join("auth/login/spec.md"),
+        "<!-- DELTA:CHANGED -->\n### Scenario: Login works\n<!-- /DELTA:CHANGED -->\n",
+    )
+    .unwrap();
+    fs::write(
+        plan_dir.join("billing/invoices/spec.md"),
+        "<!-- DELTA:NEW -->\n### Scenario: Export invoices\n<!-- /DELTA:NEW -->\n",
+    )
+    .unwrap();
+    fs::write(
+        plan_dir.join("decision-log.md"),
+        "# Decision Log: change-login\n\n## Design Decisions\n\n### [1] Stream exports\n\n- **Promotes to ADR:** yes\n",
+    )
+    .unwrap();
+    tmp
+}
+
+#[test]
+fn shows_plan_summary_as_text() {
+    let tmp = setup_plan();
+
+    cmd()
+        .current_dir(tmp.path())
+        .args(["plan", "show", "change-login"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Tasks: 1 of 2 done"))
+        .stdout(predicate::str::contains(
+            "auth/login (changed)\n  CHANGED: Login works\n",
+        ))
+        .stdout(predicate::str::contains(
+            "billing/invoices (new feature)\n  NEW: Export invoices\n",
+        ))
+        .stdout(predicate::str::contains(
+            "ADR promotions:\n  Stream exports\n",
+        ));
+}
+
+#[test]
+fn shows_plan_summary_as_json() {
+    let tmp = setup_plan();
+
+    let output = cmd()
+        .current_dir(tmp.path())
+        .args(["plan", "show", "change-login", "--format", "json"])
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    let summary: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(summary["plan"], "change-login");
+    assert_eq!(summary["features"][0]["feature"], "auth/login");
+    assert_eq!(summary["features"][0]["is_new"], false);
+    assert_eq!(summary["features"][0]["changed"][0], "Login works");
+    assert_eq!(summary["features"][1]["is_new"], true);
+    assert_eq!(summary["features"][1]["new"][0], "Export invoices");
+    assert_eq!(summary["adr_promotions"][0], "Stream exports");
+    assert_eq!(summary["tasks"]["done"], 1);
+    assert_eq!(summary["tasks"]["total"], 2);
+}
+
+#[test]
+fn show_fails_for_missing_plan() {
+    let tmp = TempDir::new().unwrap();
+
+    cmd()
+        .current_dir(tmp.path())
+        .args(["plan", "show", "missing"])
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains("Plan not found: missing"));
+}
@@ -127,6 +127,26 @@ mod delta_markers {
             .stdout(predicate::str::contains("DELTA:REMOVED not closed"));
     }
 
+    #[test]
+    fn fails_with_unrecognized_delta_marker() {
+        let tmp = TempDir::new().unwrap();
+        setup_fixture(&tmp, "valid-delta");
+        let spec = tmp
+            .path()
+            .join("specs/_plans/valid-delta/test/feature/spec.md");
+        let content = fs::read_to_string(&spec).unwrap();
+        fs::write(&spec, format!("{content}\n<!-- DELTA:BACKGROUND -->\n")).unwrap();
+
+        cmd()
+            .current_dir(tmp.path())
+            .args(["plan", "validate", "valid-delta"])
+            .assert()
+            .code(1)
+            .stdout(predicate::str::contains(
+                "Unrecognized delta marker: <!-- DELTA:BACKGROUND -->",
+            ));
+    }
+
     #[test]
     fn reports_line_number_for_unclosed_marker() {
         let tmp = TempDir::new().unwrap();
@@ -248,6 +268,166 @@ mod spec_validation {
     }
 }
 
+mod delta_targets {
+    use super::*;
+
+    #[test]
+    fn fails_when_removed_scenario_does_not_exist() {
+        let tmp = TempDir::new().unwrap();
+        setup_fixture(&tmp, "missing-target");
+        let feature_dir = tmp.path().join("specs/test/feature");
+        fs::create_dir_all(&feature_dir).unwrap();
+        fs::write(
+            feature_dir.join("spec.md"),
+            "# Feature: Test\n\nDescription.\n\n## Background\n\n* Context.\n\n## Scenarios\n\n### Scenario: Guest login\n\n* *GIVEN* a guest\n* *WHEN* they log in\n* *THEN* access SHALL be granted\n",
+        )
+        .unwrap();
+
+        cmd()
+            .current_dir(tmp.path())
+            .args(["plan", "validate", "missing-target"])
+            .assert()
+            .code(1)
+            .stdout(predicate::str::contains(
+                "test/feature/spec.md:11: DELTA:REMOVED targets scenario 'Guest logins'",
+            ))
+            .stdout(predicate::str::contains("did you mean 'Guest login'?"));
+    }
+
+    #[test]
+    fn fails_when_new_scenario_title_already_exists() {
+        let tmp = TempDir::new().unwrap();
+        setup_fixture(&tmp, "duplicate-new");
+        let feature_dir = tmp.path().join("specs/test/feature");
+        fs::create_dir_all(&feature_dir).unwrap();
+        fs::write(
+            feature_dir.join("spec.md"),
+            "# Feature: Test\n\nDescription.\n\n## Background\n\n* Context.\n\n## Scenarios\n\n### Scenario: Guest login\n\n* *GIVEN* a guest\n* *WHEN* they log in\n* *THEN* access SHALL be granted\n",
+        )
+        .unwrap();
+
+        cmd()
+            .current_dir(tmp.path())
+            .args(["plan", "validate", "duplicate-new"])
+            .assert()
+            .code(1)
+            .stdout(predicate::str::contains(
+                "test/feature/spec.md:11: DELTA:NEW adds scenario 'Guest login' which already exists",
+            ))
+            .stdout(predicate::str::contains("use DELTA:CHANGED"));
+    }
+
+    #[test]
+    fn fails_when_changed_background_fact_does_not_exist() {
+        let tmp = TempDir::new().unwrap();
+        setup_fixture(&tmp, "background-delta");
+        let feature_dir = tmp.path().join("specs/test/feature");
+        fs::create_dir_all(&feature_dir).unwrap();
+        fs::write(
+            feature_dir.join("spec.md"),
+            "# Feature: Test\n\nDescription.\n\n## Background\n\n* Guests can log in.\n\n## Scenarios\n\n### Scenario: Guest login\n\n* *GIVEN* a guest\n* *WHEN* they log in\n* *THEN* access SHALL be granted\n",
+        )
+        .unwrap();
+
+        cmd()
+            .current_dir(tmp.path())
+            .args(["plan", "validate", "background-delta"])
+            .assert()
+            .code(1)
+            .stdout(predicate::str::contains(
+                "test/feature/spec.md:7: DELTA:BACKGROUND:CHANGED targets background fact 'Guests may log in.'",
+            ));
+    }
+
+    #[test]
+    fn passes_when_changed_background_fact_exists() {
+        let tmp = TempDir::new().unwrap();
+        setup_fixture(&tmp, "background-delta");
+        let feature_dir = tmp.path().join("specs/test/feature");
+        fs::create_dir_all(&feature_dir).unwrap();
+        fs::write(
+            feature_dir.join("spec.md"),
+            "# Feature: Test\n\nDescription.\n\n## Background\n\n* Guests may log in.\n\n## Scenarios\n\n### Scenario: Guest login\n\n* *GIVEN* a guest\n* *WHEN* they log in\n* *THEN* access SHALL be granted\n",
+        )
+        .unwrap();
+
+        cmd()
+            .current_dir(tmp.path())
+            .args(["plan", "validate", "background-delta"])
+            .assert()
+            .success();
+    }
+
+    #[test]
+    fn fails_when_new_scenario_anchor_does_not_exist() {
+        let tmp = TempDir::new().unwrap();
+        setup_fixture(&tmp, "missing-anchor");
+        let feature_dir = tmp.path().join("specs/test/feature");
+        fs::create_dir_all(&feature_dir).unwrap();
+        fs::write(
+            feature_dir.join("spec.md"),
+            "# Feature: Test\n\nDescription.\n\n## Background\n\n* Context.\n\n## Scenarios\n\n### Scenario: Guest login\n\n* *GIVEN* a guest\n* *WHEN* they log in\n* *THEN* access SHALL be granted\n",
+        )
+        .unwrap();
+
+        cmd()
+            .current_dir(tmp.path())
+            .args(["plan", "validate", "missing-anchor"])
+            .assert()
+            .code(1)
+            .stdout(predicate::str::contains(
+                "test/feature/spec.md:11: DELTA:NEW is placed after scenario 'Guest logins'",
+            ))
+            .stdout(predicate::str::contains("did you mean 'Guest login'?"));
+    }
+
+    #[test]
+    fn passes_when_target_scenario_exists() {
+        let tmp = TempDir::new().unwrap();
+        setup_fixture(&tmp, "missing-target");
+        let feature_dir = tmp.path().join("specs/test/feature");
+        fs::create_dir_all(&feature_dir).unwrap();
+        fs::write(
+            feature_dir.join("spec.md"),
+            "# Feature: Test\n\nDescription.\n\n## Background\n\n* Context.\n\n## Scenarios\n\n### Scenario: Guest logins\n\n* *GIVEN* a guest\n* *WHEN* they log in\n* *THEN* access SHALL be granted\n",
+        )
+        .unwrap();
+
+        cmd()
+            .current_dir(tmp.path())
+            .args(["plan", "validate", "missing-target"])
+            .assert()
+            .success();
+    }
+}
+
+mod feature_operations {
+    use super::*;
+
+    #[test]
+    fn fails_when_moved_feature_does_not_exist() {
+        let tmp = TempDir::new().unwrap();
+        setup_fixture(&tmp, "valid-plan");
+        fs::write(
+            tmp.path().join("specs/_plans/valid-plan/plan.md"),
+            "# Plan\n\n## Feature Operations\n\n* MOVE auth/login -> identity/login\n* ARCHIVE auth/old\n",
+        )
+        .unwrap();
+
+        cmd()
+            .current_dir(tmp.path())
+            .args(["plan", "validate", "valid-plan"])
+            .assert()
+            .code(1)
+            .stdout(predicate::str::contains(
+                "plan.md:5: MOVE auth/login -> identity/login: no permanent spec at auth/login/spec.md",
+            ))
+            .stdout(predicate::str::contains(
+                "plan.md:6: unknown feature operation 'ARCHIVE'",
+            ));
+    }
+}
+
 mod decision_log {
     use super::*;
 
@@ -314,6 +494,25 @@ mod decision_log {
             .stdout(predicate::str::contains("maybe"));
     }
 
+    #[test]
+    fn plan_decision_log_entry_errors_carry_line_numbers() {
+        let tmp = TempDir::new().unwrap();
+        setup_fixture(&tmp, "decisions-bad-entry");
+
+        cmd()
+            .current_dir(tmp.path())
+            .args(["plan", "validate", "decisions-bad-entry"])
+            .assert()
+            .code(1)
+            .stdout(predicate::str::contains(
+                "decision-log.md:7: Interview question has no '**A:**' answer",
+            ))
+            .stdout(predicate::str::contains(
+                "decision-log.md:11: Design decision 'Use a line-oriented state machine' is missing '- **Alternatives:**'",
+            ))
+            .stdout(predicate::str::contains("missing '- **Rationale:**'"));
+    }
+
     #[test]
     fn plan_decision_log_bad_h1_fails() {
         let tmp = TempDir::new().unwrap();
@@ -327,3 +526,116 @@ mod decision_log {
             .stdout(predicate::str::contains("wrong-name"));
     }
 }
+
+mod tasks {
+    use super::*;
+
+    const PLAN_MD: &str = "# Plan: valid-delta
+
+## Tasks
+
+- [x] 1.1 Write the new scenario [test/feature: New one]
+- [ ] 1.2 Tune the hot path [expert]
+- [ ] 1.3 Cover the old one [test/feature: Old one]
+- Forgot the checkbox
+";
+
+    fn setup_tasks(tmp: &TempDir) {
+        setup_fixture(tmp, "valid-delta");
+        fs::write(tmp.path().join("specs/_plans/valid-delta/plan.md"), PLAN_MD).unwrap();
+    }
+
+    fn setup_well_formed_tasks(tmp: &TempDir) {
+        setup_fixture(tmp, "valid-delta");
+        fs::write(
+            tmp.path().join("specs/_plans/valid-delta/plan.md"),
+            PLAN_MD.replace("- Forgot the checkbox\n", ""),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn validate_reports_bad_tasks_and_links() {
+        let tmp = TempDir::new().unwrap();
+        setup_tasks(&tmp);
+
+        cmd()
+            .current_dir(tmp.path())
+            .args(["plan", "validate", "valid-delta"])
+            .assert()
+            .code(1)
+            .stdout(predicate::str::contains(
+                "plan.md:7: task links to unknown scenario 'Old one' in test/feature",
+            ))
+            .stdout(predicate::str::contains(
+                "plan.md:8: task needs a '[ ]' or '[x]' checkbox",
+            ))
+            .stdout(predicate::str::contains("plan.md:5").not());
+    }
+
+    #[test]
+    fn validate_warns_without_tasks_section() {
+        let tmp = TempDir::new().unwrap();
+        setup_fixture(&tmp, "valid-delta");
+
+        cmd()
+            .current_dir(tmp.path())
+            .args(["plan", "validate", "valid-delta"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains(
+                "WARN: plan.md:1: no '## Tasks' section",
+            ));
+    }
+
+    #[test]
+    fn tasks_lists_state_tags_and_links() {
+        let tmp = TempDir::new().unwrap();
+        setup_well_formed_tasks(&tmp);
+
+        cmd()
+            .current_dir(tmp.path())
+            .args(["plan", "tasks", "valid-delta"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains(
+                "Tasks for plan 'valid-delta': 1 of 3 done",
+            ))
+            .stdout(predicate::str::contains(
+                "  [x] 1.1 Write the new scenario\n        implements test/feature: New one\n",
+            ))
+            .stdout(predicate::str::contains(
+                "  [ ] 1.2 Tune the hot path [expert]\n",
+            ));
+    }
+
+    #[test]
+    fn tasks_pending_hides_done_tasks() {
+        let tmp = TempDir::new().unwrap();
+        setup_well_formed_tasks(&tmp);
+
+        cmd()
+            .current_dir(tmp.path())
+            .args(["plan", "tasks", "valid-delta", "--pending"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("1.1").not())
+            .stdout(predicate::str::contains("1.3 Cover the old one"));
+    }
+
+    #[test]
+    fn tasks_reports_malformed_lines() {
+        let tmp = TempDir::new().unwrap();
+        setup_tasks(&tmp);
+
+        cmd()
+            .current_dir(tmp.path())
+            .args(["plan", "tasks", "valid-delta"])
+            .assert()
+            .code(1)
+            .stdout(predicate::str::contains("1.3 Cover the old one"))
+            .stdout(predicate::str::contains(
+                "Malformed task lines (not listed):\n  ERROR: plan.md:8: task needs a '[ ]' or '[x]' checkbox",
+            ));
+    }
+}